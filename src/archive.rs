@@ -0,0 +1,88 @@
+//! Parsing the OFX files bundled in a ZIP archive, as some banks deliver multiple statements.
+
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use zip::ZipArchive;
+
+use crate::{Ofx, ParserOptions, Result, load::is_ofx, warning::Warning};
+
+/// The outcome of parsing each OFX file in an archive, keyed by its path within the archive.
+pub type ArchiveResults = BTreeMap<String, Result<(Ofx, Vec<Warning>)>>;
+
+/// Parses every `.ofx` and `.qfx` file in a ZIP archive, keyed by its path within the archive.
+///
+/// An entry that fails to extract or parse does not stop the others.
+///
+/// # Errors
+///
+/// Returns an error if `bytes` are not a readable ZIP archive.
+pub fn parse_zip(bytes: &[u8]) -> Result<ArchiveResults> {
+    parse_zip_with_options(bytes, &ParserOptions::default())
+}
+
+/// Like [`parse_zip`], parsing each file with `options`.
+///
+/// # Errors
+///
+/// Returns an error if `bytes` are not a readable ZIP archive.
+pub fn parse_zip_with_options(bytes: &[u8], options: &ParserOptions) -> Result<ArchiveResults> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let mut results = BTreeMap::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if !file.is_file() || !is_ofx(Path::new(file.name())) {
+            continue;
+        }
+        let name = file.name().to_owned();
+        // Bound extraction by the size limit, since the declared size cannot be trusted
+        let limit = u64::try_from(options.max_file_size)
+            .unwrap_or(u64::MAX)
+            .saturating_add(1);
+        let mut contents = Vec::new();
+        let result = (&mut file)
+            .take(limit)
+            .read_to_end(&mut contents)
+            .map_err(Into::into)
+            .and_then(|_| options.parse_bytes(&contents));
+        results.insert(name, result);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    use super::*;
+
+    #[test]
+    fn parses_each_statement() {
+        let ofx = std::fs::read("tests/files/simple.ofx").unwrap();
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let files: [(&str, &[u8]); 3] = [
+            ("2024/april.ofx", &ofx),
+            ("2024/broken.QFX", b"not ofx"),
+            ("readme.txt", b"hello"),
+        ];
+        for (name, contents) in files {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let results = parse_zip(&bytes).unwrap();
+        let names: Vec<_> = results.keys().map(String::as_str).collect();
+        assert_eq!(names, ["2024/april.ofx", "2024/broken.QFX"]);
+        assert!(results["2024/april.ofx"].is_ok());
+        assert!(results["2024/broken.QFX"].is_err());
+        assert!(parse_zip(b"not a zip").is_err());
+    }
+}
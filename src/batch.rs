@@ -0,0 +1,44 @@
+//! Parsing many files at once.
+
+use std::path::Path;
+
+use rayon::prelude::*;
+
+use crate::ParserOptions;
+pub use crate::load::FileResult;
+
+/// Parses every file in `paths` in parallel, returning one result per file in the same order.
+///
+/// A file that fails to read or parse does not stop the others. Files are decoded as described
+/// in [`crate::decode`].
+#[must_use]
+pub fn parse_many<P>(paths: &[P], options: &ParserOptions) -> Vec<FileResult>
+where
+    P: AsRef<Path> + Sync,
+{
+    paths
+        .par_iter()
+        .map(|path| {
+            let path = path.as_ref();
+            FileResult {
+                path: path.to_owned(),
+                result: options.parse_file(path),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_files_independently() {
+        let paths = ["tests/files/simple.ofx", "tests/files/does-not-exist.ofx"];
+        let results = parse_many(&paths, &ParserOptions::default());
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].path, Path::new(paths[0]));
+        assert!(results[0].result.is_ok());
+        assert!(results[1].result.is_err());
+    }
+}
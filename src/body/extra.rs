@@ -0,0 +1,363 @@
+//! Capturing tags that are not (yet) modeled by the typed structs.
+
+use std::collections::BTreeMap;
+
+use serde::{
+    Deserialize, Deserializer,
+    de::{self, Visitor, value::Error as ValueError},
+    forward_to_deserialize_any,
+};
+
+use super::{
+    Account, Balance, BankAccount, BankMessageResponse, BankTransactionList, Body,
+    CreditCardMessageResponse, CreditCardStatementResponse, CreditCardStatementTransactionResponse,
+    Currency, FinancialInstitution, NamedBalance, PendingTransaction, PendingTransactionList,
+    RewardInfo, SignOnMessageResponse, SignOnResponse, StatementResponse,
+    StatementTransactionResponse, Status, Transaction, TransactionResponse, Wrapped,
+    investment::{
+        BankTransaction, BuyDebt, BuyMutualFund, BuyOption, BuyOther, BuyStock, ClosureOption,
+        Income, InvestmentAccount, InvestmentBalance, InvestmentBuy, InvestmentMessageResponse,
+        InvestmentSell, InvestmentStatementResponse, InvestmentStatementTransactionResponse,
+        InvestmentTransaction, InvestmentTransactionList, JournalFund, JournalSecurity,
+        MarginInterest, OpenOrderList, OrderInfo, PositionInfo, PositionList, Reinvest,
+        ReturnOfCapital, SecurityId, SecurityInfo, SecurityList, SecurityListMessageResponse,
+        SellDebt, SellMutualFund, SellOption, SellOther, SellStock, Split, TransactionInfo,
+        Transfer,
+    },
+};
+use crate::{tree::Element, warning::Warning};
+
+/// Records the field names a derived `Deserialize` impl asks for.
+struct FieldNames(Option<&'static [&'static str]>);
+
+impl<'de> Deserializer<'de> for &mut FieldNames {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("expected a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0 = Some(fields);
+        Err(de::Error::custom("field names captured"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+fn field_names<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut names = FieldNames(None);
+    // Always errors; we only care about the field names recorded along the way.
+    let _ = T::deserialize(&mut names);
+    names.0.unwrap_or_default()
+}
+
+/// Collects the children of `element` that `T` does not deserialize.
+pub(crate) fn unknown_children<'de, T: Deserialize<'de>>(
+    element: &Element,
+) -> BTreeMap<String, String> {
+    let known = field_names::<T>();
+    element
+        .children
+        .iter()
+        .filter(|child| !known.contains(&child.name.as_str()))
+        .map(|child| (child.name.clone(), child.value()))
+        .collect()
+}
+
+/// The child tags modeled for an aggregate, or `None` if its children are not checked.
+fn modeled_children(name: &str) -> Option<&'static [&'static str]> {
+    let names = match name {
+        "OFX" => field_names::<Body>(),
+        "SIGNONMSGSRSV1" => field_names::<SignOnMessageResponse>(),
+        "SONRS" => field_names::<SignOnResponse>(),
+        "FI" => field_names::<FinancialInstitution>(),
+        "STATUS" => field_names::<Status>(),
+        "CREDITCARDMSGSRSV1" => field_names::<CreditCardMessageResponse>(),
+        "CCSTMTTRNRS" => field_names::<CreditCardStatementTransactionResponse>(),
+        "CCSTMTRS" => field_names::<CreditCardStatementResponse>(),
+        "CCACCTFROM" => field_names::<Account>(),
+        "REWARDINFO" => field_names::<RewardInfo>(),
+        "BANKMSGSRSV1" => field_names::<BankMessageResponse>(),
+        "STMTTRNRS" => field_names::<StatementTransactionResponse>(),
+        "STMTRS" => field_names::<StatementResponse>(),
+        "BANKACCTFROM" => field_names::<BankAccount>(),
+        "BANKTRANLIST" => field_names::<BankTransactionList>(),
+        "STMTTRN" => field_names::<Transaction>(),
+        "BANKTRANLISTP" => field_names::<PendingTransactionList>(),
+        "STMTTRNP" => field_names::<PendingTransaction>(),
+        "CURRENCY" => field_names::<Currency>(),
+        "LEDGERBAL" | "AVAILBAL" => field_names::<Balance>(),
+        "BAL" => field_names::<NamedBalance>(),
+        "INVSTMTMSGSRSV1" => field_names::<InvestmentMessageResponse>(),
+        "INVSTMTTRNRS" => field_names::<InvestmentStatementTransactionResponse>(),
+        "INVSTMTRS" => field_names::<InvestmentStatementResponse>(),
+        "INVACCTFROM" => field_names::<InvestmentAccount>(),
+        "INVTRANLIST" => field_names::<InvestmentTransactionList>(),
+        "INVTRAN" => field_names::<TransactionInfo>(),
+        "SECID" => field_names::<SecurityId>(),
+        "INVBUY" => field_names::<InvestmentBuy>(),
+        "INVSELL" => field_names::<InvestmentSell>(),
+        "BUYDEBT" => field_names::<BuyDebt>(),
+        "BUYMF" => field_names::<BuyMutualFund>(),
+        "BUYOPT" => field_names::<BuyOption>(),
+        "BUYOTHER" => field_names::<BuyOther>(),
+        "BUYSTOCK" => field_names::<BuyStock>(),
+        "CLOSUREOPT" => field_names::<ClosureOption>(),
+        "INCOME" => field_names::<Income>(),
+        "INVBANKTRAN" => field_names::<BankTransaction>(),
+        "JRNLFUND" => field_names::<JournalFund>(),
+        "JRNLSEC" => field_names::<JournalSecurity>(),
+        "MARGININTEREST" => field_names::<MarginInterest>(),
+        "REINVEST" => field_names::<Reinvest>(),
+        "RETOFCAP" => field_names::<ReturnOfCapital>(),
+        "SELLDEBT" => field_names::<SellDebt>(),
+        "SELLMF" => field_names::<SellMutualFund>(),
+        "SELLOPT" => field_names::<SellOption>(),
+        "SELLOTHER" => field_names::<SellOther>(),
+        "SELLSTOCK" => field_names::<SellStock>(),
+        "SPLIT" => field_names::<Split>(),
+        "TRANSFER" => field_names::<Transfer>(),
+        "INVPOSLIST" => field_names::<PositionList>(),
+        "INVBAL" => field_names::<InvestmentBalance>(),
+        "INVPOS" => field_names::<PositionInfo>(),
+        "INVOOLIST" => field_names::<OpenOrderList>(),
+        "OO" => field_names::<OrderInfo>(),
+        "SECLISTMSGSRSV1" => field_names::<SecurityListMessageResponse>(),
+        "SECLIST" => field_names::<SecurityList>(),
+        "SECINFO" => field_names::<SecurityInfo>(),
+        _ => return None,
+    };
+    Some(names)
+}
+
+fn warn_unknown_in<'a>(element: &'a Element, path: &mut Vec<&'a str>, warnings: &mut Vec<Warning>) {
+    path.push(&element.name);
+    let modeled = modeled_children(&element.name);
+    for child in &element.children {
+        if modeled.is_some_and(|names| !names.contains(&child.name.as_str())) {
+            let warning = Warning::UnknownTag {
+                name: child.name.clone(),
+                path: path.join(" > "),
+            };
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        } else if !child.children.is_empty() {
+            warn_unknown_in(child, path, warnings);
+        }
+    }
+    path.pop();
+}
+
+/// Warns once about each tag below `root` that was skipped because it is not modeled.
+///
+/// Only aggregates with their own types are checked. An unknown aggregate is reported without
+/// its children.
+pub(crate) fn warn_unknown(root: &Element, warnings: &mut Vec<Warning>) {
+    warn_unknown_in(root, &mut Vec::new(), warnings);
+}
+
+/// Populates `extra` maps from the element an aggregate was deserialized from.
+pub(crate) trait CaptureExtra {
+    fn capture_extra(&mut self, element: &Element);
+}
+
+impl CaptureExtra for Transaction {
+    fn capture_extra(&mut self, element: &Element) {
+        self.extra = unknown_children::<Self>(element);
+    }
+}
+
+impl CaptureExtra for BankTransactionList {
+    fn capture_extra(&mut self, element: &Element) {
+        for (transaction, child) in self
+            .transactions
+            .iter_mut()
+            .zip(element.children_named("STMTTRN"))
+        {
+            transaction.capture_extra(child);
+        }
+    }
+}
+
+/// Pending transactions keep no `extra` map, so there is nothing to capture.
+impl CaptureExtra for PendingTransactionList {
+    fn capture_extra(&mut self, _element: &Element) {}
+}
+
+impl CaptureExtra for SignOnResponse {
+    fn capture_extra(&mut self, element: &Element) {
+        self.extra = unknown_children::<Self>(element);
+    }
+}
+
+impl CaptureExtra for StatementResponse {
+    fn capture_extra(&mut self, element: &Element) {
+        self.extra = unknown_children::<Self>(element);
+        if let Some(list) = &mut self.bank_transactions
+            && let Some(child) = element.child("BANKTRANLIST")
+        {
+            list.capture_extra(child);
+        }
+    }
+}
+
+impl CaptureExtra for CreditCardStatementResponse {
+    fn capture_extra(&mut self, element: &Element) {
+        self.extra = unknown_children::<Self>(element);
+        if let Some(list) = &mut self.bank_transactions
+            && let Some(child) = element.child("BANKTRANLIST")
+        {
+            list.capture_extra(child);
+        }
+    }
+}
+
+impl CaptureExtra for InvestmentTransactionList {
+    fn capture_extra(&mut self, element: &Element) {
+        let bank_transactions =
+            self.transactions
+                .iter_mut()
+                .filter_map(|transaction| match transaction {
+                    InvestmentTransaction::BankTransaction(bank) => Some(&mut bank.transaction),
+                    _ => None,
+                });
+        let children = element
+            .children_named("INVBANKTRAN")
+            .filter_map(|child| child.child("STMTTRN"));
+        for (transaction, child) in bank_transactions.zip(children) {
+            transaction.capture_extra(child);
+        }
+    }
+}
+
+// Open orders, positions, and securities do not capture extra tags yet.
+impl CaptureExtra for OpenOrderList {
+    fn capture_extra(&mut self, _element: &Element) {}
+}
+
+impl CaptureExtra for PositionList {
+    fn capture_extra(&mut self, _element: &Element) {}
+}
+
+impl CaptureExtra for SecurityList {
+    fn capture_extra(&mut self, _element: &Element) {}
+}
+
+impl CaptureExtra for InvestmentStatementResponse {
+    fn capture_extra(&mut self, element: &Element) {
+        self.extra = unknown_children::<Self>(element);
+        if let Some(list) = &mut self.transactions
+            && let Some(child) = element.child("INVTRANLIST")
+        {
+            list.capture_extra(child);
+        }
+    }
+}
+
+impl<T: CaptureExtra + Wrapped> CaptureExtra for TransactionResponse<T> {
+    fn capture_extra(&mut self, element: &Element) {
+        if let Some(statement) = &mut self.statement
+            && let Some(child) = element.child(T::TAG)
+        {
+            statement.capture_extra(child);
+        }
+    }
+}
+
+impl CaptureExtra for Body {
+    fn capture_extra(&mut self, root: &Element) {
+        self.other = unknown_children::<Self>(root);
+        if let Some(sign_on) = &mut self.sign_on
+            && let Some(element) = root.descendant(&["SIGNONMSGSRSV1", "SONRS"])
+        {
+            sign_on.response.capture_extra(element);
+        }
+        if let Some(credit_card) = &mut self.credit_card
+            && let Some(element) = root.descendant(&["CREDITCARDMSGSRSV1", "CCSTMTTRNRS"])
+        {
+            credit_card.transaction_response.capture_extra(element);
+        }
+        if let Some(bank) = &mut self.bank
+            && let Some(element) = root.descendant(&["BANKMSGSRSV1", "STMTTRNRS"])
+        {
+            bank.transaction_response.capture_extra(element);
+        }
+        if let Some(investment) = &mut self.investment
+            && let Some(element) = root.descendant(&["INVSTMTMSGSRSV1", "INVSTMTTRNRS"])
+        {
+            investment.transaction_response.capture_extra(element);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_unknown_tags() {
+        let input = "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>USD<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1<NAME>A\
+            <EXTDNAME>A longer name</STMTTRN>\
+            </BANKTRANLIST><NOTICE>Hello</STMTRS></STMTTRNRS></BANKMSGSRSV1>\
+            <BILLPAYMSGSRSV1><PMTINQTRNRS><TRNUID>2</PMTINQTRNRS></BILLPAYMSGSRSV1></OFX>";
+        let body: Body = input.parse().unwrap();
+        assert_eq!(
+            body.other,
+            BTreeMap::from([(
+                "BILLPAYMSGSRSV1".to_owned(),
+                "<PMTINQTRNRS><TRNUID>2</TRNUID></PMTINQTRNRS>".to_owned()
+            )])
+        );
+        let statement = body.bank.unwrap().transaction_response.statement.unwrap();
+        assert_eq!(
+            statement.extra,
+            BTreeMap::from([("NOTICE".to_owned(), "Hello".to_owned())])
+        );
+        let transaction = &statement.bank_transactions.unwrap().transactions[0];
+        assert_eq!(
+            transaction.extra.get("EXTDNAME").map(String::as_str),
+            Some("A longer name")
+        );
+    }
+
+    #[test]
+    fn warns_about_unknown_tags() {
+        let root = Element::aggregate(
+            "OFX",
+            vec![Element::aggregate(
+                "SIGNONMSGSRSV1",
+                vec![Element::aggregate(
+                    "SONRS",
+                    vec![
+                        Element::leaf("LANGUAGE", "ENG"),
+                        Element::leaf("INTU.BID", "1234"),
+                    ],
+                )],
+            )],
+        );
+        let mut warnings = Vec::new();
+        warn_unknown(&root, &mut warnings);
+        warn_unknown(&root, &mut warnings);
+        assert_eq!(
+            warnings,
+            [Warning::UnknownTag {
+                name: "INTU.BID".into(),
+                path: "OFX > SIGNONMSGSRSV1 > SONRS".into(),
+            }]
+        );
+    }
+}
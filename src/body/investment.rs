@@ -0,0 +1,1886 @@
+//! Investment statements (1.6 spec, chapter 13).
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, IgnoredAny, IntoDeserializer, MapAccess, Visitor, value::StrDeserializer},
+};
+
+use super::{AccountId, Currency, NamedBalance, Transaction, TransactionResponse, Wrapped};
+use crate::{
+    datetime::{DateTime, deserialize_datetime, deserialize_optional_datetime},
+    decimal::Decimal,
+};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InvestmentMessageResponse {
+    #[serde(rename = "INVSTMTTRNRS")]
+    pub transaction_response: InvestmentStatementTransactionResponse,
+}
+
+pub type InvestmentStatementTransactionResponse = TransactionResponse<InvestmentStatementResponse>;
+
+impl Wrapped for InvestmentStatementResponse {
+    const TAG: &'static str = "INVSTMTRS";
+    const FIELDS: &'static [&'static str] = &["TRNUID", "STATUS", "CLTCOOKIE", Self::TAG];
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InvestmentStatementResponse {
+    #[serde(rename = "DTASOF")]
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub date_as_of: DateTime,
+    #[serde(rename = "CURDEF")]
+    pub currency: String,
+    #[serde(rename = "INVACCTFROM")]
+    pub account: InvestmentAccount,
+    #[serde(rename = "INVTRANLIST")]
+    pub transactions: Option<InvestmentTransactionList>,
+    #[serde(rename = "INVPOSLIST")]
+    pub positions: Option<PositionList>,
+    #[serde(rename = "INVBAL")]
+    pub balance: Option<InvestmentBalance>,
+    #[serde(rename = "INVOOLIST")]
+    pub open_orders: Option<OpenOrderList>,
+    #[serde(rename = "MKTGINFO")]
+    pub marketing_info: Option<String>,
+    /// Child tags not otherwise modeled, keyed by tag name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl InvestmentStatementResponse {
+    #[must_use]
+    pub fn new(
+        date_as_of: DateTime,
+        currency: impl Into<String>,
+        account: InvestmentAccount,
+    ) -> Self {
+        Self {
+            date_as_of,
+            currency: currency.into(),
+            account,
+            transactions: None,
+            positions: None,
+            balance: None,
+            open_orders: None,
+            marketing_info: None,
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+/// The cash balances of an investment account (1.6 spec, 13.9.2.7).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InvestmentBalance {
+    /// Cash available for investment, which may include money market funds.
+    #[serde(rename = "AVAILCASH")]
+    pub available_cash: Decimal,
+    /// Negative when money is owed on margin.
+    #[serde(rename = "MARGINBALANCE")]
+    pub margin_balance: Decimal,
+    /// The market value of short positions, which are not included in the positions' value.
+    #[serde(rename = "SHORTBALANCE")]
+    pub short_balance: Decimal,
+    #[serde(rename = "BUYPOWER")]
+    pub buying_power: Option<Decimal>,
+    /// Additional balances from `<BALLIST>`, e.g. net worth or margin interest rate.
+    #[serde(
+        rename = "BALLIST",
+        default,
+        deserialize_with = "super::deserialize_balance_list",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub balances: Vec<NamedBalance>,
+}
+
+impl InvestmentBalance {
+    #[must_use]
+    pub fn new(available_cash: Decimal, margin_balance: Decimal, short_balance: Decimal) -> Self {
+        Self {
+            available_cash,
+            margin_balance,
+            short_balance,
+            buying_power: None,
+            balances: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InvestmentAccount {
+    #[serde(rename = "BROKERID")]
+    pub broker_id: String,
+    #[serde(rename = "ACCTID")]
+    pub id: AccountId,
+}
+
+impl InvestmentAccount {
+    #[must_use]
+    pub fn new(broker_id: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            broker_id: broker_id.into(),
+            id: AccountId::new(id),
+        }
+    }
+}
+
+/// The transactions of an investment statement, in the order the server sent them.
+///
+/// Aggregates Ofxy does not model yet are skipped.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct InvestmentTransactionList {
+    #[serde(rename = "DTSTART")]
+    pub start: DateTime,
+    #[serde(rename = "DTEND")]
+    pub end: DateTime,
+    pub transactions: Vec<InvestmentTransaction>,
+}
+
+impl InvestmentTransactionList {
+    const FIELDS: &'static [&'static str] = &[
+        "DTSTART",
+        "DTEND",
+        "BUYDEBT",
+        "BUYMF",
+        "BUYOPT",
+        "BUYOTHER",
+        "BUYSTOCK",
+        "CLOSUREOPT",
+        "INCOME",
+        "INVBANKTRAN",
+        "JRNLFUND",
+        "JRNLSEC",
+        "MARGININTEREST",
+        "REINVEST",
+        "RETOFCAP",
+        "SELLDEBT",
+        "SELLMF",
+        "SELLOPT",
+        "SELLOTHER",
+        "SELLSTOCK",
+        "SPLIT",
+        "TRANSFER",
+    ];
+
+    #[must_use]
+    pub fn new(start: DateTime, end: DateTime) -> Self {
+        Self {
+            start,
+            end,
+            transactions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum InvestmentTransaction {
+    #[serde(rename = "BUYDEBT")]
+    BuyDebt(BuyDebt),
+    #[serde(rename = "BUYMF")]
+    BuyMutualFund(BuyMutualFund),
+    #[serde(rename = "BUYOPT")]
+    BuyOption(BuyOption),
+    #[serde(rename = "BUYOTHER")]
+    BuyOther(BuyOther),
+    #[serde(rename = "BUYSTOCK")]
+    BuyStock(BuyStock),
+    #[serde(rename = "CLOSUREOPT")]
+    ClosureOption(ClosureOption),
+    #[serde(rename = "INCOME")]
+    Income(Income),
+    #[serde(rename = "INVBANKTRAN")]
+    BankTransaction(BankTransaction),
+    #[serde(rename = "JRNLFUND")]
+    JournalFund(JournalFund),
+    #[serde(rename = "JRNLSEC")]
+    JournalSecurity(JournalSecurity),
+    #[serde(rename = "MARGININTEREST")]
+    MarginInterest(MarginInterest),
+    #[serde(rename = "REINVEST")]
+    Reinvest(Reinvest),
+    #[serde(rename = "RETOFCAP")]
+    ReturnOfCapital(ReturnOfCapital),
+    #[serde(rename = "SELLDEBT")]
+    SellDebt(SellDebt),
+    #[serde(rename = "SELLMF")]
+    SellMutualFund(SellMutualFund),
+    #[serde(rename = "SELLOPT")]
+    SellOption(SellOption),
+    #[serde(rename = "SELLOTHER")]
+    SellOther(SellOther),
+    #[serde(rename = "SELLSTOCK")]
+    SellStock(SellStock),
+    #[serde(rename = "SPLIT")]
+    Split(Split),
+    #[serde(rename = "TRANSFER")]
+    Transfer(Transfer),
+}
+
+impl<'de> Deserialize<'de> for InvestmentTransactionList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("INVTRANLIST", Self::FIELDS, TransactionListVisitor)
+    }
+}
+
+struct TransactionListVisitor;
+
+impl<'de> Visitor<'de> for TransactionListVisitor {
+    type Value = InvestmentTransactionList;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an INVTRANLIST aggregate")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        use InvestmentTransaction as Tx;
+
+        let (mut start, mut end, mut transactions) = (None, None, Vec::new());
+        let out = &mut transactions;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "DTSTART" => start = Some(datetime(&map.next_value::<String>()?)?),
+                "DTEND" => end = Some(datetime(&map.next_value::<String>()?)?),
+                "BUYDEBT" => read_all(&mut map, out, Tx::BuyDebt)?,
+                "BUYMF" => read_all(&mut map, out, Tx::BuyMutualFund)?,
+                "BUYOPT" => read_all(&mut map, out, Tx::BuyOption)?,
+                "BUYOTHER" => read_all(&mut map, out, Tx::BuyOther)?,
+                "BUYSTOCK" => read_all(&mut map, out, Tx::BuyStock)?,
+                "CLOSUREOPT" => read_all(&mut map, out, Tx::ClosureOption)?,
+                "INCOME" => read_all(&mut map, out, Tx::Income)?,
+                "INVBANKTRAN" => read_all(&mut map, out, Tx::BankTransaction)?,
+                "JRNLFUND" => read_all(&mut map, out, Tx::JournalFund)?,
+                "JRNLSEC" => read_all(&mut map, out, Tx::JournalSecurity)?,
+                "MARGININTEREST" => read_all(&mut map, out, Tx::MarginInterest)?,
+                "REINVEST" => read_all(&mut map, out, Tx::Reinvest)?,
+                "RETOFCAP" => read_all(&mut map, out, Tx::ReturnOfCapital)?,
+                "SELLDEBT" => read_all(&mut map, out, Tx::SellDebt)?,
+                "SELLMF" => read_all(&mut map, out, Tx::SellMutualFund)?,
+                "SELLOPT" => read_all(&mut map, out, Tx::SellOption)?,
+                "SELLOTHER" => read_all(&mut map, out, Tx::SellOther)?,
+                "SELLSTOCK" => read_all(&mut map, out, Tx::SellStock)?,
+                "SPLIT" => read_all(&mut map, out, Tx::Split)?,
+                "TRANSFER" => read_all(&mut map, out, Tx::Transfer)?,
+                // As serialized to other formats
+                "transactions" => out.extend(map.next_value::<Vec<Tx>>()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(InvestmentTransactionList {
+            start: start.ok_or_else(|| de::Error::missing_field("DTSTART"))?,
+            end: end.ok_or_else(|| de::Error::missing_field("DTEND"))?,
+            transactions,
+        })
+    }
+}
+
+/// Reads the next value as a sequence, so runs of the same aggregate keep their order whether or
+/// not the deserializer groups repeated tags.
+fn read_all<'de, A, T, U>(map: &mut A, out: &mut Vec<U>, wrap: fn(T) -> U) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+    T: Deserialize<'de>,
+{
+    out.extend(map.next_value::<Vec<T>>()?.into_iter().map(wrap));
+    Ok(())
+}
+
+fn datetime<E: de::Error>(raw: &str) -> Result<DateTime, E> {
+    let de: StrDeserializer<E> = raw.into_deserializer();
+    deserialize_datetime(de)
+}
+
+/// Optional `Y`/`N` flags, written back as `Y`/`N` so the model round-trips through other
+/// serde formats.
+mod flag {
+    use std::borrow::Borrow;
+
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    // Serde passes a reference, which `Borrow` accepts while the flag is taken by value
+    pub(super) fn serialize<S: Serializer>(
+        flag: impl Borrow<Option<bool>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match *flag.borrow() {
+            Some(true) => serializer.serialize_some("Y"),
+            Some(false) => serializer.serialize_some("N"),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<bool>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|flag| match flag.as_str() {
+                "Y" => Ok(true),
+                "N" => Ok(false),
+                other => Err(de::Error::custom(format!("invalid Y/N flag: {other}"))),
+            })
+            .transpose()
+    }
+}
+
+/// The `INVTRAN` aggregate common to all investment transactions.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TransactionInfo {
+    #[serde(rename = "FITID")]
+    pub id: String,
+    #[serde(rename = "SRVRTID")]
+    pub server_id: Option<String>,
+    #[serde(rename = "DTTRADE")]
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub trade_date: DateTime,
+    #[serde(rename = "DTSETTLE", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub settle_date: Option<DateTime>,
+    /// The FITID of the transaction this one reverses.
+    #[serde(rename = "REVERSALFITID")]
+    pub reversal_id: Option<String>,
+    #[serde(rename = "MEMO")]
+    pub memo: Option<String>,
+}
+
+impl TransactionInfo {
+    #[must_use]
+    pub fn new(id: impl Into<String>, trade_date: DateTime) -> Self {
+        Self {
+            id: id.into(),
+            server_id: None,
+            trade_date,
+            settle_date: None,
+            reversal_id: None,
+            memo: None,
+        }
+    }
+}
+
+/// A security identifier, e.g. a CUSIP.
+///
+/// Transactions, positions, and the security list all identify securities this way, so it can be
+/// used as a key to join them.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SecurityId {
+    #[serde(rename = "UNIQUEID")]
+    pub unique_id: String,
+    #[serde(rename = "UNIQUEIDTYPE")]
+    pub unique_id_type: String,
+}
+
+impl SecurityId {
+    #[must_use]
+    pub fn new(unique_id: impl Into<String>, unique_id_type: impl Into<String>) -> Self {
+        Self {
+            unique_id: unique_id.into(),
+            unique_id_type: unique_id_type.into(),
+        }
+    }
+
+    /// Whether this is a CUSIP, the identifier type used by most US institutions.
+    #[must_use]
+    pub fn is_cusip(&self) -> bool {
+        self.unique_id_type.eq_ignore_ascii_case("CUSIP")
+    }
+
+    #[must_use]
+    pub fn is_isin(&self) -> bool {
+        self.unique_id_type.eq_ignore_ascii_case("ISIN")
+    }
+
+    #[must_use]
+    pub fn is_ticker(&self) -> bool {
+        self.unique_id_type.eq_ignore_ascii_case("TICKER")
+    }
+}
+
+impl fmt::Display for SecurityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.unique_id_type, self.unique_id)
+    }
+}
+
+/// The sub-account a security or cash is held in.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SubAccount {
+    Cash,
+    Margin,
+    Short,
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum IncomeType {
+    #[serde(rename = "CGLONG")]
+    LongTermCapitalGain,
+    #[serde(rename = "CGSHORT")]
+    ShortTermCapitalGain,
+    #[serde(rename = "DIV")]
+    Dividend,
+    Interest,
+    #[serde(rename = "MISC")]
+    Miscellaneous,
+}
+
+/// The source of money in a 401(k) account.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Inv401kSource {
+    PreTax,
+    AfterTax,
+    Match,
+    ProfitSharing,
+    Rollover,
+    OtherVest,
+    OtherNonVest,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TransferAction {
+    In,
+    Out,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PositionType {
+    Long,
+    Short,
+}
+
+/// Investment income, such as a dividend or capital gains distribution, paid in cash.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Income {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "INCOMETYPE")]
+    pub income_type: IncomeType,
+    #[serde(rename = "TOTAL")]
+    pub total: Decimal,
+    #[serde(rename = "SUBACCTSEC")]
+    pub security_sub_account: SubAccount,
+    #[serde(rename = "SUBACCTFUND")]
+    pub fund_sub_account: SubAccount,
+    #[serde(rename = "TAXEXEMPT", default)]
+    #[serde(with = "flag")]
+    pub tax_exempt: Option<bool>,
+    #[serde(rename = "WITHHOLDING")]
+    pub withholding: Option<Decimal>,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "ORIGCURRENCY")]
+    pub original_currency: Option<Currency>,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+/// Investment income reinvested in the security that paid it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Reinvest {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "INCOMETYPE")]
+    pub income_type: IncomeType,
+    #[serde(rename = "TOTAL")]
+    pub total: Decimal,
+    #[serde(rename = "SUBACCTSEC")]
+    pub security_sub_account: SubAccount,
+    #[serde(rename = "UNITS")]
+    pub units: Decimal,
+    #[serde(rename = "UNITPRICE")]
+    pub unit_price: Decimal,
+    #[serde(rename = "COMMISSION")]
+    pub commission: Option<Decimal>,
+    #[serde(rename = "TAXES")]
+    pub taxes: Option<Decimal>,
+    #[serde(rename = "FEES")]
+    pub fees: Option<Decimal>,
+    #[serde(rename = "LOAD")]
+    pub load: Option<Decimal>,
+    #[serde(rename = "TAXEXEMPT", default)]
+    #[serde(with = "flag")]
+    pub tax_exempt: Option<bool>,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "ORIGCURRENCY")]
+    pub original_currency: Option<Currency>,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+/// A transfer of securities into or out of the account.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Transfer {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "SUBACCTSEC")]
+    pub security_sub_account: SubAccount,
+    #[serde(rename = "UNITS")]
+    pub units: Decimal,
+    #[serde(rename = "TFERACTION")]
+    pub action: TransferAction,
+    #[serde(rename = "POSTYPE")]
+    pub position_type: PositionType,
+    /// The other account involved in the transfer.
+    #[serde(rename = "INVACCTFROM")]
+    pub account: Option<InvestmentAccount>,
+    #[serde(rename = "AVGCOSTBASIS")]
+    pub average_cost_basis: Option<Decimal>,
+    #[serde(rename = "UNITPRICE")]
+    pub unit_price: Option<Decimal>,
+    #[serde(rename = "DTPURCHASE", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub purchase_date: Option<DateTime>,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+/// A return of capital paid in cash.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReturnOfCapital {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "TOTAL")]
+    pub total: Decimal,
+    #[serde(rename = "SUBACCTSEC")]
+    pub security_sub_account: SubAccount,
+    #[serde(rename = "SUBACCTFUND")]
+    pub fund_sub_account: SubAccount,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "ORIGCURRENCY")]
+    pub original_currency: Option<Currency>,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+/// The `INVBUY` aggregate common to purchases of every security type.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InvestmentBuy {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "UNITS")]
+    pub units: Decimal,
+    #[serde(rename = "UNITPRICE")]
+    pub unit_price: Decimal,
+    #[serde(rename = "MARKUP")]
+    pub markup: Option<Decimal>,
+    #[serde(rename = "COMMISSION")]
+    pub commission: Option<Decimal>,
+    #[serde(rename = "TAXES")]
+    pub taxes: Option<Decimal>,
+    #[serde(rename = "FEES")]
+    pub fees: Option<Decimal>,
+    #[serde(rename = "LOAD")]
+    pub load: Option<Decimal>,
+    #[serde(rename = "TOTAL")]
+    pub total: Decimal,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "ORIGCURRENCY")]
+    pub original_currency: Option<Currency>,
+    #[serde(rename = "SUBACCTSEC")]
+    pub security_sub_account: SubAccount,
+    #[serde(rename = "SUBACCTFUND")]
+    pub fund_sub_account: SubAccount,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+/// The `INVSELL` aggregate common to sales of every security type.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InvestmentSell {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "UNITS")]
+    pub units: Decimal,
+    #[serde(rename = "UNITPRICE")]
+    pub unit_price: Decimal,
+    #[serde(rename = "MARKDOWN")]
+    pub markdown: Option<Decimal>,
+    #[serde(rename = "COMMISSION")]
+    pub commission: Option<Decimal>,
+    #[serde(rename = "TAXES")]
+    pub taxes: Option<Decimal>,
+    #[serde(rename = "FEES")]
+    pub fees: Option<Decimal>,
+    #[serde(rename = "LOAD")]
+    pub load: Option<Decimal>,
+    #[serde(rename = "WITHHOLDING")]
+    pub withholding: Option<Decimal>,
+    #[serde(rename = "TAXEXEMPT", default)]
+    #[serde(with = "flag")]
+    pub tax_exempt: Option<bool>,
+    #[serde(rename = "TOTAL")]
+    pub total: Decimal,
+    #[serde(rename = "GAIN")]
+    pub gain: Option<Decimal>,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "ORIGCURRENCY")]
+    pub original_currency: Option<Currency>,
+    #[serde(rename = "SUBACCTSEC")]
+    pub security_sub_account: SubAccount,
+    #[serde(rename = "SUBACCTFUND")]
+    pub fund_sub_account: SubAccount,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OptionBuyType {
+    BuyToOpen,
+    BuyToClose,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OptionSellType {
+    SellToOpen,
+    SellToClose,
+}
+
+/// How an option position was closed other than by trading it.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OptionAction {
+    Exercise,
+    Assign,
+    Expire,
+}
+
+/// How a sold option relates to the transaction identified by its `related_id`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RelatedType {
+    Spread,
+    Straddle,
+    None,
+    Other,
+}
+
+/// Whether a sold option is backed by the underlying security.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Secured {
+    Naked,
+    Covered,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyOption {
+    #[serde(rename = "INVBUY")]
+    pub buy: InvestmentBuy,
+    #[serde(rename = "OPTBUYTYPE")]
+    pub buy_type: OptionBuyType,
+    #[serde(rename = "SHPERCTRCT")]
+    pub shares_per_contract: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellOption {
+    #[serde(rename = "INVSELL")]
+    pub sell: InvestmentSell,
+    #[serde(rename = "OPTSELLTYPE")]
+    pub sell_type: OptionSellType,
+    #[serde(rename = "SHPERCTRCT")]
+    pub shares_per_contract: u32,
+    /// The FITID of a related transaction, e.g. the other leg of a spread.
+    #[serde(rename = "RELFITID")]
+    pub related_id: Option<String>,
+    #[serde(rename = "RELTYPE")]
+    pub related_type: Option<RelatedType>,
+    #[serde(rename = "SECURED")]
+    pub secured: Option<Secured>,
+}
+
+/// An option position closed by exercise, assignment, or expiration.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ClosureOption {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "OPTACTION")]
+    pub action: OptionAction,
+    #[serde(rename = "UNITS")]
+    pub units: Decimal,
+    #[serde(rename = "SHPERCTRCT")]
+    pub shares_per_contract: u32,
+    #[serde(rename = "SUBACCTSEC")]
+    pub security_sub_account: SubAccount,
+    /// The FITID of the transaction for the underlying security, if any.
+    #[serde(rename = "RELFITID")]
+    pub related_id: Option<String>,
+    #[serde(rename = "GAIN")]
+    pub gain: Option<Decimal>,
+}
+
+impl ClosureOption {
+    /// Whether the option expired worthless, so there is no related underlying transaction.
+    #[must_use]
+    pub fn expired(&self) -> bool {
+        self.action == OptionAction::Expire
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum BuyType {
+    Buy,
+    BuyToCover,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SellType {
+    Sell,
+    SellShort,
+}
+
+/// Why a debt security was sold.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SellReason {
+    Call,
+    Sell,
+    Maturity,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyDebt {
+    #[serde(rename = "INVBUY")]
+    pub buy: InvestmentBuy,
+    #[serde(rename = "ACCRDINT")]
+    pub accrued_interest: Option<Decimal>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyMutualFund {
+    #[serde(rename = "INVBUY")]
+    pub buy: InvestmentBuy,
+    #[serde(rename = "BUYTYPE")]
+    pub buy_type: BuyType,
+    #[serde(rename = "RELFITID")]
+    pub related_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyOther {
+    #[serde(rename = "INVBUY")]
+    pub buy: InvestmentBuy,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyStock {
+    #[serde(rename = "INVBUY")]
+    pub buy: InvestmentBuy,
+    #[serde(rename = "BUYTYPE")]
+    pub buy_type: BuyType,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellDebt {
+    #[serde(rename = "INVSELL")]
+    pub sell: InvestmentSell,
+    #[serde(rename = "SELLREASON")]
+    pub reason: SellReason,
+    #[serde(rename = "ACCRDINT")]
+    pub accrued_interest: Option<Decimal>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellMutualFund {
+    #[serde(rename = "INVSELL")]
+    pub sell: InvestmentSell,
+    #[serde(rename = "SELLTYPE")]
+    pub sell_type: SellType,
+    #[serde(rename = "AVGCOSTBASIS")]
+    pub average_cost_basis: Option<Decimal>,
+    #[serde(rename = "RELFITID")]
+    pub related_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellOther {
+    #[serde(rename = "INVSELL")]
+    pub sell: InvestmentSell,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellStock {
+    #[serde(rename = "INVSELL")]
+    pub sell: InvestmentSell,
+    #[serde(rename = "SELLTYPE")]
+    pub sell_type: SellType,
+}
+
+/// Interest charged on a margin balance.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MarginInterest {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "TOTAL")]
+    pub total: Decimal,
+    #[serde(rename = "SUBACCTFUND")]
+    pub fund_sub_account: SubAccount,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "ORIGCURRENCY")]
+    pub original_currency: Option<Currency>,
+}
+
+/// A stock split, which changes `old_units` to `new_units` at a ratio of
+/// `numerator`:`denominator`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Split {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "SUBACCTSEC")]
+    pub security_sub_account: SubAccount,
+    #[serde(rename = "OLDUNITS")]
+    pub old_units: Decimal,
+    #[serde(rename = "NEWUNITS")]
+    pub new_units: Decimal,
+    #[serde(rename = "NUMERATOR")]
+    pub numerator: Decimal,
+    #[serde(rename = "DENOMINATOR")]
+    pub denominator: Decimal,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "ORIGCURRENCY")]
+    pub original_currency: Option<Currency>,
+    /// Cash paid in lieu of fractional units.
+    #[serde(rename = "FRACCASH")]
+    pub fractional_cash: Option<Decimal>,
+    #[serde(rename = "SUBACCTFUND")]
+    pub fund_sub_account: Option<SubAccount>,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+/// Securities moved between sub-accounts.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JournalSecurity {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "SUBACCTTO")]
+    pub to: SubAccount,
+    #[serde(rename = "SUBACCTFROM")]
+    pub from: SubAccount,
+    #[serde(rename = "UNITS")]
+    pub units: Decimal,
+}
+
+/// Cash moved between sub-accounts.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JournalFund {
+    #[serde(rename = "INVTRAN")]
+    pub info: TransactionInfo,
+    #[serde(rename = "SUBACCTTO")]
+    pub to: SubAccount,
+    #[serde(rename = "SUBACCTFROM")]
+    pub from: SubAccount,
+    #[serde(rename = "TOTAL")]
+    pub total: Decimal,
+}
+
+/// A cash transaction in a brokerage account, such as interest or a transfer.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BankTransaction {
+    #[serde(rename = "STMTTRN")]
+    pub transaction: Transaction,
+    #[serde(rename = "SUBACCTFUND")]
+    pub fund_sub_account: SubAccount,
+}
+
+/// The holdings of an investment account.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PositionList {
+    pub positions: Vec<Position>,
+}
+
+impl PositionList {
+    const FIELDS: &'static [&'static str] = &["POSDEBT", "POSMF", "POSOPT", "POSOTHER", "POSSTOCK"];
+}
+
+impl<'de> Deserialize<'de> for PositionList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("INVPOSLIST", Self::FIELDS, PositionListVisitor)
+    }
+}
+
+struct PositionListVisitor;
+
+impl<'de> Visitor<'de> for PositionListVisitor {
+    type Value = PositionList;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an INVPOSLIST aggregate")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        use Position as Pos;
+
+        let mut positions = Vec::new();
+        let out = &mut positions;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "POSDEBT" => read_all(&mut map, out, Pos::Debt)?,
+                "POSMF" => read_all(&mut map, out, Pos::MutualFund)?,
+                "POSOPT" => read_all(&mut map, out, Pos::Option)?,
+                "POSOTHER" => read_all(&mut map, out, Pos::Other)?,
+                "POSSTOCK" => read_all(&mut map, out, Pos::Stock)?,
+                // As serialized to other formats
+                "positions" => out.extend(map.next_value::<Vec<Pos>>()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(PositionList { positions })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum Position {
+    #[serde(rename = "POSDEBT")]
+    Debt(DebtPosition),
+    #[serde(rename = "POSMF")]
+    MutualFund(MutualFundPosition),
+    #[serde(rename = "POSOPT")]
+    Option(OptionPosition),
+    #[serde(rename = "POSOTHER")]
+    Other(OtherPosition),
+    #[serde(rename = "POSSTOCK")]
+    Stock(StockPosition),
+}
+
+impl Position {
+    /// The `INVPOS` shared by every position type.
+    #[must_use]
+    pub fn info(&self) -> &PositionInfo {
+        match self {
+            Self::Debt(position) => &position.info,
+            Self::MutualFund(position) => &position.info,
+            Self::Option(position) => &position.info,
+            Self::Other(position) => &position.info,
+            Self::Stock(position) => &position.info,
+        }
+    }
+}
+
+/// The `INVPOS` aggregate common to all position types.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PositionInfo {
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "HELDINACCT")]
+    pub held_in: SubAccount,
+    #[serde(rename = "POSTYPE")]
+    pub position_type: PositionType,
+    #[serde(rename = "UNITS")]
+    pub units: Decimal,
+    #[serde(rename = "UNITPRICE")]
+    pub unit_price: Decimal,
+    #[serde(rename = "MKTVAL")]
+    pub market_value: Decimal,
+    #[serde(rename = "AVGCOSTBASIS")]
+    pub average_cost_basis: Option<Decimal>,
+    /// When `unit_price` was current.
+    #[serde(rename = "DTPRICEASOF")]
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub price_date: DateTime,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "MEMO")]
+    pub memo: Option<String>,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DebtPosition {
+    #[serde(rename = "INVPOS")]
+    pub info: PositionInfo,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MutualFundPosition {
+    #[serde(rename = "INVPOS")]
+    pub info: PositionInfo,
+    /// Units held in the broker's name.
+    #[serde(rename = "UNITSSTREET")]
+    pub units_street: Option<Decimal>,
+    /// Units held in the user's name.
+    #[serde(rename = "UNITSUSER")]
+    pub units_user: Option<Decimal>,
+    /// Whether dividends are reinvested.
+    #[serde(rename = "REINVDIV", default)]
+    #[serde(with = "flag")]
+    pub reinvest_dividends: Option<bool>,
+    /// Whether capital gains are reinvested.
+    #[serde(rename = "REINVCG", default)]
+    #[serde(with = "flag")]
+    pub reinvest_capital_gains: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OptionPosition {
+    #[serde(rename = "INVPOS")]
+    pub info: PositionInfo,
+    #[serde(rename = "SECURED")]
+    pub secured: Option<Secured>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OtherPosition {
+    #[serde(rename = "INVPOS")]
+    pub info: PositionInfo,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct StockPosition {
+    #[serde(rename = "INVPOS")]
+    pub info: PositionInfo,
+    /// Units held in the broker's name.
+    #[serde(rename = "UNITSSTREET")]
+    pub units_street: Option<Decimal>,
+    /// Units held in the user's name.
+    #[serde(rename = "UNITSUSER")]
+    pub units_user: Option<Decimal>,
+    /// Whether dividends are reinvested.
+    #[serde(rename = "REINVDIV", default)]
+    #[serde(with = "flag")]
+    pub reinvest_dividends: Option<bool>,
+}
+
+/// Orders that have been placed but not yet filled.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct OpenOrderList {
+    pub orders: Vec<OpenOrder>,
+}
+
+impl OpenOrderList {
+    const FIELDS: &'static [&'static str] = &[
+        "OOBUYDEBT",
+        "OOBUYMF",
+        "OOBUYOPT",
+        "OOBUYOTHER",
+        "OOBUYSTOCK",
+        "OOSELLDEBT",
+        "OOSELLMF",
+        "OOSELLOPT",
+        "OOSELLOTHER",
+        "OOSELLSTOCK",
+        "SWITCHMF",
+    ];
+}
+
+impl<'de> Deserialize<'de> for OpenOrderList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("INVOOLIST", Self::FIELDS, OpenOrderListVisitor)
+    }
+}
+
+struct OpenOrderListVisitor;
+
+impl<'de> Visitor<'de> for OpenOrderListVisitor {
+    type Value = OpenOrderList;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an INVOOLIST aggregate")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        use OpenOrder as Oo;
+
+        let mut orders = Vec::new();
+        let out = &mut orders;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "OOBUYDEBT" => read_all(&mut map, out, Oo::BuyDebt)?,
+                "OOBUYMF" => read_all(&mut map, out, Oo::BuyMutualFund)?,
+                "OOBUYOPT" => read_all(&mut map, out, Oo::BuyOption)?,
+                "OOBUYOTHER" => read_all(&mut map, out, Oo::BuyOther)?,
+                "OOBUYSTOCK" => read_all(&mut map, out, Oo::BuyStock)?,
+                "OOSELLDEBT" => read_all(&mut map, out, Oo::SellDebt)?,
+                "OOSELLMF" => read_all(&mut map, out, Oo::SellMutualFund)?,
+                "OOSELLOPT" => read_all(&mut map, out, Oo::SellOption)?,
+                "OOSELLOTHER" => read_all(&mut map, out, Oo::SellOther)?,
+                "OOSELLSTOCK" => read_all(&mut map, out, Oo::SellStock)?,
+                "SWITCHMF" => read_all(&mut map, out, Oo::SwitchMutualFund)?,
+                // As serialized to other formats
+                "orders" => out.extend(map.next_value::<Vec<Oo>>()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(OpenOrderList { orders })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum OpenOrder {
+    #[serde(rename = "OOBUYDEBT")]
+    BuyDebt(BuyDebtOrder),
+    #[serde(rename = "OOBUYMF")]
+    BuyMutualFund(BuyMutualFundOrder),
+    #[serde(rename = "OOBUYOPT")]
+    BuyOption(BuyOptionOrder),
+    #[serde(rename = "OOBUYOTHER")]
+    BuyOther(BuyOtherOrder),
+    #[serde(rename = "OOBUYSTOCK")]
+    BuyStock(BuyStockOrder),
+    #[serde(rename = "OOSELLDEBT")]
+    SellDebt(SellDebtOrder),
+    #[serde(rename = "OOSELLMF")]
+    SellMutualFund(SellMutualFundOrder),
+    #[serde(rename = "OOSELLOPT")]
+    SellOption(SellOptionOrder),
+    #[serde(rename = "OOSELLOTHER")]
+    SellOther(SellOtherOrder),
+    #[serde(rename = "OOSELLSTOCK")]
+    SellStock(SellStockOrder),
+    #[serde(rename = "SWITCHMF")]
+    SwitchMutualFund(SwitchMutualFundOrder),
+}
+
+impl OpenOrder {
+    /// The `OO` shared by every order type.
+    #[must_use]
+    pub fn info(&self) -> &OrderInfo {
+        match self {
+            Self::BuyDebt(order) => &order.info,
+            Self::BuyMutualFund(order) => &order.info,
+            Self::BuyOption(order) => &order.info,
+            Self::BuyOther(order) => &order.info,
+            Self::BuyStock(order) => &order.info,
+            Self::SellDebt(order) => &order.info,
+            Self::SellMutualFund(order) => &order.info,
+            Self::SellOption(order) => &order.info,
+            Self::SellOther(order) => &order.info,
+            Self::SellStock(order) => &order.info,
+            Self::SwitchMutualFund(order) => &order.info,
+        }
+    }
+}
+
+/// How long an order stays open.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderDuration {
+    Day,
+    GoodTilCancel,
+    Immediate,
+}
+
+/// Conditions on how an order may be filled.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderRestriction {
+    AllOrNone,
+    MinUnits,
+    None,
+}
+
+/// Whether an order's `units` count shares or an amount of currency.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum UnitType {
+    Shares,
+    Currency,
+}
+
+/// The `OO` aggregate common to all open orders.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OrderInfo {
+    #[serde(rename = "FITID")]
+    pub id: String,
+    #[serde(rename = "SRVRTID")]
+    pub server_id: Option<String>,
+    #[serde(rename = "SECID")]
+    pub security: SecurityId,
+    #[serde(rename = "DTPLACED")]
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub date_placed: DateTime,
+    #[serde(rename = "UNITS")]
+    pub units: Decimal,
+    #[serde(rename = "SUBACCT")]
+    pub sub_account: SubAccount,
+    #[serde(rename = "DURATION")]
+    pub duration: OrderDuration,
+    #[serde(rename = "RESTRICTION")]
+    pub restriction: OrderRestriction,
+    /// The smallest fill accepted, when `restriction` is [`OrderRestriction::MinUnits`].
+    #[serde(rename = "MINUNITS")]
+    pub min_units: Option<Decimal>,
+    #[serde(rename = "LIMITPRICE")]
+    pub limit_price: Option<Decimal>,
+    #[serde(rename = "STOPPRICE")]
+    pub stop_price: Option<Decimal>,
+    #[serde(rename = "MEMO")]
+    pub memo: Option<String>,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "INV401KSOURCE")]
+    pub inv401k_source: Option<Inv401kSource>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyDebtOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    /// Whether the debt is being bought at auction.
+    #[serde(rename = "AUCTION", default)]
+    #[serde(with = "flag")]
+    pub auction: Option<bool>,
+    #[serde(rename = "DTAUCTION", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub auction_date: Option<DateTime>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyMutualFundOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    #[serde(rename = "BUYTYPE")]
+    pub buy_type: BuyType,
+    #[serde(rename = "UNITTYPE")]
+    pub unit_type: UnitType,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyOptionOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    #[serde(rename = "OPTBUYTYPE")]
+    pub buy_type: OptionBuyType,
+    #[serde(rename = "SHPERCTRCT")]
+    pub shares_per_contract: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyOtherOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    #[serde(rename = "UNITTYPE")]
+    pub unit_type: UnitType,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BuyStockOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    #[serde(rename = "BUYTYPE")]
+    pub buy_type: BuyType,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellDebtOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellMutualFundOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    #[serde(rename = "SELLTYPE")]
+    pub sell_type: SellType,
+    #[serde(rename = "UNITTYPE")]
+    pub unit_type: UnitType,
+    /// Whether to sell the whole holding, whatever `units` says.
+    #[serde(rename = "SELLALL", default)]
+    #[serde(with = "flag")]
+    pub sell_all: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellOptionOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    #[serde(rename = "OPTSELLTYPE")]
+    pub sell_type: OptionSellType,
+    #[serde(rename = "SHPERCTRCT")]
+    pub shares_per_contract: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellOtherOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    #[serde(rename = "UNITTYPE")]
+    pub unit_type: UnitType,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SellStockOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    #[serde(rename = "SELLTYPE")]
+    pub sell_type: SellType,
+}
+
+/// An exchange of one mutual fund for another.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SwitchMutualFundOrder {
+    #[serde(rename = "OO")]
+    pub info: OrderInfo,
+    /// The fund being switched into.
+    #[serde(rename = "SECID")]
+    pub target: SecurityId,
+    #[serde(rename = "UNITTYPE")]
+    pub unit_type: UnitType,
+    #[serde(rename = "SWITCHALL", default)]
+    #[serde(with = "flag")]
+    pub switch_all: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SecurityListMessageResponse {
+    #[serde(rename = "SECLIST")]
+    pub list: Option<SecurityList>,
+}
+
+/// The securities referenced by the investment statements in a document.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SecurityList {
+    pub securities: Vec<Security>,
+}
+
+impl SecurityList {
+    const FIELDS: &'static [&'static str] =
+        &["DEBTINFO", "MFINFO", "OPTINFO", "OTHERINFO", "STOCKINFO"];
+
+    /// Finds a security by its identifier.
+    #[must_use]
+    pub fn get(&self, id: &SecurityId) -> Option<&Security> {
+        self.securities
+            .iter()
+            .find(|security| &security.info().id == id)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecurityList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("SECLIST", Self::FIELDS, SecurityListVisitor)
+    }
+}
+
+struct SecurityListVisitor;
+
+impl<'de> Visitor<'de> for SecurityListVisitor {
+    type Value = SecurityList;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a SECLIST aggregate")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        use Security as Sec;
+
+        let mut securities = Vec::new();
+        let out = &mut securities;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "DEBTINFO" => read_all(&mut map, out, Sec::Debt)?,
+                "MFINFO" => read_all(&mut map, out, Sec::MutualFund)?,
+                "OPTINFO" => read_all(&mut map, out, Sec::Option)?,
+                "OTHERINFO" => read_all(&mut map, out, Sec::Other)?,
+                "STOCKINFO" => read_all(&mut map, out, Sec::Stock)?,
+                // As serialized to other formats
+                "securities" => out.extend(map.next_value::<Vec<Sec>>()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(SecurityList { securities })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum Security {
+    #[serde(rename = "DEBTINFO")]
+    Debt(DebtInfo),
+    #[serde(rename = "MFINFO")]
+    MutualFund(MutualFundInfo),
+    #[serde(rename = "OPTINFO")]
+    Option(OptionInfo),
+    #[serde(rename = "OTHERINFO")]
+    Other(OtherInfo),
+    #[serde(rename = "STOCKINFO")]
+    Stock(StockInfo),
+}
+
+impl Security {
+    /// The `SECINFO` shared by every security type.
+    #[must_use]
+    pub fn info(&self) -> &SecurityInfo {
+        match self {
+            Self::Debt(security) => &security.info,
+            Self::MutualFund(security) => &security.info,
+            Self::Option(security) => &security.info,
+            Self::Other(security) => &security.info,
+            Self::Stock(security) => &security.info,
+        }
+    }
+}
+
+/// The `SECINFO` aggregate common to all security types.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SecurityInfo {
+    #[serde(rename = "SECID")]
+    pub id: SecurityId,
+    #[serde(rename = "SECNAME")]
+    pub name: String,
+    #[serde(rename = "TICKER")]
+    pub ticker: Option<String>,
+    /// The institution's own identifier for the security.
+    #[serde(rename = "FIID")]
+    pub fi_id: Option<String>,
+    #[serde(rename = "RATING")]
+    pub rating: Option<String>,
+    #[serde(rename = "UNITPRICE")]
+    pub unit_price: Option<Decimal>,
+    /// When `unit_price` was current.
+    #[serde(rename = "DTASOF", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub price_date: Option<DateTime>,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+    #[serde(rename = "MEMO")]
+    pub memo: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AssetClass {
+    DomesticBond,
+    #[serde(rename = "INTLBOND")]
+    InternationalBond,
+    LargeStock,
+    SmallStock,
+    #[serde(rename = "INTLSTOCK")]
+    InternationalStock,
+    #[serde(rename = "MONEYMRKT")]
+    MoneyMarket,
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StockType {
+    Common,
+    Preferred,
+    Convertible,
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct StockInfo {
+    #[serde(rename = "SECINFO")]
+    pub info: SecurityInfo,
+    #[serde(rename = "STOCKTYPE")]
+    pub stock_type: Option<StockType>,
+    #[serde(rename = "YIELD")]
+    pub yield_: Option<Decimal>,
+    #[serde(rename = "DTYIELDASOF", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub yield_date: Option<DateTime>,
+    #[serde(rename = "ASSETCLASS")]
+    pub asset_class: Option<AssetClass>,
+    #[serde(rename = "FIASSETCLASS")]
+    pub fi_asset_class: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum MutualFundType {
+    OpenEnd,
+    CloseEnd,
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MutualFundInfo {
+    #[serde(rename = "SECINFO")]
+    pub info: SecurityInfo,
+    #[serde(rename = "MFTYPE")]
+    pub fund_type: Option<MutualFundType>,
+    #[serde(rename = "YIELD")]
+    pub yield_: Option<Decimal>,
+    #[serde(rename = "DTYIELDASOF", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub yield_date: Option<DateTime>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OptionType {
+    Put,
+    Call,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OptionInfo {
+    #[serde(rename = "SECINFO")]
+    pub info: SecurityInfo,
+    #[serde(rename = "OPTTYPE")]
+    pub option_type: OptionType,
+    #[serde(rename = "STRIKEPRICE")]
+    pub strike_price: Decimal,
+    #[serde(rename = "DTEXPIRE")]
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub expires: DateTime,
+    #[serde(rename = "SHPERCTRCT")]
+    pub shares_per_contract: u32,
+    /// The underlying security.
+    #[serde(rename = "SECID")]
+    pub underlying: Option<SecurityId>,
+    #[serde(rename = "ASSETCLASS")]
+    pub asset_class: Option<AssetClass>,
+    #[serde(rename = "FIASSETCLASS")]
+    pub fi_asset_class: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DebtType {
+    Coupon,
+    Zero,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DebtClass {
+    Treasury,
+    Municipal,
+    Corporate,
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DebtInfo {
+    #[serde(rename = "SECINFO")]
+    pub info: SecurityInfo,
+    #[serde(rename = "PARVALUE")]
+    pub par_value: Decimal,
+    #[serde(rename = "DEBTTYPE")]
+    pub debt_type: DebtType,
+    #[serde(rename = "DEBTCLASS")]
+    pub debt_class: Option<DebtClass>,
+    #[serde(rename = "COUPONRT")]
+    pub coupon_rate: Option<Decimal>,
+    #[serde(rename = "DTCOUPON", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub next_coupon: Option<DateTime>,
+    #[serde(rename = "YIELDTOMAT")]
+    pub yield_to_maturity: Option<Decimal>,
+    #[serde(rename = "DTMAT", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub maturity: Option<DateTime>,
+    #[serde(rename = "ASSETCLASS")]
+    pub asset_class: Option<AssetClass>,
+    #[serde(rename = "FIASSETCLASS")]
+    pub fi_asset_class: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OtherInfo {
+    #[serde(rename = "SECINFO")]
+    pub info: SecurityInfo,
+    /// A description of the security type.
+    #[serde(rename = "TYPEDESC")]
+    pub type_description: Option<String>,
+    #[serde(rename = "ASSETCLASS")]
+    pub asset_class: Option<AssetClass>,
+    #[serde(rename = "FIASSETCLASS")]
+    pub fi_asset_class: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_income_and_transfers_in_order() {
+        let statement: InvestmentStatementResponse = "<INVSTMTRS><DTASOF>20250930<CURDEF>USD\
+            <INVACCTFROM><BROKERID>example.com<ACCTID>12345</INVACCTFROM>\
+            <INVTRANLIST><DTSTART>20250901<DTEND>20250930\
+            <INCOME><INVTRAN><FITID>1<DTTRADE>20250905</INVTRAN>\
+            <SECID><UNIQUEID>78462F103<UNIQUEIDTYPE>CUSIP</SECID><INCOMETYPE>DIV\
+            <TOTAL>+00000000000005.5300<SUBACCTSEC>CASH<SUBACCTFUND>CASH<TAXEXEMPT>N</INCOME>\
+            <TRANSFER><INVTRAN><FITID>2<DTTRADE>20250910</INVTRAN>\
+            <SECID><UNIQUEID>BAR<UNIQUEIDTYPE>PRIVATE</SECID><SUBACCTSEC>OTHER<UNITS>6.8\
+            <TFERACTION>IN<POSTYPE>LONG<UNITPRICE>29.21<INV401KSOURCE>MATCH</TRANSFER>\
+            <REINVEST><INVTRAN><FITID>3<DTTRADE>20250915</INVTRAN>\
+            <SECID><UNIQUEID>78462F103<UNIQUEIDTYPE>CUSIP</SECID><INCOMETYPE>CGLONG\
+            <TOTAL>-10<SUBACCTSEC>CASH<UNITS>0.1<UNITPRICE>100</REINVEST>\
+            <RETOFCAP><INVTRAN><FITID>4<DTTRADE>20250920</INVTRAN>\
+            <SECID><UNIQUEID>78462F103<UNIQUEIDTYPE>CUSIP</SECID><TOTAL>3\
+            <SUBACCTSEC>CASH<SUBACCTFUND>CASH</RETOFCAP>\
+            </INVTRANLIST></INVSTMTRS>"
+            .parse()
+            .unwrap();
+        assert_eq!(statement.account.broker_id, "example.com");
+
+        let transactions = statement.transactions.unwrap().transactions;
+        let ids: Vec<_> = transactions
+            .iter()
+            .map(|transaction| match transaction {
+                InvestmentTransaction::Income(t) => &t.info.id,
+                InvestmentTransaction::Reinvest(t) => &t.info.id,
+                InvestmentTransaction::ReturnOfCapital(t) => &t.info.id,
+                InvestmentTransaction::Transfer(t) => &t.info.id,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(ids, ["1", "2", "3", "4"]);
+
+        let InvestmentTransaction::Income(income) = &transactions[0] else {
+            panic!("expected income");
+        };
+        assert_eq!(income.income_type, IncomeType::Dividend);
+        assert_eq!(income.tax_exempt, Some(false));
+        let InvestmentTransaction::Transfer(transfer) = &transactions[1] else {
+            panic!("expected transfer");
+        };
+        assert_eq!(transfer.action, TransferAction::In);
+        assert_eq!(transfer.inv401k_source, Some(Inv401kSource::Match));
+    }
+
+    #[test]
+    fn parses_option_transactions() {
+        let list: InvestmentTransactionList = "<INVTRANLIST><DTSTART>20250901<DTEND>20250930\
+            <BUYOPT><INVBUY><INVTRAN><FITID>1<DTTRADE>20250902</INVTRAN>\
+            <SECID><UNIQUEID>AAPL250919C00200000<UNIQUEIDTYPE>TICKER</SECID><UNITS>2\
+            <UNITPRICE>1.25<COMMISSION>1.30<TOTAL>-251.30<SUBACCTSEC>MARGIN\
+            <SUBACCTFUND>MARGIN</INVBUY><OPTBUYTYPE>BUYTOOPEN<SHPERCTRCT>100</BUYOPT>\
+            <SELLOPT><INVSELL><INVTRAN><FITID>2<DTTRADE>20250903</INVTRAN>\
+            <SECID><UNIQUEID>AAPL250919C00210000<UNIQUEIDTYPE>TICKER</SECID><UNITS>-2\
+            <UNITPRICE>0.5<TOTAL>100<SUBACCTSEC>MARGIN<SUBACCTFUND>MARGIN</INVSELL>\
+            <OPTSELLTYPE>SELLTOOPEN<SHPERCTRCT>100<RELFITID>1<RELTYPE>SPREAD</SELLOPT>\
+            <CLOSUREOPT><INVTRAN><FITID>3<DTTRADE>20250919</INVTRAN>\
+            <SECID><UNIQUEID>AAPL250919C00210000<UNIQUEIDTYPE>TICKER</SECID>\
+            <OPTACTION>EXPIRE<UNITS>2<SHPERCTRCT>100<SUBACCTSEC>MARGIN</CLOSUREOPT>\
+            </INVTRANLIST>"
+            .parse()
+            .unwrap();
+        let [
+            InvestmentTransaction::BuyOption(buy),
+            InvestmentTransaction::SellOption(sell),
+            InvestmentTransaction::ClosureOption(closure),
+        ] = list.transactions.as_slice()
+        else {
+            panic!("unexpected transactions: {:?}", list.transactions);
+        };
+        assert_eq!(buy.buy_type, OptionBuyType::BuyToOpen);
+        assert_eq!(buy.shares_per_contract, 100);
+        assert_eq!(sell.related_type, Some(RelatedType::Spread));
+        assert!(closure.expired());
+    }
+
+    #[test]
+    fn parses_quantities_as_decimals() {
+        let list: InvestmentTransactionList = "<INVTRANLIST><DTSTART>20250901<DTEND>20250930\
+            <BUYSTOCK><INVBUY><INVTRAN><FITID>1<DTTRADE>20250902</INVTRAN>\
+            <SECID><UNIQUEID>037833100<UNIQUEIDTYPE>CUSIP</SECID><UNITS>+00000100.0000\
+            <UNITPRICE>00000000000150.2500<COMMISSION>4.95  <FEES>.05\
+            <TOTAL>-00000000015030.0000<SUBACCTSEC>CASH<SUBACCTFUND>CASH</INVBUY>\
+            <BUYTYPE>BUY</BUYSTOCK></INVTRANLIST>"
+            .parse()
+            .unwrap();
+        let [InvestmentTransaction::BuyStock(stock)] = list.transactions.as_slice() else {
+            panic!("unexpected transactions: {:?}", list.transactions);
+        };
+        let decimal = |s: &str| s.parse::<Decimal>().unwrap();
+        let buy = &stock.buy;
+        assert_eq!(buy.units, decimal("100"));
+        assert_eq!(buy.unit_price, decimal("150.25"));
+        assert_eq!(buy.commission, Some(decimal("4.95")));
+        assert_eq!(buy.fees, Some(decimal("0.05")));
+        assert_eq!(buy.total, decimal("-15030"));
+
+        #[cfg(feature = "rust_decimal")]
+        {
+            let cost = buy.units * buy.unit_price
+                + buy.commission.unwrap_or_default()
+                + buy.fees.unwrap_or_default();
+            assert_eq!(-cost, buy.total);
+        }
+    }
+
+    #[test]
+    fn parses_margin_interest_splits_and_journals() {
+        let list: InvestmentTransactionList = "<INVTRANLIST><DTSTART>20250901<DTEND>20250930\
+            <MARGININTEREST><INVTRAN><FITID>1<DTTRADE>20250930</INVTRAN><TOTAL>-4.10\
+            <SUBACCTFUND>MARGIN</MARGININTEREST>\
+            <SPLIT><INVTRAN><FITID>2<DTTRADE>20250910</INVTRAN>\
+            <SECID><UNIQUEID>67066G104<UNIQUEIDTYPE>CUSIP</SECID><SUBACCTSEC>CASH\
+            <OLDUNITS>10<NEWUNITS>100<NUMERATOR>10<DENOMINATOR>1</SPLIT>\
+            <JRNLSEC><INVTRAN><FITID>3<DTTRADE>20250911</INVTRAN>\
+            <SECID><UNIQUEID>67066G104<UNIQUEIDTYPE>CUSIP</SECID><SUBACCTTO>MARGIN\
+            <SUBACCTFROM>CASH<UNITS>100</JRNLSEC>\
+            <JRNLFUND><INVTRAN><FITID>4<DTTRADE>20250912</INVTRAN><SUBACCTTO>CASH\
+            <SUBACCTFROM>MARGIN<TOTAL>50</JRNLFUND>\
+            <SELLSTOCK><INVSELL><INVTRAN><FITID>5<DTTRADE>20250915</INVTRAN>\
+            <SECID><UNIQUEID>67066G104<UNIQUEIDTYPE>CUSIP</SECID><UNITS>-100<UNITPRICE>1.5\
+            <TOTAL>150<SUBACCTSEC>MARGIN<SUBACCTFUND>CASH</INVSELL><SELLTYPE>SELL</SELLSTOCK>\
+            </INVTRANLIST>"
+            .parse()
+            .unwrap();
+        let [
+            InvestmentTransaction::MarginInterest(interest),
+            InvestmentTransaction::Split(split),
+            InvestmentTransaction::JournalSecurity(journal_security),
+            InvestmentTransaction::JournalFund(journal_fund),
+            InvestmentTransaction::SellStock(sell),
+        ] = list.transactions.as_slice()
+        else {
+            panic!("unexpected transactions: {:?}", list.transactions);
+        };
+        assert_eq!(interest.fund_sub_account, SubAccount::Margin);
+        assert_eq!(split.new_units, "100".parse::<Decimal>().unwrap());
+        assert_eq!(journal_security.to, SubAccount::Margin);
+        assert_eq!(journal_fund.from, SubAccount::Margin);
+        assert_eq!(sell.sell_type, SellType::Sell);
+    }
+
+    #[test]
+    fn parses_bank_transactions() {
+        let list: InvestmentTransactionList = "<INVTRANLIST><DTSTART>20250901<DTEND>20250930\
+            <INVBANKTRAN><STMTTRN><TRNTYPE>DEP<DTPOSTED>20250930<TRNAMT>+00000000000000.2400\
+            <FITID>abc<NAME>INTEREST EARNED<EXTDNAME>Interest</STMTTRN><SUBACCTFUND>CASH\
+            </INVBANKTRAN></INVTRANLIST>"
+            .parse()
+            .unwrap();
+        let [InvestmentTransaction::BankTransaction(bank)] = list.transactions.as_slice() else {
+            panic!("unexpected transactions: {:?}", list.transactions);
+        };
+        assert_eq!(bank.transaction.name.as_deref(), Some("INTEREST EARNED"));
+        assert_eq!(
+            bank.transaction.extra.get("EXTDNAME").map(String::as_str),
+            Some("Interest")
+        );
+        assert_eq!(bank.fund_sub_account, SubAccount::Cash);
+    }
+
+    #[test]
+    fn parses_security_list() {
+        let list: SecurityList = "<SECLIST><STOCKINFO><SECINFO>\
+            <SECID><UNIQUEID>458140100<UNIQUEIDTYPE>CUSIP</SECID><SECNAME>INTEL CORP\
+            <TICKER>INTC<UNITPRICE>24.19</SECINFO><STOCKTYPE>COMMON</STOCKINFO>\
+            <OPTINFO><SECINFO><SECID><UNIQUEID>INTC251017C00025000<UNIQUEIDTYPE>TICKER</SECID>\
+            <SECNAME>INTC Oct 25 call</SECINFO><OPTTYPE>CALL<STRIKEPRICE>25\
+            <DTEXPIRE>20251017<SHPERCTRCT>100\
+            <SECID><UNIQUEID>458140100<UNIQUEIDTYPE>CUSIP</SECID></OPTINFO>\
+            <MFINFO><SECINFO><SECID><UNIQUEID>922908363<UNIQUEIDTYPE>CUSIP</SECID>\
+            <SECNAME>500 Index Fund<MEMO>Price as of close</SECINFO><MFTYPE>OPENEND</MFINFO>\
+            </SECLIST>"
+            .parse()
+            .unwrap();
+        assert_eq!(list.securities.len(), 3);
+        let intel = list.get(&SecurityId::new("458140100", "CUSIP")).unwrap();
+        assert_eq!(intel.info().ticker.as_deref(), Some("INTC"));
+        let Security::Option(option) = &list.securities[1] else {
+            panic!("expected an option");
+        };
+        assert_eq!(option.option_type, OptionType::Call);
+        assert_eq!(option.underlying.as_ref(), Some(&intel.info().id));
+        assert_eq!(
+            list.securities[2].info().memo.as_deref(),
+            Some("Price as of close")
+        );
+    }
+
+    #[test]
+    fn classifies_security_ids() {
+        let cusip = SecurityId::new("458140100", "CUSIP");
+        assert!(cusip.is_cusip());
+        assert!(!cusip.is_isin());
+        assert!(SecurityId::new("US4581401001", "isin").is_isin());
+        assert!(SecurityId::new("INTC", "TICKER").is_ticker());
+        assert_eq!(cusip.to_string(), "CUSIP:458140100");
+    }
+
+    #[test]
+    fn parses_positions() {
+        let list: PositionList = "<INVPOSLIST><POSMF><INVPOS>\
+            <SECID><UNIQUEID>922908363<UNIQUEIDTYPE>CUSIP</SECID><HELDINACCT>CASH\
+            <POSTYPE>LONG<UNITS>17.6<UNITPRICE>22.5<MKTVAL>396<DTPRICEASOF>20250930\
+            </INVPOS><REINVDIV>Y<REINVCG>N</POSMF><POSOPT><INVPOS>\
+            <SECID><UNIQUEID>INTC251017C00025000<UNIQUEIDTYPE>TICKER</SECID>\
+            <HELDINACCT>MARGIN<POSTYPE>SHORT<UNITS>-1<UNITPRICE>0.5<MKTVAL>-50\
+            <DTPRICEASOF>20250930</INVPOS><SECURED>COVERED</POSOPT></INVPOSLIST>"
+            .parse()
+            .unwrap();
+        let [Position::MutualFund(fund), Position::Option(option)] = list.positions.as_slice()
+        else {
+            panic!("unexpected positions: {:?}", list.positions);
+        };
+        assert_eq!(fund.reinvest_dividends, Some(true));
+        assert_eq!(fund.reinvest_capital_gains, Some(false));
+        assert_eq!(option.secured, Some(Secured::Covered));
+        assert_eq!(list.positions[1].info().position_type, PositionType::Short);
+    }
+
+    #[test]
+    fn parses_open_orders() {
+        let list: OpenOrderList = "<INVOOLIST><OOBUYSTOCK><OO><FITID>23321\
+            <SECID><UNIQUEID>666678578<UNIQUEIDTYPE>CUSIP</SECID><DTPLACED>20050624031505\
+            <UNITS>100<SUBACCT>CASH<DURATION>GOODTILCANCEL<RESTRICTION>NONE\
+            <LIMITPRICE>50.00</OO><BUYTYPE>BUY</OOBUYSTOCK>\
+            <SWITCHMF><OO><FITID>23322<SECID><UNIQUEID>922908363<UNIQUEIDTYPE>CUSIP</SECID>\
+            <DTPLACED>20050625<UNITS>1000<SUBACCT>CASH<DURATION>DAY<RESTRICTION>NONE</OO>\
+            <SECID><UNIQUEID>922908728<UNIQUEIDTYPE>CUSIP</SECID><UNITTYPE>CURRENCY\
+            <SWITCHALL>N</SWITCHMF></INVOOLIST>"
+            .parse()
+            .unwrap();
+        let [
+            OpenOrder::BuyStock(buy),
+            OpenOrder::SwitchMutualFund(switch),
+        ] = list.orders.as_slice()
+        else {
+            panic!("unexpected orders: {:?}", list.orders);
+        };
+        assert_eq!(buy.info.duration, OrderDuration::GoodTilCancel);
+        assert_eq!(
+            buy.info.limit_price,
+            Some("50.00".parse::<Decimal>().unwrap())
+        );
+        assert_eq!(switch.info.security.unique_id, "922908363");
+        assert_eq!(switch.target.unique_id, "922908728");
+        assert_eq!(switch.unit_type, UnitType::Currency);
+        assert_eq!(list.orders[1].info().id, "23322");
+    }
+}
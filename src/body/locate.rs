@@ -0,0 +1,124 @@
+//! Attributing deserialization errors to the aggregate that caused them.
+
+use serde::de::DeserializeOwned;
+
+use super::{
+    BankTransactionList, CreditCardStatementResponse, CreditCardStatementTransactionResponse,
+    PendingTransaction, PendingTransactionList, SignOnResponse, StatementResponse,
+    StatementTransactionResponse, Transaction,
+    investment::{
+        BankTransaction, BuyDebt, BuyMutualFund, BuyOption, BuyOther, BuyStock, ClosureOption,
+        Income, InvestmentStatementResponse, InvestmentTransactionList, JournalFund,
+        JournalSecurity, MarginInterest, OpenOrderList, PositionList, Reinvest, ReturnOfCapital,
+        SecurityList, SellDebt, SellMutualFund, SellOption, SellOther, SellStock, Split, Transfer,
+    },
+    normalize,
+};
+use crate::{Result, error::Error, tree::Element};
+
+type Check = fn(&Element) -> Result<()>;
+
+fn check<T: DeserializeOwned>(element: &Element) -> Result<()> {
+    let sgml = element.to_sgml();
+    sgmlish::from_fragment::<T>(normalize(&sgml)?)
+        .map(drop)
+        .map_err(Error::from)
+}
+
+fn checker(name: &str) -> Option<Check> {
+    let check: Check = match name {
+        "SONRS" => check::<SignOnResponse>,
+        "STMTTRNRS" => check::<StatementTransactionResponse>,
+        "STMTRS" => check::<StatementResponse>,
+        "CCSTMTTRNRS" => check::<CreditCardStatementTransactionResponse>,
+        "CCSTMTRS" => check::<CreditCardStatementResponse>,
+        "BANKTRANLIST" => check::<BankTransactionList>,
+        "STMTTRN" => check::<Transaction>,
+        "BANKTRANLISTP" => check::<PendingTransactionList>,
+        "STMTTRNP" => check::<PendingTransaction>,
+        "INVSTMTRS" => check::<InvestmentStatementResponse>,
+        "INVTRANLIST" => check::<InvestmentTransactionList>,
+        "INVPOSLIST" => check::<PositionList>,
+        "INVOOLIST" => check::<OpenOrderList>,
+        "SECLIST" => check::<SecurityList>,
+        "BUYDEBT" => check::<BuyDebt>,
+        "BUYMF" => check::<BuyMutualFund>,
+        "BUYOPT" => check::<BuyOption>,
+        "BUYOTHER" => check::<BuyOther>,
+        "BUYSTOCK" => check::<BuyStock>,
+        "CLOSUREOPT" => check::<ClosureOption>,
+        "INCOME" => check::<Income>,
+        "INVBANKTRAN" => check::<BankTransaction>,
+        "JRNLFUND" => check::<JournalFund>,
+        "JRNLSEC" => check::<JournalSecurity>,
+        "MARGININTEREST" => check::<MarginInterest>,
+        "REINVEST" => check::<Reinvest>,
+        "RETOFCAP" => check::<ReturnOfCapital>,
+        "SELLDEBT" => check::<SellDebt>,
+        "SELLMF" => check::<SellMutualFund>,
+        "SELLOPT" => check::<SellOption>,
+        "SELLOTHER" => check::<SellOther>,
+        "SELLSTOCK" => check::<SellStock>,
+        "SPLIT" => check::<Split>,
+        "TRANSFER" => check::<Transfer>,
+        _ => return None,
+    };
+    Some(check)
+}
+
+/// Finds the innermost typed aggregate below `element` that fails to deserialize on its own.
+fn locate_in(element: &Element, path: &mut Vec<String>) -> Option<(String, Error)> {
+    let mut seen = std::collections::HashMap::<&str, usize>::new();
+    for child in &element.children {
+        let index = seen.entry(child.name.as_str()).or_default();
+        let label = if element.children_named(&child.name).nth(1).is_some() {
+            format!("{}[{index}]", child.name)
+        } else {
+            child.name.clone()
+        };
+        *index += 1;
+
+        path.push(label);
+        let found = match checker(&child.name).map(|check| check(child)) {
+            Some(Err(err)) => {
+                Some(locate_in(child, path).unwrap_or_else(|| (path.join(" > "), err)))
+            }
+            Some(Ok(())) => None,
+            None => locate_in(child, path),
+        };
+        path.pop();
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+/// Wraps `err` with the path to the aggregate below `root` that caused it, if one can be found.
+pub(crate) fn locate(root: &Element, err: Error) -> Error {
+    let mut path = vec![root.name.clone()];
+    match locate_in(root, &mut path) {
+        Some((context, source)) => source.with_location(context),
+        None => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::body::Body;
+
+    #[test]
+    fn attributes_errors_to_transaction() {
+        let input = "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>USD<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1</STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<FITID>2</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+        let err = input.parse::<Body>().unwrap_err();
+        assert_eq!(
+            err.location(),
+            Some("OFX > BANKMSGSRSV1 > STMTTRNRS > STMTRS > BANKTRANLIST > STMTTRN[1]")
+        );
+    }
+}
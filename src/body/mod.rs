@@ -1,66 +1,27 @@
-use std::str::FromStr;
-
-use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
-use rust_decimal::Decimal;
-use serde::{self, Deserialize, Deserializer};
-use sgmlish::Parser;
-
-use crate::{Result, error::Error};
-
-fn deserialize_datetime<'de, D>(deserializer: D) -> std::result::Result<DateTime<Utc>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    use serde::de::Error as SerdeErr;
-    let s = String::deserialize(deserializer)?;
-
-    // Per 1.6 spec, 3.2.8.2:
-    // Note that times zones are specified by an offset and optionally, a time zone name. The offset
-    // defines the time zone. Valid offset values are in the range from –12 to +12 for whole number
-    // offsets. Formatting is +12.00 to -12.00 for fractional offsets, plus sign may be omitted.
-    let (dt_str, offset_str) = s.find('[').map_or_else(
-        || (&s[..], "0"),
-        |idx| (&s[..idx], &s[idx + 1..s.len() - 1]),
-    );
-    let offset_num = offset_str
-        .split(':')
-        .next()
-        .ok_or_else(|| SerdeErr::custom(format!("invalid timezone offset format: {offset_str}")))?
-        .parse::<f32>()
-        .map_err(serde::de::Error::custom)?;
-
-    if offset_num.abs() > 12.0 {
-        return Err(SerdeErr::custom(format!(
-            "timezone offset too large or small: {offset_num}"
-        )));
-    }
-    #[allow(clippy::cast_possible_truncation)]
-    let offset_secs = (offset_num * 3600.0).round() as i32;
-
-    let offset = FixedOffset::east_opt(offset_secs)
-        .ok_or_else(|| SerdeErr::custom(format!("invalid timezone offset: {offset_num}")))?;
-
-    let naive = match dt_str.chars().count() {
-        18 => NaiveDateTime::parse_from_str(dt_str, "%Y%m%d%H%M%S%.3f"),
-        14 => NaiveDateTime::parse_from_str(dt_str, "%Y%m%d%H%M%S"),
-        8 => Ok(NaiveDate::parse_from_str(dt_str, "%Y%m%d")
-            .map_err(|err| SerdeErr::custom(format!("unable to parse as date: {dt_str}: {err}")))?
-            .and_hms_opt(0, 0, 0)
-            .expect("couldn't set time to 00:00:00")),
-        _ => {
-            return Err(SerdeErr::custom(format!("invalid datetime: {dt_str}")));
-        }
-    };
+use std::{borrow::Cow, collections::BTreeMap, fmt, marker::PhantomData, str::FromStr};
 
-    naive
-        .map_err(|err| SerdeErr::custom(format!("unable to parse '{s}' as datetime: {err}")))?
-        .and_local_timezone(offset)
-        .single()
-        .map(|dt| dt.with_timezone(&Utc))
-        .ok_or_else(|| SerdeErr::custom(format!("ambiguous or invalid local datetime: {s}")))
-}
+use serde::{
+    self, Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, DeserializeOwned, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    ser::SerializeStruct,
+};
+use sgmlish::{Parser, SgmlEvent, SgmlFragment};
+
+use crate::{
+    ParserOptions, Result,
+    datetime::{DateTime, deserialize_datetime, deserialize_optional_datetime},
+    decimal::{self, Decimal},
+    error::Error,
+    tree::Element,
+    warning::Warning,
+};
+
+mod extra;
+pub mod investment;
+mod locate;
+use extra::CaptureExtra;
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct BankTransactionList {
     pub dtstart: Option<String>,
@@ -69,14 +30,76 @@ pub struct BankTransactionList {
     pub transactions: Vec<Transaction>,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+impl BankTransactionList {
+    /// Drops transactions whose FITID repeats an earlier one, returning their original indices.
+    ///
+    /// FITIDs must be unique (1.6 spec, 3.2.3), but some banks repeat them; this keeps the first
+    /// occurrence of each.
+    pub fn dedup_fitids(&mut self) -> Vec<usize> {
+        let mut seen = std::collections::HashSet::new();
+        let mut removed = Vec::new();
+        let mut index = 0;
+        self.transactions.retain(|transaction| {
+            let keep = seen.insert(transaction.id.clone());
+            if !keep {
+                removed.push(index);
+            }
+            index += 1;
+            keep
+        });
+        removed
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Transaction> {
+        self.transactions.iter()
+    }
+
+    /// Transactions with a negative amount, i.e. money out.
+    pub fn debits(&self) -> impl Iterator<Item = &Transaction> {
+        self.iter().filter(|transaction| transaction.is_debit())
+    }
+
+    /// Transactions with a zero or positive amount, i.e. money in.
+    pub fn credits(&self) -> impl Iterator<Item = &Transaction> {
+        self.iter().filter(|transaction| !transaction.is_debit())
+    }
+
+    pub fn by_type<'a>(
+        &'a self,
+        transaction_type: &'a TransactionType,
+    ) -> impl Iterator<Item = &'a Transaction> {
+        self.iter()
+            .filter(move |transaction| &transaction.transaction_type == transaction_type)
+    }
+}
+
+impl IntoIterator for BankTransactionList {
+    type Item = Transaction;
+    type IntoIter = std::vec::IntoIter<Transaction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.transactions.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a BankTransactionList {
+    type Item = &'a Transaction;
+    type IntoIter = std::slice::Iter<'a, Transaction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 #[serde(rename_all = "UPPERCASE")]
 pub struct Transaction {
     #[serde(rename = "TRNTYPE")]
     pub transaction_type: TransactionType,
     #[serde(rename = "DTPOSTED")]
     #[serde(deserialize_with = "deserialize_datetime")]
-    pub date_posted: DateTime<Utc>,
+    pub date_posted: DateTime,
     #[serde(rename = "TRNAMT")]
     pub amount: Decimal,
     #[serde(rename = "FITID")]
@@ -84,9 +107,140 @@ pub struct Transaction {
     pub name: Option<String>,
     pub memo: Option<String>,
     pub currency: Option<Currency>,
+    /// Child tags not otherwise modeled, keyed by tag name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl Transaction {
+    #[must_use]
+    pub fn new(
+        transaction_type: TransactionType,
+        date_posted: DateTime,
+        amount: Decimal,
+        id: impl Into<String>,
+    ) -> Self {
+        Self {
+            transaction_type,
+            date_posted,
+            amount,
+            id: id.into(),
+            name: None,
+            memo: None,
+            currency: None,
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// Whether the amount is negative. Compared textually, so this works with either `Decimal`
+    /// backend.
+    fn is_debit(&self) -> bool {
+        self.amount.to_string().starts_with('-')
+    }
+
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Builds the `<STMTTRN>` aggregate for writing the transaction back out.
+    pub(crate) fn to_element(&self) -> Element {
+        let mut children = vec![
+            Element::leaf("TRNTYPE", self.transaction_type.code()),
+            Element::leaf("DTPOSTED", crate::datetime::format(&self.date_posted)),
+            Element::leaf("TRNAMT", self.amount.to_string()),
+            Element::leaf("FITID", &self.id),
+        ];
+        children.extend(self.name.as_ref().map(|name| Element::leaf("NAME", name)));
+        children.extend(self.memo.as_ref().map(|memo| Element::leaf("MEMO", memo)));
+        children.extend(self.currency.as_ref().map(|currency| {
+            Element::aggregate(
+                "CURRENCY",
+                vec![
+                    Element::leaf("CURRATE", currency.rate.to_string()),
+                    Element::leaf("CURSYM", &currency.symbol),
+                ],
+            )
+        }));
+        Element::aggregate("STMTTRN", children)
+    }
+}
+
+/// Holds and other transactions that have not yet posted (OFX 2.1.1, 11.4.2.5).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct PendingTransactionList {
+    #[serde(rename = "DTASOF")]
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub date_as_of: DateTime,
+    #[serde(rename = "STMTTRNP", default)]
+    pub transactions: Vec<PendingTransaction>,
+}
+
+impl PendingTransactionList {
+    #[must_use]
+    pub fn new(date_as_of: DateTime) -> Self {
+        Self {
+            date_as_of,
+            transactions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(rename_all = "UPPERCASE")]
+pub struct PendingTransaction {
+    #[serde(rename = "TRNTYPE")]
+    pub transaction_type: TransactionType,
+    /// When the transaction was initiated.
+    #[serde(rename = "DTTRAN")]
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub date: DateTime,
+    /// When a hold will be released if the transaction has not posted.
+    #[serde(rename = "DTEXPIRE", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub expires: Option<DateTime>,
+    /// The expected amount, which may differ from the amount that eventually posts.
+    #[serde(rename = "TRNAMT")]
+    pub amount: Decimal,
+    #[serde(rename = "REFNUM")]
+    pub reference_number: Option<String>,
+    pub name: Option<String>,
+    pub memo: Option<String>,
+    pub currency: Option<Currency>,
+}
+
+impl PendingTransaction {
+    #[must_use]
+    pub fn new(transaction_type: TransactionType, date: DateTime, amount: Decimal) -> Self {
+        Self {
+            transaction_type,
+            date,
+            expires: None,
+            amount,
+            reference_number: None,
+            name: None,
+            memo: None,
+            currency: None,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum TransactionType {
     Credit,
@@ -116,7 +270,67 @@ pub enum TransactionType {
     Other,
 }
 
-#[derive(Debug, Deserialize, Eq, PartialEq)]
+/// The sign an amount is expected to have for a [`TransactionType`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    /// Money in, a positive amount.
+    Credit,
+    /// Money out, a negative amount.
+    Debit,
+    /// Depends on the sign of the amount.
+    Either,
+}
+
+impl TransactionType {
+    /// The expected sign of the amount, per the table in the 1.6 spec, 11.4.3.1.
+    ///
+    /// `INT`, `ATM`, `POS`, `XFER`, and `OTHER` may go either way.
+    #[must_use]
+    pub fn direction(&self) -> Direction {
+        match self {
+            Self::Credit | Self::Dividend | Self::Deposit | Self::DirectDeposit => {
+                Direction::Credit
+            }
+            Self::Debit
+            | Self::Fee
+            | Self::ServiceCharge
+            | Self::Check
+            | Self::Payment
+            | Self::Cash
+            | Self::DirectDebit
+            | Self::RepeatPayment => Direction::Debit,
+            Self::Interest | Self::Atm | Self::PointOfSale | Self::Transfer | Self::Other => {
+                Direction::Either
+            }
+        }
+    }
+
+    /// The code used on the wire, e.g. `SRVCHG`.
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Self::Credit => "CREDIT",
+            Self::Debit => "DEBIT",
+            Self::Interest => "INT",
+            Self::Dividend => "DIV",
+            Self::Fee => "FEE",
+            Self::ServiceCharge => "SRVCHG",
+            Self::Deposit => "DEP",
+            Self::Atm => "ATM",
+            Self::PointOfSale => "POS",
+            Self::Transfer => "XFER",
+            Self::Check => "CHECK",
+            Self::Payment => "PAYMENT",
+            Self::Cash => "CASH",
+            Self::DirectDeposit => "DIRECTDEP",
+            Self::DirectDebit => "DIRECTDEBIT",
+            Self::RepeatPayment => "REPEATPMT",
+            Self::Other => "OTHER",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct Currency {
     #[serde(rename = "CURRATE")]
     pub rate: Decimal,
@@ -124,13 +338,24 @@ pub struct Currency {
     pub symbol: String,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+impl Currency {
+    #[must_use]
+    pub fn new(rate: Decimal, symbol: impl Into<String>) -> Self {
+        Self {
+            rate,
+            symbol: symbol.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SignOnMessageResponse {
     #[serde(rename = "SONRS")]
     pub response: SignOnResponse,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct FinancialInstitution {
     #[serde(rename = "ORG")]
     pub organization: String,
@@ -138,22 +363,90 @@ pub struct FinancialInstitution {
     pub id: Option<String>,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+impl FinancialInstitution {
+    #[must_use]
+    pub fn new(organization: impl Into<String>) -> Self {
+        Self {
+            organization: organization.into(),
+            id: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct SignOnResponse {
     #[serde(rename = "STATUS")]
     pub status: Status,
     #[serde(rename = "DTSERVER")]
     #[serde(deserialize_with = "deserialize_datetime")]
-    pub server_date: DateTime<Utc>,
+    pub server_date: DateTime,
     #[serde(rename = "LANGUAGE")]
     pub language: Language,
     #[serde(rename = "FI")]
     pub financial_institution: Option<FinancialInstitution>,
+    /// A session cookie to echo back in the next `SONRQ`
+    #[serde(rename = "SESSCOOKIE")]
+    pub session_cookie: Option<String>,
+    /// Child tags not otherwise modeled, keyed by tag name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
 }
 
-#[derive(Debug, PartialEq)]
+impl SignOnResponse {
+    #[must_use]
+    pub fn new(status: Status, server_date: DateTime) -> Self {
+        Self {
+            status,
+            server_date,
+            language: Language::default(),
+            financial_institution: None,
+            session_cookie: None,
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Language(isolang::Language);
 
+impl Language {
+    #[must_use]
+    pub fn new(language: isolang::Language) -> Self {
+        Self(language)
+    }
+
+    #[must_use]
+    pub fn as_isolang(&self) -> isolang::Language {
+        self.0
+    }
+
+    /// The uppercase ISO-639-3 code used on the wire, e.g. `ENG`.
+    #[must_use]
+    pub fn code(&self) -> String {
+        self.0.to_639_3().to_uppercase()
+    }
+}
+
+/// Writes the [`Language::code`], as the spec requires for `<LANGUAGE>`.
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.code())
+    }
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Self(isolang::Language::Eng)
+    }
+}
+
+impl Serialize for Language {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        s.serialize_str(self.0.to_639_3())
+    }
+}
+
 impl<'de> serde::Deserialize<'de> for Language {
     fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
         let lang = String::deserialize(d)?.trim().to_lowercase();
@@ -162,23 +455,21 @@ impl<'de> serde::Deserialize<'de> for Language {
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct CreditCardMessageResponse {
     #[serde(rename = "CCSTMTTRNRS")]
     pub transaction_response: CreditCardStatementTransactionResponse,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
-pub struct CreditCardStatementTransactionResponse {
-    #[serde(rename = "TRNUID")]
-    pub transaction_id: String,
-    #[serde(rename = "STATUS")]
-    pub status: Status,
-    #[serde(rename = "CCSTMTRS")]
-    pub statement: CreditCardStatementResponse,
+pub type CreditCardStatementTransactionResponse = TransactionResponse<CreditCardStatementResponse>;
+
+impl Wrapped for CreditCardStatementResponse {
+    const TAG: &'static str = "CCSTMTRS";
+    const FIELDS: &'static [&'static str] = &["TRNUID", "STATUS", "CLTCOOKIE", Self::TAG];
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct CreditCardStatementResponse {
     #[serde(rename = "CURDEF")]
     pub currency: String,
@@ -186,52 +477,389 @@ pub struct CreditCardStatementResponse {
     pub account: Account,
     #[serde(rename = "BANKTRANLIST")]
     pub bank_transactions: Option<BankTransactionList>,
+    /// Pending transactions, from OFX 2.1.1 and later servers.
+    #[serde(rename = "BANKTRANLISTP")]
+    pub pending_transactions: Option<PendingTransactionList>,
     #[serde(rename = "LEDGERBAL")]
     pub ledger_balance: Balance,
     #[serde(rename = "AVAILBAL")]
     pub available_balance: Option<Balance>,
+    /// Additional balances from `<BALLIST>`, e.g. interest paid year to date.
+    #[serde(
+        rename = "BALLIST",
+        default,
+        deserialize_with = "deserialize_balance_list",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub balances: Vec<NamedBalance>,
+    #[serde(rename = "REWARDINFO")]
+    pub reward_info: Option<RewardInfo>,
+    /// Free-form marketing text, which some banks use for account notices.
+    #[serde(rename = "MKTGINFO")]
+    pub marketing_info: Option<String>,
+    /// Child tags not otherwise modeled, keyed by tag name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl CreditCardStatementResponse {
+    #[must_use]
+    pub fn new(currency: impl Into<String>, account: Account, ledger_balance: Balance) -> Self {
+        Self {
+            currency: currency.into(),
+            account,
+            bank_transactions: None,
+            pending_transactions: None,
+            ledger_balance,
+            available_balance: None,
+            balances: Vec::new(),
+            reward_info: None,
+            marketing_info: None,
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+/// A credit card rewards program balance, e.g. cashback or points (1.6 spec, 11.4.3.2).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct RewardInfo {
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "REWARDBAL")]
+    pub balance: Decimal,
+    /// Rewards earned during this statement period.
+    #[serde(rename = "REWARDEARNED")]
+    pub earned: Option<Decimal>,
+}
+
+impl RewardInfo {
+    #[must_use]
+    pub fn new(name: impl Into<String>, balance: Decimal) -> Self {
+        Self {
+            name: name.into(),
+            balance,
+            earned: None,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct Account {
     #[serde(rename = "ACCTID")]
-    pub id: String,
+    pub id: AccountId,
+}
+
+impl Account {
+    #[must_use]
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: AccountId::new(id),
+        }
+    }
+}
+
+/// An account number, masked to its last four characters by `Display` and `Debug`.
+///
+/// Use [`AccountId::expose`] when the full value is actually needed. Serialization always writes
+/// the full value.
+#[derive(Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct AccountId(String);
+
+impl AccountId {
+    #[must_use]
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// The full, unmasked account number.
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for AccountId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<String> for AccountId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.0.chars().count();
+        // Short IDs are masked entirely, since the last four would be the whole thing
+        let shown = if len > 4 { len - 4 } else { len };
+        let last: String = self.0.chars().skip(shown).collect();
+        write!(f, "****{last}")
+    }
+}
+
+impl fmt::Debug for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AccountId").field(&self.to_string()).finish()
+    }
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct Status {
     #[serde(rename = "CODE")]
     pub code: u32,
     #[serde(rename = "SEVERITY")]
     pub severity: String,
+    /// The server's explanation, if it sent one
+    #[serde(rename = "MESSAGE")]
+    pub message: Option<String>,
+}
+
+impl Status {
+    #[must_use]
+    pub fn new(code: u32, severity: impl Into<String>) -> Self {
+        Self {
+            code,
+            severity: severity.into(),
+            message: None,
+        }
+    }
+
+    /// Whether this is the `0` success code.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.code == 0
+    }
+}
+
+impl Default for Status {
+    // Per the 1.6 spec, code 0 is "Success" with severity INFO
+    fn default() -> Self {
+        Self::new(0, "INFO")
+    }
+}
+
+/// A response that arrives inside a [`TransactionResponse`], such as a statement.
+pub trait Wrapped {
+    /// The response's tag, e.g. `STMTRS`.
+    const TAG: &'static str;
+    /// Every tag of the wrapper: `TRNUID`, `STATUS`, `CLTCOOKIE`, and [`Wrapped::TAG`].
+    const FIELDS: &'static [&'static str];
+}
+
+/// The transaction wrapper (`<...TRNRS>`) around a response, e.g. `<STMTTRNRS>` around
+/// `<STMTRS>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactionResponse<T> {
+    /// The `TRNUID` of the request this answers
+    pub transaction_id: String,
+    pub status: Status,
+    /// The `CLTCOOKIE` echoed from the request, if the client sent one
+    pub client_cookie: Option<String>,
+    /// Absent when `status` reports an error.
+    pub statement: Option<T>,
+}
+
+impl<T> TransactionResponse<T> {
+    #[must_use]
+    pub fn new(transaction_id: impl Into<String>, statement: T) -> Self {
+        Self {
+            transaction_id: transaction_id.into(),
+            status: Status::default(),
+            client_cookie: None,
+            statement: Some(statement),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Wrapped> Deserialize<'de> for TransactionResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_struct("TRNRS", T::FIELDS, TransactionResponseVisitor(PhantomData))
+    }
+}
+
+struct TransactionResponseVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de> + Wrapped> Visitor<'de> for TransactionResponseVisitor<T> {
+    type Value = TransactionResponse<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a transaction wrapper around {}", T::TAG)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<Self::Value, A::Error> {
+        let (mut transaction_id, mut status, mut client_cookie, mut statement) =
+            (None, None, None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "TRNUID" => transaction_id = Some(map.next_value()?),
+                "STATUS" => status = Some(map.next_value()?),
+                "CLTCOOKIE" => client_cookie = Some(map.next_value()?),
+                // Optional, since other formats write a missing statement as null
+                tag if tag == T::TAG => statement = map.next_value::<Option<T>>()?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(TransactionResponse {
+            transaction_id: transaction_id.ok_or_else(|| de::Error::missing_field("TRNUID"))?,
+            status: status.ok_or_else(|| de::Error::missing_field("STATUS"))?,
+            client_cookie,
+            statement,
+        })
+    }
+}
+
+impl<T: Serialize + Wrapped> Serialize for TransactionResponse<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TRNRS", 4)?;
+        state.serialize_field("TRNUID", &self.transaction_id)?;
+        state.serialize_field("STATUS", &self.status)?;
+        if let Some(cookie) = &self.client_cookie {
+            state.serialize_field("CLTCOOKIE", cookie)?;
+        } else {
+            state.skip_field("CLTCOOKIE")?;
+        }
+        state.serialize_field(T::TAG, &self.statement)?;
+        state.end()
+    }
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct Balance {
     #[serde(rename = "BALAMT")]
     pub amount: String,
     #[serde(rename = "DTASOF")]
     #[serde(deserialize_with = "deserialize_datetime")]
-    pub date: DateTime<Utc>,
+    pub date: DateTime,
+}
+
+impl Balance {
+    #[must_use]
+    pub fn new(amount: impl Into<String>, date: DateTime) -> Self {
+        Self {
+            amount: amount.into(),
+            date,
+        }
+    }
+}
+
+/// A named balance from a `<BALLIST>` (1.6 spec, 3.1.4).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct NamedBalance {
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "DESC")]
+    pub description: String,
+    #[serde(rename = "BALTYPE")]
+    pub balance_type: BalanceType,
+    #[serde(rename = "VALUE")]
+    pub value: Decimal,
+    #[serde(rename = "DTASOF", default)]
+    #[serde(deserialize_with = "deserialize_optional_datetime")]
+    pub date: Option<DateTime>,
+    #[serde(rename = "CURRENCY")]
+    pub currency: Option<Currency>,
+}
+
+impl NamedBalance {
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        balance_type: BalanceType,
+        value: Decimal,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            balance_type,
+            value,
+            date: None,
+            currency: None,
+        }
+    }
+}
+
+/// How the `VALUE` of a [`NamedBalance`] should be interpreted.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum BalanceType {
+    Dollar,
+    Percent,
+    Number,
+}
+
+fn deserialize_balance_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<NamedBalance>, D::Error> {
+    struct BalanceListVisitor;
+
+    impl<'de> Visitor<'de> for BalanceListVisitor {
+        type Value = Vec<NamedBalance>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a BALLIST aggregate")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(
+            self,
+            mut map: A,
+        ) -> std::result::Result<Self::Value, A::Error> {
+            let mut balances = Vec::new();
+            while let Some(key) = map.next_key::<String>()? {
+                if key == "BAL" {
+                    balances.extend(map.next_value::<Vec<NamedBalance>>()?);
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+            Ok(balances)
+        }
+
+        // As serialized to other formats, a plain list
+        fn visit_seq<A: SeqAccess<'de>>(
+            self,
+            mut seq: A,
+        ) -> std::result::Result<Self::Value, A::Error> {
+            let mut balances = Vec::new();
+            while let Some(balance) = seq.next_element()? {
+                balances.push(balance);
+            }
+            Ok(balances)
+        }
+    }
+
+    deserializer.deserialize_struct("BALLIST", &["BAL"], BalanceListVisitor)
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct BankMessageResponse {
     #[serde(rename = "STMTTRNRS")]
     pub transaction_response: StatementTransactionResponse,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
-pub struct StatementTransactionResponse {
-    #[serde(rename = "TRNUID")]
-    pub transaction_id: String,
-    #[serde(rename = "STATUS")]
-    pub status: Status,
-    #[serde(rename = "STMTRS")]
-    pub statement: StatementResponse,
+pub type StatementTransactionResponse = TransactionResponse<StatementResponse>;
+
+impl Wrapped for StatementResponse {
+    const TAG: &'static str = "STMTRS";
+    const FIELDS: &'static [&'static str] = &["TRNUID", "STATUS", "CLTCOOKIE", Self::TAG];
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct StatementResponse {
     #[serde(rename = "CURDEF")]
     pub currency: String,
@@ -239,24 +867,74 @@ pub struct StatementResponse {
     pub account: Option<BankAccount>,
     #[serde(rename = "BANKTRANLIST")]
     pub bank_transactions: Option<BankTransactionList>,
+    /// Pending transactions, from OFX 2.1.1 and later servers.
+    #[serde(rename = "BANKTRANLISTP")]
+    pub pending_transactions: Option<PendingTransactionList>,
     #[serde(rename = "LEDGERBAL")]
     pub ledger_balance: Option<Balance>,
     #[serde(rename = "AVAILBAL")]
     pub available_balance: Option<Balance>,
+    /// Additional balances from `<BALLIST>`, e.g. interest paid year to date.
+    #[serde(
+        rename = "BALLIST",
+        default,
+        deserialize_with = "deserialize_balance_list",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub balances: Vec<NamedBalance>,
+    /// Free-form marketing text, which some banks use for account notices.
+    #[serde(rename = "MKTGINFO")]
+    pub marketing_info: Option<String>,
+    /// Child tags not otherwise modeled, keyed by tag name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl StatementResponse {
+    #[must_use]
+    pub fn new(currency: impl Into<String>) -> Self {
+        Self {
+            currency: currency.into(),
+            account: None,
+            bank_transactions: None,
+            pending_transactions: None,
+            ledger_balance: None,
+            available_balance: None,
+            balances: Vec::new(),
+            marketing_info: None,
+            extra: BTreeMap::new(),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct BankAccount {
     #[serde(rename = "BANKID")]
     pub bank_id: String,
     #[serde(rename = "ACCTID")]
-    pub id: String,
+    pub id: AccountId,
     #[serde(rename = "ACCTTYPE")]
     pub account_type: AccountType,
 }
 
+impl BankAccount {
+    #[must_use]
+    pub fn new(
+        bank_id: impl Into<String>,
+        id: impl Into<String>,
+        account_type: AccountType,
+    ) -> Self {
+        Self {
+            bank_id: bank_id.into(),
+            id: AccountId::new(id),
+            account_type,
+        }
+    }
+}
+
 // 11.3.1.2 Account Types for <ACCTTYPE> and <ACCTTYPE2> Elements
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum AccountType {
     Checking,
@@ -266,7 +944,20 @@ pub enum AccountType {
     Cma,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+impl AccountType {
+    /// The code used on the wire, e.g. `CHECKING`.
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Self::Checking => "CHECKING",
+            Self::Savings => "SAVINGS",
+            Self::Moneymrkt => "MONEYMRKT",
+            Self::Creditline => "CREDITLINE",
+            Self::Cma => "CMA",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct Body {
     #[serde(rename = "SIGNONMSGSRSV1")]
     pub sign_on: Option<SignOnMessageResponse>,
@@ -274,23 +965,290 @@ pub struct Body {
     pub credit_card: Option<CreditCardMessageResponse>,
     #[serde(rename = "BANKMSGSRSV1")]
     pub bank: Option<BankMessageResponse>,
+    #[serde(rename = "INVSTMTMSGSRSV1")]
+    pub investment: Option<investment::InvestmentMessageResponse>,
+    #[serde(rename = "SECLISTMSGSRSV1")]
+    pub securities: Option<investment::SecurityListMessageResponse>,
+    /// Message sets not otherwise modeled, such as `BILLPAYMSGSRSV1`, keyed by tag name, with
+    /// their contents as SGML.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub other: BTreeMap<String, String>,
+}
+
+fn check_limits(fragment: &SgmlFragment<'_>, options: &ParserOptions) -> Result<()> {
+    let mut depth = 0_usize;
+    let mut transactions = 0_usize;
+    // The depth of the open `INVTRANLIST`, whose children other than its dates are transactions
+    let mut investment_list = None;
+    for event in fragment {
+        match event {
+            SgmlEvent::OpenStartTag { name } => {
+                depth += 1;
+                ParserOptions::check("nesting depth", depth, options.max_depth)?;
+                let transaction = match investment_list {
+                    Some(list) => depth == list + 1 && !matches!(&**name, "DTSTART" | "DTEND"),
+                    None => *name == "STMTTRN" || *name == "STMTTRNP",
+                };
+                if name.ends_with("TRANLIST") || name.ends_with("TRANLISTP") {
+                    transactions = 0;
+                    investment_list = (*name == "INVTRANLIST").then_some(depth);
+                } else if transaction {
+                    transactions += 1;
+                    ParserOptions::check(
+                        "transactions per list",
+                        transactions,
+                        options.max_transactions,
+                    )?;
+                }
+            }
+            SgmlEvent::EndTag { .. } | SgmlEvent::XmlCloseEmptyElement => {
+                depth = depth.saturating_sub(1);
+                if investment_list.is_some_and(|list| depth < list) {
+                    investment_list = None;
+                }
+            }
+            SgmlEvent::Character(text) => {
+                ParserOptions::check("value length", text.len(), options.max_value_len)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses SGML with OFX entities expanded and omitted end tags filled in, without checking
+/// limits.
+pub(crate) fn normalize(s: &str) -> Result<SgmlFragment<'_>> {
+    let sgml = Parser::builder()
+        .expand_entities(|entity| match entity {
+            "lt" => Some("<"),
+            "gt" => Some(">"),
+            "amp" => Some("&"),
+            "nbsp" => Some(" "),
+            _ => None,
+        })
+        .parse(s)?;
+    Ok(sgmlish::transforms::normalize_end_tags(sgml)?)
+}
+
+/// Parses SGML with OFX entities expanded and omitted end tags filled in.
+///
+/// Only the four fixed entities above are expanded, each to a single character, so expansion
+/// cannot blow up the input. Documents that exceed the limits in `options` are rejected.
+pub(crate) fn normalized_fragment<'a>(
+    s: &'a str,
+    options: &ParserOptions,
+) -> Result<SgmlFragment<'a>> {
+    let sgml = normalize(s)?;
+    check_limits(&sgml, options)?;
+    Ok(sgml)
+}
+
+/// Parses `s` as `T`, also returning its element tree.
+fn parse_tree<T: DeserializeOwned + CaptureExtra>(
+    s: &str,
+    options: &ParserOptions,
+) -> Result<(T, Element)> {
+    let sgml = normalized_fragment(s, options)?;
+    let root = Element::from_fragment(&sgml)?;
+    let mut value =
+        sgmlish::from_fragment::<T>(sgml).map_err(|err| locate::locate(&root, err.into()))?;
+    value.capture_extra(&root);
+    Ok((value, root))
+}
+
+fn parse_fragment<T: DeserializeOwned + CaptureExtra>(
+    s: &str,
+    options: &ParserOptions,
+) -> Result<T> {
+    parse_tree(s, options).map(|(value, _)| value)
+}
+
+macro_rules! impl_from_str {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl FromStr for $ty {
+                type Err = Error;
+
+                fn from_str(s: &str) -> Result<Self> {
+                    parse_fragment(s, &ParserOptions::default())
+                }
+            }
+        )+
+    };
+}
+
+impl_from_str!(
+    StatementTransactionResponse,
+    StatementResponse,
+    CreditCardStatementResponse,
+    BankTransactionList,
+    Transaction,
+    PendingTransactionList,
+    investment::InvestmentStatementResponse,
+    investment::InvestmentTransactionList,
+    investment::OpenOrderList,
+    investment::PositionList,
+    investment::SecurityList,
+);
+
+/// Rewrites message set wrappers of any version (e.g. `<BANKMSGSRSV2>`) to the V1 tags that
+/// [`Body`] deserializes, since the response types within them are the same.
+fn normalize_message_set_versions<'a>(s: &'a str, warnings: &mut Vec<Warning>) -> Cow<'a, str> {
+    const MARKER: &str = "MSGSRSV";
+    if !s.contains(MARKER) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(idx) = rest.find(MARKER) {
+        let end = idx + MARKER.len();
+        let digits = rest[end..].bytes().take_while(u8::is_ascii_digit).count();
+        out.push_str(&rest[..end]);
+        if digits > 0 && rest[end + digits..].starts_with('>') {
+            let version = &rest[end..end + digits];
+            if version != "1" {
+                let name_start = rest[..idx].rfind(['<', '/']).map_or(0, |i| i + 1);
+                let fixup =
+                    Warning::Fixup(format!("treated {}{version} as V1", &rest[name_start..end]));
+                if !warnings.contains(&fixup) {
+                    warnings.push(fixup);
+                }
+            }
+            out.push('1');
+            rest = &rest[end + digits..];
+        } else {
+            rest = &rest[end..];
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Tags whose values are amounts, quantities, prices, or rates.
+const DECIMAL_TAGS: &[&str] = &[
+    "ACCRDINT",
+    "AVAILCASH",
+    "AVGCOSTBASIS",
+    "BALAMT",
+    "BUYPOWER",
+    "COMMISSION",
+    "COUPONRT",
+    "CURRATE",
+    "DENOMINATOR",
+    "FEES",
+    "FRACCASH",
+    "GAIN",
+    "LIMITPRICE",
+    "LOAD",
+    "MARGINBALANCE",
+    "MARKDOWN",
+    "MARKUP",
+    "MINUNITS",
+    "MKTVAL",
+    "NEWUNITS",
+    "NUMERATOR",
+    "OLDUNITS",
+    "PARVALUE",
+    "REWARDBAL",
+    "REWARDEARNED",
+    "SHORTBALANCE",
+    "STOPPRICE",
+    "STRIKEPRICE",
+    "TAXES",
+    "TOTAL",
+    "TRNAMT",
+    "UNITPRICE",
+    "UNITS",
+    "UNITSSTREET",
+    "UNITSUSER",
+    "VALUE",
+    "WITHHOLDING",
+    "YIELD",
+    "YIELDTOMAT",
+];
+
+/// Rewrites amounts that some banks write with a decimal comma, thousands separators, or
+/// parentheses for negatives, e.g. `<TRNAMT>(1,234.56)`, to the form the spec requires, so one
+/// bad value does not fail the whole document.
+fn normalize_amounts<'a>(s: &'a str, warnings: &mut Vec<Warning>) -> Cow<'a, str> {
+    let mut out = String::new();
+    let mut copied = 0;
+    for (idx, _) in s.match_indices('<') {
+        let Some((tag, _)) = s[idx + 1..].split_once('>') else {
+            break;
+        };
+        if !DECIMAL_TAGS.contains(&tag) {
+            continue;
+        }
+        let start = idx + tag.len() + 2;
+        let end = s[start..].find('<').map_or(s.len(), |i| start + i);
+        let value = s[start..end].trim();
+        let Some(fixed) = decimal::lenient(value) else {
+            continue;
+        };
+        let fixup = Warning::Fixup(format!("read {tag} `{value}` as `{fixed}`"));
+        if !warnings.contains(&fixup) {
+            warnings.push(fixup);
+        }
+        out.push_str(&s[copied..start]);
+        out.push_str(&fixed);
+        copied = end;
+    }
+    if copied == 0 {
+        return Cow::Borrowed(s);
+    }
+    out.push_str(&s[copied..]);
+    Cow::Owned(out)
+}
+
+impl Body {
+    pub(crate) fn parse_with_options(
+        s: &str,
+        options: &ParserOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<Self> {
+        Self::parse_tree_with_options(s, options, warnings).map(|(body, _)| body)
+    }
+
+    /// Parses like [`Body::parse_with_options`], also returning the element tree.
+    ///
+    /// The tree is built from `s` as written, before the message set and lenient amount fixups
+    /// the typed layer needs, so it holds the institution's original tags and text.
+    pub(crate) fn parse_tree_with_options(
+        s: &str,
+        options: &ParserOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<(Self, Element)> {
+        let versioned = normalize_message_set_versions(s, warnings);
+        let fixed = if options.lenient {
+            normalize_amounts(&versioned, warnings)
+        } else {
+            Cow::Borrowed(versioned.as_ref())
+        };
+        let rewritten = matches!(versioned, Cow::Owned(_)) || matches!(fixed, Cow::Owned(_));
+        let (body, root) = parse_tree(&fixed, options)?;
+        extra::warn_unknown(&root, warnings);
+        let root = if rewritten {
+            Self::parse_element(s, options)?
+        } else {
+            root
+        };
+        Ok((body, root))
+    }
+
+    /// Parses only the element tree, with tags and text as written.
+    pub(crate) fn parse_element(s: &str, options: &ParserOptions) -> Result<Element> {
+        Element::from_fragment(&normalized_fragment(s, options)?)
+    }
 }
 
 impl FromStr for Body {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let sgml = Parser::builder()
-            .expand_entities(|entity| match entity {
-                "lt" => Some("<"),
-                "gt" => Some(">"),
-                "amp" => Some("&"),
-                "nbsp" => Some(" "),
-                _ => None,
-            })
-            .parse(s)?;
-        let sgml = sgmlish::transforms::normalize_end_tags(sgml)?;
-        Ok(sgmlish::from_fragment::<Body>(sgml)?)
+        Self::parse_with_options(s, &ParserOptions::default(), &mut Vec::new())
     }
 }
 
@@ -298,85 +1256,247 @@ impl FromStr for Body {
 mod tests {
     use super::*;
 
-    use chrono::{TimeZone, Timelike, Utc};
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn rejects_pathological_input() {
+        let max_depth = ParserOptions::default().max_depth;
+        let deep = "<A>".repeat(max_depth + 1) + &"</A>".repeat(max_depth + 1);
+        let err = deep.parse::<Body>().unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::LimitExceeded {
+                limit: "nesting depth",
+                ..
+            }
+        ));
 
-    use serde::de::IntoDeserializer;
-    use serde::de::value::{Error as SerdeError, StrDeserializer};
+        let max_value_len = ParserOptions::default().max_value_len;
+        let long = format!("<STMTTRN><MEMO>{}</STMTTRN>", "x".repeat(max_value_len + 1));
+        let err = long.parse::<Transaction>().unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::LimitExceeded {
+                limit: "value length",
+                ..
+            }
+        ));
+    }
 
     #[test]
-    fn test_parse_date_spec_example() {
-        // Per 1.6 spec, 3.2.8.1
-        // There is one format for representing dates, times, and time zones. The complete form is:
-        // YYYYMMDDHHMMSS.XXX [gmt offset[:tz name]]
-        //
-        // 3.2.8.2
-        // For example, “19961005132200.124[-5:EST]”
-        // This is the same as 6:22 p.m. Greenwich Mean Time (GMT).
-
-        let examples = [
-            (
-                "19961005132200.124[-5:EST]",
-                Utc.with_ymd_and_hms(1996, 10, 5, 18, 22, 0)
-                    .unwrap()
-                    .with_nanosecond(124_000_000)
-                    .unwrap(),
-            ),
-            (
-                "19961005132200.124[-5]",
-                Utc.with_ymd_and_hms(1996, 10, 5, 18, 22, 0)
-                    .unwrap()
-                    .with_nanosecond(124_000_000)
-                    .unwrap(),
-            ),
-            (
-                "19961005132200.124",
-                Utc.with_ymd_and_hms(1996, 10, 5, 13, 22, 0)
-                    .unwrap()
-                    .with_nanosecond(124_000_000)
-                    .unwrap(),
-            ),
-            (
-                "19961005132200",
-                Utc.with_ymd_and_hms(1996, 10, 5, 13, 22, 0).unwrap(),
-            ),
-            (
-                "19961005",
-                Utc.with_ymd_and_hms(1996, 10, 5, 0, 0, 0).unwrap(),
+    fn limits_investment_transactions() {
+        let buy = "<BUYSTOCK><INVBUY><INVTRAN><FITID>1<DTTRADE>20250902</INVTRAN>\
+            <SECID><UNIQUEID>037833100<UNIQUEIDTYPE>CUSIP</SECID><UNITS>1<UNITPRICE>1\
+            <TOTAL>-1<SUBACCTSEC>CASH<SUBACCTFUND>CASH</INVBUY><BUYTYPE>BUY</BUYSTOCK>";
+        let bank = "<INVBANKTRAN><STMTTRN><TRNTYPE>DEP<DTPOSTED>20250930<TRNAMT>1<FITID>2\
+            </STMTTRN><SUBACCTFUND>CASH</INVBANKTRAN>";
+        let list =
+            format!("<INVTRANLIST><DTSTART>20250901<DTEND>20250930{buy}{bank}</INVTRANLIST>");
+        let limited = |max_transactions| ParserOptions {
+            max_transactions,
+            ..ParserOptions::default()
+        };
+        assert!(normalized_fragment(&list, &limited(2)).is_ok());
+        let err = normalized_fragment(&list, &limited(1)).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::LimitExceeded {
+                limit: "transactions per list",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parses_fragments() {
+        let transaction: Transaction = "<STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20250901\
+            <TRNAMT>12.50<FITID>abc<MEMO>Refund</STMTTRN>"
+            .parse()
+            .unwrap();
+        assert_eq!(transaction.transaction_type, TransactionType::Credit);
+        assert_eq!(transaction.memo.as_deref(), Some("Refund"));
+
+        let statement: StatementResponse = "<STMTRS><CURDEF>USD<BANKTRANLIST>\
+            <DTSTART>20250901<DTEND>20250930<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250902\
+            <TRNAMT>-1<FITID>def</STMTTRN></BANKTRANLIST></STMTRS>"
+            .parse()
+            .unwrap();
+        let list = statement.bank_transactions.unwrap();
+        assert_eq!(list.dtstart.as_deref(), Some("20250901"));
+        assert_eq!(list.transactions.len(), 1);
+    }
+
+    #[test]
+    fn parses_balance_list_and_marketing_info() {
+        let statement: StatementResponse = "<STMTRS><CURDEF>USD<BALLIST>\
+            <BAL><NAME>INTYTD<DESC>Interest paid YTD<BALTYPE>DOLLAR<VALUE>12.34\
+            <DTASOF>20250930</BAL><BAL><NAME>APY<DESC>Annual yield<BALTYPE>PERCENT\
+            <VALUE>4.1</BAL></BALLIST><MKTGINFO>New rates apply</STMTRS>"
+            .parse()
+            .unwrap();
+        assert_eq!(statement.balances.len(), 2);
+        assert_eq!(statement.balances[0].name, "INTYTD");
+        assert!(statement.balances[0].date.is_some());
+        assert_eq!(statement.balances[1].balance_type, BalanceType::Percent);
+        assert!(statement.balances[1].date.is_none());
+        assert_eq!(statement.marketing_info.as_deref(), Some("New rates apply"));
+        assert!(statement.extra.is_empty());
+    }
+
+    #[test]
+    fn parses_pending_transactions() {
+        let statement: StatementResponse = "<STMTRS><CURDEF>USD<BANKTRANLISTP>\
+            <DTASOF>20250930<STMTTRNP><TRNTYPE>POS<DTTRAN>20250929<DTEXPIRE>20251006\
+            <TRNAMT>-42.10<NAME>GAS STATION</STMTTRNP><STMTTRNP><TRNTYPE>DEBIT\
+            <DTTRAN>20250930<TRNAMT>-5</STMTTRNP></BANKTRANLISTP></STMTRS>"
+            .parse()
+            .unwrap();
+        let pending = statement.pending_transactions.unwrap().transactions;
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].transaction_type, TransactionType::PointOfSale);
+        assert!(pending[0].expires.is_some());
+        assert_eq!(pending[0].name.as_deref(), Some("GAS STATION"));
+        assert!(pending[1].expires.is_none());
+    }
+
+    #[test]
+    fn parses_reward_info() {
+        let statement: CreditCardStatementResponse = "<CCSTMTRS><CURDEF>USD\
+            <CCACCTFROM><ACCTID>4111111111111111</CCACCTFROM>\
+            <LEDGERBAL><BALAMT>-100<DTASOF>20250930</LEDGERBAL>\
+            <REWARDINFO><NAME>Cash back<REWARDBAL>52.17<REWARDEARNED>3.05</REWARDINFO>\
+            </CCSTMTRS>"
+            .parse()
+            .unwrap();
+        let rewards = statement.reward_info.unwrap();
+        assert_eq!(rewards.name, "Cash back");
+        assert_eq!(rewards.balance, "52.17".parse::<Decimal>().unwrap());
+        assert_eq!(rewards.earned, Some("3.05".parse::<Decimal>().unwrap()));
+    }
+
+    #[test]
+    fn accepts_newer_message_set_versions() {
+        assert_eq!(
+            normalize_message_set_versions(
+                "<BANKMSGSRSV2><STMTTRNRS></BANKMSGSRSV2>",
+                &mut Vec::new()
             ),
-        ];
+            "<BANKMSGSRSV1><STMTTRNRS></BANKMSGSRSV1>"
+        );
+        let body: Body = "<OFX><SIGNONMSGSRSV12><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <DTSERVER>20250901<LANGUAGE>ENG</SONRS></SIGNONMSGSRSV12></OFX>"
+            .parse()
+            .unwrap();
+        assert!(body.sign_on.is_some());
 
-        for (input, expected) in examples {
-            let deserializer: StrDeserializer<SerdeError> = input.into_deserializer();
-            assert_eq!(deserialize_datetime(deserializer), Ok(expected));
-        }
+        let input = "<OFX><SIGNONMSGSRSV12><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <DTSERVER>20250901<LANGUAGE>ENG</SONRS></SIGNONMSGSRSV12></OFX>";
+        let (_, root) =
+            Body::parse_tree_with_options(input, &ParserOptions::default(), &mut Vec::new())
+                .unwrap();
+        assert!(root.child("SIGNONMSGSRSV12").is_some());
+        assert!(root.child("SIGNONMSGSRSV1").is_none());
     }
 
     #[test]
-    fn test_alt_timezone_formats() {
-        // Per 1.6 spec, 3.2.8.2:
-        // Note that times zones are specified by an offset and optionally, a time zone name. The offset
-        // defines the time zone. Valid offset values are in the range from –12 to +12 for whole number
-        // offsets. Formatting is +12.00 to -12.00 for fractional offsets, plus sign may be omitted.
-        let examples = [
-            "19961005132200.124[-5:EST]",
-            "19961005132200.124[-5]",
-            "19961005132200.124[-5:]",
-            "19961005132200.124[-5.0:EST]",
-            "19961005212200.124[+3:EST]",
-            "19961005212200.124[+3]",
-            "19961005212200.124[+3:]",
-            "19961005212200.124[+3.0:EST]",
-        ];
+    fn masks_account_ids() {
+        let id = AccountId::new("1234567890");
+        assert_eq!(id.to_string(), "****7890");
+        assert_eq!(format!("{id:?}"), r#"AccountId("****7890")"#);
+        assert_eq!(id.expose(), "1234567890");
+        assert_eq!(AccountId::new("123").to_string(), "****");
+    }
+
+    #[test]
+    fn parses_error_only_responses() {
+        let body: Body = "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>2003<SEVERITY>ERROR<MESSAGE>Account not found</STATUS>\
+            </STMTTRNRS></BANKMSGSRSV1></OFX>"
+            .parse()
+            .unwrap();
+        let response = body.bank.unwrap().transaction_response;
+        assert_eq!(response.status.code, 2003);
+        assert_eq!(response.statement, None);
+    }
+
+    #[test]
+    fn parses_client_cookie() {
+        let response: StatementTransactionResponse = "<STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS><CLTCOOKIE>abc\
+            <STMTRS><CURDEF>USD</STMTRS></STMTTRNRS>"
+            .parse()
+            .unwrap();
+        assert_eq!(response.client_cookie.as_deref(), Some("abc"));
+        assert_eq!(response.statement.unwrap().currency, "USD");
+    }
 
-        let expected = Utc
-            .with_ymd_and_hms(1996, 10, 5, 18, 22, 0)
-            .unwrap()
-            .with_nanosecond(124_000_000)
+    #[test]
+    fn iterates_transaction_lists() {
+        let list: BankTransactionList = "<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1</STMTTRN>\
+            <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20250902<TRNAMT>10<FITID>2</STMTTRN>\
+            <STMTTRN><TRNTYPE>POS<DTPOSTED>20250903<TRNAMT>-2<FITID>3</STMTTRN>\
+            </BANKTRANLIST>"
+            .parse()
             .unwrap();
+        let ids = |transactions: Vec<&Transaction>| -> Vec<String> {
+            transactions.into_iter().map(|t| t.id.clone()).collect()
+        };
+        assert_eq!(ids(list.debits().collect()), ["1", "3"]);
+        assert_eq!(ids(list.credits().collect()), ["2"]);
+        assert_eq!(
+            ids(list.by_type(&TransactionType::PointOfSale).collect()),
+            ["3"]
+        );
+        assert_eq!((&list).into_iter().count(), 3);
+        assert_eq!(list.into_iter().last().unwrap().id, "3");
+    }
 
-        for input in examples {
-            let deserializer: StrDeserializer<SerdeError> = input.into_deserializer();
-            assert_eq!(deserialize_datetime(deserializer), Ok(expected));
-        }
+    #[test]
+    fn exposes_language() {
+        let language = Language::new(isolang::Language::Fra);
+        assert_eq!(language.as_isolang(), isolang::Language::Fra);
+        assert_eq!(language.code(), "FRA");
+        assert_eq!(language.to_string(), "FRA");
+    }
+
+    #[test]
+    fn reads_decimal_commas_leniently() {
+        let input = "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>EUR<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-16,40<FITID>1</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+        assert!(input.parse::<Body>().is_err());
+
+        let options = ParserOptions {
+            lenient: true,
+            ..ParserOptions::default()
+        };
+        let mut warnings = Vec::new();
+        let body = Body::parse_with_options(input, &options, &mut warnings).unwrap();
+        let statement = body.bank.unwrap().transaction_response.statement.unwrap();
+        let transaction = &statement.bank_transactions.unwrap().transactions[0];
+        assert_eq!(transaction.amount, "-16.40".parse::<Decimal>().unwrap());
+        assert_eq!(
+            warnings,
+            [Warning::Fixup("read TRNAMT `-16,40` as `-16.40`".into())]
+        );
+
+        let (_, root) = Body::parse_tree_with_options(input, &options, &mut Vec::new()).unwrap();
+        let amount = root
+            .find("TRNAMT")
+            .and_then(|trnamt| trnamt.text.as_deref());
+        assert_eq!(amount, Some("-16,40"));
+    }
+
+    #[test]
+    fn transaction_types_have_directions() {
+        assert_eq!(
+            TransactionType::DirectDeposit.direction(),
+            Direction::Credit
+        );
+        assert_eq!(TransactionType::Check.direction(), Direction::Debit);
+        assert_eq!(TransactionType::PointOfSale.direction(), Direction::Either);
     }
 }
@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    Ofx, Result,
+    body::{
+        BankMessageResponse, BankTransactionList, Body, CreditCardMessageResponse,
+        CreditCardStatementResponse, CreditCardStatementTransactionResponse, SignOnMessageResponse,
+        SignOnResponse, StatementResponse, StatementTransactionResponse, Transaction,
+    },
+    error::ErrorKind,
+    header::{Header, Version},
+};
+
+/// Builds a complete [`Ofx`] response document in code.
+///
+/// Transactions added with [`OfxBuilder::transaction`] are appended to the credit card statement
+/// if one was provided, otherwise to the bank statement.
+#[derive(Debug)]
+pub struct OfxBuilder {
+    header: Header,
+    sign_on: Option<SignOnResponse>,
+    credit_card: Option<CreditCardStatementResponse>,
+    bank: Option<StatementResponse>,
+    transaction_id: String,
+    transactions: Vec<Transaction>,
+}
+
+impl Default for OfxBuilder {
+    fn default() -> Self {
+        Self {
+            header: Header::default(),
+            sign_on: None,
+            credit_card: None,
+            bank: None,
+            transaction_id: "0".into(),
+            transactions: Vec::new(),
+        }
+    }
+}
+
+impl OfxBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+
+    #[must_use]
+    pub fn version(mut self, version: Version) -> Self {
+        self.header.version = version;
+        self
+    }
+
+    #[must_use]
+    pub fn sign_on(mut self, sign_on: SignOnResponse) -> Self {
+        self.sign_on = Some(sign_on);
+        self
+    }
+
+    #[must_use]
+    pub fn credit_card_statement(mut self, statement: CreditCardStatementResponse) -> Self {
+        self.credit_card = Some(statement);
+        self
+    }
+
+    #[must_use]
+    pub fn bank_statement(mut self, statement: StatementResponse) -> Self {
+        self.bank = Some(statement);
+        self
+    }
+
+    /// Sets the `<TRNUID>` used for the statement transaction wrappers.
+    #[must_use]
+    pub fn transaction_id(mut self, transaction_id: impl Into<String>) -> Self {
+        self.transaction_id = transaction_id.into();
+        self
+    }
+
+    #[must_use]
+    pub fn transaction(mut self, transaction: Transaction) -> Self {
+        self.transactions.push(transaction);
+        self
+    }
+
+    #[must_use]
+    pub fn transactions(mut self, transactions: impl IntoIterator<Item = Transaction>) -> Self {
+        self.transactions.extend(transactions);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if transactions were added but no statement was provided to hold them.
+    pub fn build(self) -> Result<Ofx> {
+        let Self {
+            header,
+            sign_on,
+            mut credit_card,
+            mut bank,
+            transaction_id,
+            transactions,
+        } = self;
+
+        if !transactions.is_empty() {
+            let list = if let Some(statement) = credit_card.as_mut() {
+                &mut statement.bank_transactions
+            } else if let Some(statement) = bank.as_mut() {
+                &mut statement.bank_transactions
+            } else {
+                return Err(ErrorKind::Builder(
+                    "transactions require a credit card or bank statement".into(),
+                )
+                .into());
+            };
+            list.get_or_insert_with(BankTransactionList::default)
+                .transactions
+                .extend(transactions);
+        }
+
+        let body = Body {
+            sign_on: sign_on.map(|response| SignOnMessageResponse { response }),
+            credit_card: credit_card.map(|statement| CreditCardMessageResponse {
+                transaction_response: CreditCardStatementTransactionResponse::new(
+                    transaction_id.clone(),
+                    statement,
+                ),
+            }),
+            bank: bank.map(|statement| BankMessageResponse {
+                transaction_response: StatementTransactionResponse::new(transaction_id, statement),
+            }),
+            investment: None,
+            securities: None,
+            other: BTreeMap::new(),
+        };
+
+        Ok(Ofx::new(header, body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        Decimal,
+        body::{Account, Balance, TransactionType},
+        datetime,
+    };
+
+    fn transaction(id: &str) -> Transaction {
+        Transaction::new(
+            TransactionType::Debit,
+            datetime::parse("20250901").unwrap(),
+            Decimal::from(-5),
+            id,
+        )
+        .with_name("COFFEE")
+    }
+
+    #[test]
+    fn builds_credit_card_document() {
+        let statement = CreditCardStatementResponse::new(
+            "USD",
+            Account::new("abc123"),
+            Balance::new("-10.00", datetime::parse("20250902").unwrap()),
+        );
+        let ofx = OfxBuilder::new()
+            .credit_card_statement(statement)
+            .transaction(transaction("1"))
+            .transaction(transaction("2"))
+            .build()
+            .unwrap();
+
+        assert_eq!(ofx.header.version, Version::V160);
+        assert_eq!(ofx.header.charset, "1252");
+        let transactions = ofx
+            .body
+            .credit_card
+            .unwrap()
+            .transaction_response
+            .statement
+            .unwrap()
+            .bank_transactions
+            .unwrap()
+            .transactions;
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[1].id, "2");
+    }
+
+    #[test]
+    fn transactions_without_statement_fail() {
+        assert!(
+            OfxBuilder::new()
+                .transaction(transaction("1"))
+                .build()
+                .is_err()
+        );
+    }
+}
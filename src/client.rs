@@ -0,0 +1,138 @@
+//! Transport helpers for talking to OFX servers.
+//!
+//! Ofxy does not send HTTP requests itself. These helpers cover the session handling real bank
+//! endpoints need — retrying flaky requests and keeping HTTP cookies between them — for use with
+//! whichever HTTP client you prefer. For the OFX-level `SESSCOOKIE`, see
+//! [`SignOnRequest::continuing`](crate::request::SignOnRequest::continuing).
+
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// How often, and how patiently, to retry requests that failed transiently.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// What each delay is multiplied by for the next retry
+    pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Whether an HTTP status is worth retrying: server errors, and 408/429.
+    #[must_use]
+    pub fn is_retryable_status(status: u16) -> bool {
+        matches!(status, 408 | 429 | 500..=599)
+    }
+
+    /// How long to wait before retrying after `attempt` (starting at 1) failed, or `None` once
+    /// attempts are exhausted.
+    #[must_use]
+    pub fn delay_after(&self, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let factor = self.multiplier.saturating_pow(attempt.saturating_sub(1));
+        Some(
+            self.initial_delay
+                .saturating_mul(factor)
+                .min(self.max_delay),
+        )
+    }
+}
+
+/// HTTP cookies set by a server, to send back on later requests.
+///
+/// Attributes like `Path` and `Expires` are ignored; OFX servers use a single endpoint per
+/// session. The jar implements `Serialize` and `Deserialize` so it can be persisted between runs.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CookieJar {
+    cookies: BTreeMap<String, String>,
+}
+
+impl CookieJar {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the cookie from a `Set-Cookie` header value.
+    pub fn store(&mut self, set_cookie: &str) {
+        let pair = set_cookie.split(';').next().unwrap_or_default();
+        if let Some((name, value)) = pair.split_once('=') {
+            self.cookies
+                .insert(name.trim().to_owned(), value.trim().to_owned());
+        }
+    }
+
+    /// The `Cookie` header value to send, or `None` if no cookies have been set.
+    #[must_use]
+    pub fn header(&self) -> Option<String> {
+        (!self.cookies.is_empty()).then(|| {
+            self.cookies
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(3),
+            multiplier: 2,
+        };
+        let delays: Vec<_> = (1..=4).map(|attempt| policy.delay_after(attempt)).collect();
+        assert_eq!(
+            delays,
+            [
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(3)),
+                None
+            ]
+        );
+        assert_eq!(RetryPolicy::none().delay_after(1), None);
+        assert!(RetryPolicy::is_retryable_status(503));
+        assert!(!RetryPolicy::is_retryable_status(401));
+    }
+
+    #[test]
+    fn keeps_cookies() {
+        let mut jar = CookieJar::new();
+        assert_eq!(jar.header(), None);
+        jar.store("JSESSIONID=abc123; Path=/; HttpOnly");
+        jar.store("lb=node2");
+        assert_eq!(jar.header().as_deref(), Some("JSESSIONID=abc123; lb=node2"));
+    }
+}
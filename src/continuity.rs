@@ -0,0 +1,259 @@
+//! Checking that a series of statements for one account fits together.
+//!
+//! When statements are archived one download at a time, a missed download or a bank correction
+//! shows up as a break between them: days no statement covers, days two statements both cover,
+//! or a closing balance that does not follow from the previous one. [`check`] reports each.
+
+use std::fmt;
+
+use crate::{Decimal, Ofx, body::BankTransactionList, datetime};
+
+/// A break between two statements, which are identified by their index in the input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Issue {
+    /// No statement covers the days from `from` to `to`, inclusive, as `YYYYMMDD` dates.
+    Gap {
+        previous: usize,
+        next: usize,
+        from: String,
+        to: String,
+    },
+    /// Both statements cover the days from `from` to `to`, inclusive, as `YYYYMMDD` dates.
+    Overlap {
+        previous: usize,
+        next: usize,
+        from: String,
+        to: String,
+    },
+    /// The previous closing balance plus the next statement's transactions is `expected`, but the
+    /// next statement closes at `actual`.
+    Balance {
+        previous: usize,
+        next: usize,
+        expected: Decimal,
+        actual: Decimal,
+    },
+    /// The statement is missing something needed to check it, and is left out.
+    Incomplete { index: usize, reason: String },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gap {
+                previous,
+                next,
+                from,
+                to,
+            } => write!(
+                f,
+                "statements {previous} and {next} leave {from} to {to} uncovered"
+            ),
+            Self::Overlap {
+                previous,
+                next,
+                from,
+                to,
+            } => write!(
+                f,
+                "statements {previous} and {next} both cover {from} to {to}"
+            ),
+            Self::Balance {
+                previous,
+                next,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "statement {next} closes at {actual}, but statement {previous} and its \
+                 transactions give {expected}"
+            ),
+            Self::Incomplete { index, reason } => write!(f, "statement {index} {reason}"),
+        }
+    }
+}
+
+/// The parts of a statement the checks need.
+struct Period {
+    index: usize,
+    start: i64,
+    end: i64,
+    net: Decimal,
+    closing: Decimal,
+}
+
+/// The transaction list and ledger balance of the bank statement in `ofx`, or failing that the
+/// credit card statement.
+fn statement(ofx: &Ofx) -> Option<(Option<&BankTransactionList>, Option<&str>)> {
+    let bank = ofx
+        .body
+        .bank
+        .as_ref()
+        .and_then(|message| message.transaction_response.statement.as_ref())
+        .map(|statement| {
+            (
+                statement.bank_transactions.as_ref(),
+                statement
+                    .ledger_balance
+                    .as_ref()
+                    .map(|balance| balance.amount.as_str()),
+            )
+        });
+    let credit_card = || {
+        ofx.body
+            .credit_card
+            .as_ref()
+            .and_then(|message| message.transaction_response.statement.as_ref())
+            .map(|statement| {
+                (
+                    statement.bank_transactions.as_ref(),
+                    Some(statement.ledger_balance.amount.as_str()),
+                )
+            })
+    };
+    bank.or_else(credit_card)
+}
+
+fn period(index: usize, ofx: &Ofx) -> Result<Period, String> {
+    let (list, balance) = statement(ofx).ok_or("has no bank or credit card statement")?;
+    let list = list.ok_or("has no transaction list")?;
+    let date = |value: Option<&String>, name: &str| {
+        value
+            .and_then(|value| datetime::days(value).ok())
+            .ok_or_else(|| format!("has no readable {name}"))
+    };
+    let closing = balance
+        .and_then(|balance| balance.trim().parse().ok())
+        .ok_or("has no readable ledger balance")?;
+    Ok(Period {
+        index,
+        start: date(list.dtstart.as_ref(), "DTSTART")?,
+        end: date(list.dtend.as_ref(), "DTEND")?,
+        net: list
+            .transactions
+            .iter()
+            .map(|transaction| transaction.amount)
+            .sum(),
+        closing,
+    })
+}
+
+/// Checks a series of statements for one account, in any order, for gaps, overlaps, and
+/// balances that do not carry over.
+///
+/// Each document's bank statement is used, or if it has none its credit card statement. The
+/// statements are put in order of their `DTSTART`, and each is compared with the one before. The
+/// statement dates are compared as calendar days in the bank's time zone, and a statement may
+/// start on the day the previous one ends, since banks disagree on whether `DTEND` is inclusive.
+#[must_use]
+pub fn check(statements: &[Ofx]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut periods = Vec::with_capacity(statements.len());
+    for (index, ofx) in statements.iter().enumerate() {
+        match period(index, ofx) {
+            Ok(period) => periods.push(period),
+            Err(reason) => issues.push(Issue::Incomplete { index, reason }),
+        }
+    }
+    periods.sort_by_key(|period| period.start);
+
+    for pair in periods.windows(2) {
+        let [previous, next] = pair else { continue };
+        let (previous_index, next_index) = (previous.index, next.index);
+        if next.start > previous.end + 1 {
+            issues.push(Issue::Gap {
+                previous: previous_index,
+                next: next_index,
+                from: datetime::date(previous.end + 1),
+                to: datetime::date(next.start - 1),
+            });
+        } else if next.start < previous.end {
+            issues.push(Issue::Overlap {
+                previous: previous_index,
+                next: next_index,
+                from: datetime::date(next.start),
+                to: datetime::date(previous.end.min(next.end)),
+            });
+        }
+        let expected = previous.closing + next.net;
+        if expected != next.closing {
+            issues.push(Issue::Balance {
+                previous: previous_index,
+                next: next_index,
+                expected,
+                actual: next.closing,
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        body::{AccountType, BankAccount, Body},
+        csv::Statement,
+        header::Header,
+    };
+
+    fn statement(csv: &str, balance: &str) -> Ofx {
+        let account = BankAccount::new("123456789", "0001", AccountType::Checking);
+        Statement::new(account, "USD")
+            .balance(balance)
+            .to_ofx(csv)
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_breaks_between_statements() {
+        let march = statement("2025-03-01,100.00,A\n2025-03-31,-20.00,B", "180.00");
+        let april = statement("2025-04-01,-30.00,C\n2025-04-30,-10.00,D", "140.00");
+        let may = statement("2025-05-03,5.00,E\n2025-05-31,5.00,F", "160.00");
+        let overlapping = statement("2025-05-20,1.00,G\n2025-06-30,1.00,H", "162.00");
+        assert_eq!(check(&[april.clone(), march.clone()]), []);
+
+        let issues = check(&[
+            march,
+            april,
+            may,
+            overlapping,
+            Ofx::new(Header::default(), Body::default()),
+        ]);
+        assert_eq!(
+            issues,
+            [
+                Issue::Incomplete {
+                    index: 4,
+                    reason: "has no bank or credit card statement".into(),
+                },
+                Issue::Gap {
+                    previous: 1,
+                    next: 2,
+                    from: "20250501".into(),
+                    to: "20250502".into(),
+                },
+                Issue::Balance {
+                    previous: 1,
+                    next: 2,
+                    expected: "150.00".parse().unwrap(),
+                    actual: "160.00".parse().unwrap(),
+                },
+                Issue::Overlap {
+                    previous: 2,
+                    next: 3,
+                    from: "20250520".into(),
+                    to: "20250531".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            issues[1].to_string(),
+            "statements 1 and 2 leave 20250501 to 20250502 uncovered"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Looking up the passwords used to sign on to institutions.
+//!
+//! Implement [`CredentialStore`] to plug in a secrets manager. [`EnvStore`] reads environment
+//! variables, and with the `keyring` feature `KeyringStore` uses the OS keychain, so passwords
+//! never need to live in plaintext config files.
+
+use std::{fmt, io};
+
+use crate::Result;
+
+/// A user ID and password for one institution.
+#[derive(Clone)]
+pub struct Credentials {
+    pub user_id: String,
+    password: String,
+}
+
+impl Credentials {
+    #[must_use]
+    pub fn new(user_id: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            password: password.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("user_id", &self.user_id)
+            .field("password", &"[redacted]")
+            .finish()
+    }
+}
+
+/// Somewhere passwords can be looked up by institution and user ID.
+pub trait CredentialStore {
+    /// Returns the stored password, or `None` if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backing store cannot be read.
+    fn password(&self, institution: &str, user_id: &str) -> Result<Option<String>>;
+
+    /// Stores `password`, replacing any existing one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backing store cannot be written or is read-only.
+    fn set_password(&self, institution: &str, user_id: &str, password: &str) -> Result<()>;
+
+    /// Looks up complete [`Credentials`].
+    ///
+    /// # Errors
+    ///
+    /// See [`CredentialStore::password`].
+    fn credentials(&self, institution: &str, user_id: &str) -> Result<Option<Credentials>> {
+        Ok(self
+            .password(institution, user_id)?
+            .map(|password| Credentials::new(user_id, password)))
+    }
+}
+
+/// Reads passwords from `OFXY_PASSWORD_<INSTITUTION>_<USER_ID>` environment variables.
+///
+/// Names are uppercased, with anything other than ASCII letters and digits replaced by `_`.
+/// This store is read-only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvStore;
+
+impl EnvStore {
+    #[must_use]
+    pub fn variable(institution: &str, user_id: &str) -> String {
+        format!("OFXY_PASSWORD_{institution}_{user_id}")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+}
+
+impl CredentialStore for EnvStore {
+    fn password(&self, institution: &str, user_id: &str) -> Result<Option<String>> {
+        Ok(std::env::var(Self::variable(institution, user_id)).ok())
+    }
+
+    fn set_password(&self, _institution: &str, _user_id: &str, _password: &str) -> Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "environment variable credentials are read-only",
+        )
+        .into())
+    }
+}
+
+/// Stores passwords in the OS keychain under the given service name.
+#[cfg(feature = "keyring")]
+#[derive(Clone, Debug)]
+pub struct KeyringStore {
+    service: String,
+}
+
+#[cfg(feature = "keyring")]
+impl KeyringStore {
+    #[must_use]
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+        }
+    }
+
+    fn entry(&self, institution: &str, user_id: &str) -> Result<keyring::Entry> {
+        Ok(keyring::Entry::new(
+            &self.service,
+            &format!("{institution}:{user_id}"),
+        )?)
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl Default for KeyringStore {
+    fn default() -> Self {
+        Self::new("ofxy")
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl CredentialStore for KeyringStore {
+    fn password(&self, institution: &str, user_id: &str) -> Result<Option<String>> {
+        match self.entry(institution, user_id)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn set_password(&self, institution: &str, user_id: &str, password: &str) -> Result<()> {
+        Ok(self.entry(institution, user_id)?.set_password(password)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_and_names_variables() {
+        let credentials = Credentials::new("jdoe", "hunter2");
+        assert!(!format!("{credentials:?}").contains("hunter2"));
+        assert_eq!(credentials.password(), "hunter2");
+        assert_eq!(
+            EnvStore::variable("Example Bank", "jdoe"),
+            "OFXY_PASSWORD_EXAMPLE_BANK_JDOE"
+        );
+        assert!(EnvStore.set_password("a", "b", "c").is_err());
+    }
+}
@@ -0,0 +1,308 @@
+//! Converting simple CSV exports into OFX 1.x bank statements.
+//!
+//! Some accounts only offer CSV downloads. [`parse`] reads rows of `date,amount,payee,memo` into
+//! transactions, and [`Statement::to_ofx`] wraps them in a complete OFX file that other software
+//! can import.
+
+use std::collections::HashMap;
+
+use crate::{
+    Result,
+    body::{BankAccount, Transaction, TransactionType},
+    datetime,
+    decimal::{self, Decimal},
+    error::Error,
+    header::{Encoding, Header, HeaderBuilder, Version},
+    tree::Element,
+};
+
+/// The longest `<NAME>` OFX 1.x allows; longer payees are truncated.
+const NAME_LENGTH: usize = 32;
+
+fn error(line: usize, message: impl std::fmt::Display) -> Error {
+    Error::parse(format!("invalid CSV on line {line}: {message}"))
+}
+
+/// Splits a CSV line into fields, unquoting `"..."` fields and their `""` escapes.
+fn fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Converts a `YYYY-MM-DD`, `MM/DD/YYYY`, or `YYYYMMDD` date to an OFX `YYYYMMDD` date.
+fn date(s: &str) -> Option<String> {
+    let date = if let Some((year, rest)) = s.split_once('-') {
+        let (month, day) = rest.split_once('-')?;
+        format!("{year}{month:0>2}{day:0>2}")
+    } else if let Some((month, rest)) = s.split_once('/') {
+        let (day, year) = rest.split_once('/')?;
+        format!("{year}{month:0>2}{day:0>2}")
+    } else {
+        s.to_owned()
+    };
+    (date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit())).then_some(date)
+}
+
+/// Parses an amount, accepting the separators and parentheses described in
+/// [`ParserOptions::lenient`](crate::ParserOptions::lenient).
+fn amount(s: &str) -> Option<Decimal> {
+    s.parse()
+        .ok()
+        .or_else(|| decimal::lenient(s).and_then(|fixed| fixed.parse().ok()))
+}
+
+/// Parses CSV rows of `date,amount,payee,memo` into transactions.
+///
+/// Dates may be written `YYYY-MM-DD`, `MM/DD/YYYY`, or `YYYYMMDD`, and the memo column is
+/// optional. A first row whose date cannot be read is taken as a header and skipped, as are blank
+/// lines. Negative amounts become debits and the rest credits. CSV exports carry no FITIDs, so
+/// each transaction is given `YYYYMMDD-N`, numbering the transactions on each date in file
+/// order.
+///
+/// # Errors
+///
+/// Returns an error if a row has too few columns or an unreadable date or amount.
+pub fn parse(csv: &str) -> Result<Vec<Transaction>> {
+    let mut transactions = Vec::new();
+    let mut per_date: HashMap<String, usize> = HashMap::new();
+    let mut first = true;
+    for (index, line) in csv.lines().enumerate() {
+        let number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let header = std::mem::replace(&mut first, false);
+        let fields = fields(line);
+        let [date_field, amount_field, payee, rest @ ..] = &fields[..] else {
+            return Err(error(number, "expected date, amount, and payee columns"));
+        };
+        let Some(posted) = date(date_field.trim()) else {
+            if header {
+                continue;
+            }
+            return Err(error(number, format!("bad date `{date_field}`")));
+        };
+        let amount = amount(amount_field.trim())
+            .ok_or_else(|| error(number, format!("bad amount `{amount_field}`")))?;
+
+        let transaction_type = if amount.to_string().starts_with('-') {
+            TransactionType::Debit
+        } else {
+            TransactionType::Credit
+        };
+        let same_day = per_date.entry(posted.clone()).or_default();
+        let mut transaction = Transaction::new(
+            transaction_type,
+            datetime::parse(&posted)?,
+            amount,
+            format!("{posted}-{same_day}"),
+        )
+        .with_name(payee.trim().chars().take(NAME_LENGTH).collect::<String>());
+        if let Some(memo) = rest
+            .first()
+            .map(|memo| memo.trim())
+            .filter(|m| !m.is_empty())
+        {
+            transaction = transaction.with_memo(memo);
+        }
+        transactions.push(transaction);
+        *same_day += 1;
+    }
+    Ok(transactions)
+}
+
+/// The account a CSV export belongs to, which the CSV itself does not record.
+#[derive(Clone, Debug)]
+pub struct Statement {
+    account: BankAccount,
+    currency: String,
+    balance: Option<String>,
+}
+
+impl Statement {
+    #[must_use]
+    pub fn new(account: BankAccount, currency: impl Into<String>) -> Self {
+        Self {
+            account,
+            currency: currency.into(),
+            balance: None,
+        }
+    }
+
+    /// Sets the ledger balance as of the last transaction. Without it the statement reports a
+    /// balance of `0`, since OFX requires one.
+    #[must_use]
+    pub fn balance(mut self, balance: impl Into<String>) -> Self {
+        self.balance = Some(balance.into());
+        self
+    }
+
+    /// Converts a CSV export, as read by [`parse`], into a version 102 OFX file.
+    ///
+    /// The server date, statement end, and balance date are all the last transaction's date, so
+    /// the same input always produces the same body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CSV cannot be parsed or contains no transactions.
+    pub fn to_ofx(&self, csv: &str) -> Result<String> {
+        let header = HeaderBuilder::new(Version::V102)
+            .encoding(Encoding::Utf8)
+            .charset("NONE")
+            .build();
+        let transactions = parse(csv)?;
+        let dates: Vec<String> = transactions
+            .iter()
+            .map(|transaction| datetime::format(&transaction.date_posted))
+            .collect();
+        let (Some(start), Some(end)) = (dates.iter().min(), dates.iter().max()) else {
+            return Err(Error::parse("invalid CSV: no transactions"));
+        };
+        Ok(self.render(&header, start, end, &transactions))
+    }
+
+    /// Writes `header` and a signon and bank statement from `start` to `end` holding
+    /// `transactions`.
+    pub(crate) fn render(
+        &self,
+        header: &Header,
+        start: &str,
+        end: &str,
+        transactions: &[Transaction],
+    ) -> String {
+        let status = || {
+            Element::aggregate(
+                "STATUS",
+                vec![
+                    Element::leaf("CODE", "0"),
+                    Element::leaf("SEVERITY", "INFO"),
+                ],
+            )
+        };
+        let sign_on = Element::aggregate(
+            "SONRS",
+            vec![
+                status(),
+                Element::leaf("DTSERVER", end),
+                Element::leaf("LANGUAGE", "ENG"),
+            ],
+        );
+
+        let mut list = vec![Element::leaf("DTSTART", start), Element::leaf("DTEND", end)];
+        list.extend(transactions.iter().map(Transaction::to_element));
+
+        let statement = Element::aggregate(
+            "STMTRS",
+            vec![
+                Element::leaf("CURDEF", &self.currency),
+                Element::aggregate(
+                    "BANKACCTFROM",
+                    vec![
+                        Element::leaf("BANKID", &self.account.bank_id),
+                        Element::leaf("ACCTID", self.account.id.expose()),
+                        Element::leaf("ACCTTYPE", self.account.account_type.code()),
+                    ],
+                ),
+                Element::aggregate("BANKTRANLIST", list),
+                Element::aggregate(
+                    "LEDGERBAL",
+                    vec![
+                        Element::leaf("BALAMT", self.balance.as_deref().unwrap_or("0")),
+                        Element::leaf("DTASOF", end),
+                    ],
+                ),
+            ],
+        );
+
+        let body = Element::aggregate(
+            "OFX",
+            vec![
+                Element::aggregate("SIGNONMSGSRSV1", vec![sign_on]),
+                Element::aggregate(
+                    "BANKMSGSRSV1",
+                    vec![Element::aggregate(
+                        "STMTTRNRS",
+                        vec![Element::leaf("TRNUID", "0"), status(), statement],
+                    )],
+                ),
+            ],
+        );
+        format!("{header}\r\n{}", body.to_sgml())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Ofx, body::AccountType};
+
+    const CSV: &str = "Date,Amount,Payee,Memo
+2025-09-02,-12.50,\"Coffee, Inc.\",Latte
+09/02/2025,\"1,200.00\",Payroll,
+20250903,(4.00),Bank,\"Monthly \"\"fee\"\"\"
+";
+
+    #[test]
+    fn parses_rows() {
+        let transactions = parse(CSV).unwrap();
+        assert_eq!(transactions.len(), 3);
+        let [coffee, payroll, fee] = &transactions[..] else {
+            panic!("expected three transactions");
+        };
+        assert_eq!(coffee.id, "20250902-0");
+        assert_eq!(coffee.name.as_deref(), Some("Coffee, Inc."));
+        assert_eq!(coffee.transaction_type, TransactionType::Debit);
+        assert_eq!(payroll.id, "20250902-1");
+        assert_eq!(payroll.transaction_type, TransactionType::Credit);
+        assert_eq!(payroll.memo, None);
+        assert_eq!(fee.memo.as_deref(), Some("Monthly \"fee\""));
+        assert_eq!(
+            fee.amount.to_string(),
+            "-4.00".parse::<Decimal>().unwrap().to_string()
+        );
+
+        assert!(parse("2025-09-02,12.00").is_err());
+        assert!(parse("2025-09-02,1,a\nnot a date,1,b").is_err());
+    }
+
+    #[test]
+    fn converts_to_parseable_ofx() {
+        let account = BankAccount::new("123456789", "0001", AccountType::Checking);
+        let ofx = Statement::new(account, "USD")
+            .balance("1183.50")
+            .to_ofx(CSV)
+            .unwrap();
+        assert!(ofx.starts_with("OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\n"));
+
+        let parsed: Ofx = ofx.parse().unwrap();
+        let statement = parsed
+            .body
+            .bank
+            .unwrap()
+            .transaction_response
+            .statement
+            .unwrap();
+        assert_eq!(statement.currency, "USD");
+        assert_eq!(statement.ledger_balance.unwrap().amount, "1183.50");
+        let list = statement.bank_transactions.unwrap();
+        assert_eq!(list.transactions.len(), 3);
+        assert_eq!(list.transactions[2].id, "20250903-0");
+        assert!(list.dtstart.unwrap().starts_with("20250902"));
+    }
+}
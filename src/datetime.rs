@@ -0,0 +1,974 @@
+//! OFX datetime parsing and the datetime type exposed in the public API.
+//!
+//! The concrete [`DateTime`] type depends on the enabled backend feature. When more than one is
+//! enabled, `chrono` takes precedence over `time`, which takes precedence over `jiff`.
+//!
+//! | feature  | [`DateTime`]                    |
+//! |----------|---------------------------------|
+//! | `chrono` | `chrono::DateTime<chrono::Utc>` |
+//! | `time`   | `time::OffsetDateTime`          |
+//! | `jiff`   | `jiff::Zoned`                   |
+//!
+//! Unlike the other backends, `jiff` preserves the parsed offset as a fixed-offset time zone
+//! rather than normalizing to UTC. [`ParserOptions::time_zone`] picks another
+//! [`TimeZonePolicy`].
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+use crate::{ParserOptions, Result, error::Error, warning::Warning};
+
+#[cfg(not(any(feature = "chrono", feature = "time", feature = "jiff")))]
+compile_error!("ofxy requires one of the `chrono`, `time`, or `jiff` features");
+
+#[cfg(feature = "chrono")]
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+pub type DateTime = time::OffsetDateTime;
+
+#[cfg(all(feature = "jiff", not(any(feature = "chrono", feature = "time"))))]
+pub type DateTime = jiff::Zoned;
+
+/// Which time zone parsed datetimes are expressed in.
+///
+/// `time` and `jiff` datetimes carry the chosen offset. A `chrono` [`DateTime`] is always UTC,
+/// so with `chrono` the policies other than [`TimeZonePolicy::Utc`] expose the local wall-clock
+/// time as though it were UTC, which keeps calendar dates as the bank or caller sees them.
+///
+/// The default is [`TimeZonePolicy::Utc`], except under `jiff`, where it is
+/// [`TimeZonePolicy::BankLocal`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum TimeZonePolicy {
+    /// Normalize to UTC.
+    #[cfg_attr(any(feature = "chrono", feature = "time"), default)]
+    Utc,
+    /// Keep the offset the bank wrote, e.g. `-5` in `[-5:EST]`.
+    #[cfg_attr(not(any(feature = "chrono", feature = "time")), default)]
+    BankLocal,
+    /// Convert to a fixed offset from UTC, in seconds. Offsets of a day or more are treated as
+    /// zero.
+    Offset(i32),
+}
+
+/// The components of an OFX datetime, prior to conversion into a [`DateTime`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct Parts<'a> {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+    pub offset_seconds: i32,
+    /// Whether `offset_seconds` was read from a valid numeric offset, which per the spec defines
+    /// the zone, rather than looked up from the name or defaulted.
+    pub has_offset: bool,
+    /// The zone name after the offset, e.g. `EST`
+    pub zone_name: Option<&'a str>,
+}
+
+fn number<T: std::str::FromStr>(s: &str, start: usize, end: usize) -> Option<T> {
+    s.get(start..end)
+        .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|digits| digits.parse().ok())
+}
+
+/// UTC offsets, in seconds, of zone names seen without a numeric offset.
+const ZONE_OFFSETS: &[(&str, i32)] = &[
+    ("GMT", 0),
+    ("UTC", 0),
+    ("Z", 0),
+    ("EST", -5 * 3600),
+    ("EDT", -4 * 3600),
+    ("CST", -6 * 3600),
+    ("CDT", -5 * 3600),
+    ("MST", -7 * 3600),
+    ("MDT", -6 * 3600),
+    ("PST", -8 * 3600),
+    ("PDT", -7 * 3600),
+    ("AKST", -9 * 3600),
+    ("AKDT", -8 * 3600),
+    ("HST", -10 * 3600),
+];
+
+fn zone_offset(name: &str) -> Option<i32> {
+    ZONE_OFFSETS
+        .iter()
+        .find(|(zone, _)| zone.eq_ignore_ascii_case(name))
+        .map(|&(_, offset)| offset)
+}
+
+/// Parses a numeric offset in hours, such as `-5` or `+5.30`, into seconds.
+fn offset(offset_str: &str) -> std::result::Result<i32, String> {
+    let hours = offset_str
+        .parse::<f32>()
+        .map_err(|err| format!("invalid timezone offset `{offset_str}` ({err})"))?;
+    if hours.abs() > 12.0 {
+        return Err(format!("timezone offset too large or small: {hours}"));
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let seconds = (hours * 3600.0).round() as i32;
+    Ok(seconds)
+}
+
+/// Converts fractional-second digits of any width to nanoseconds, truncating past nine digits.
+fn nanoseconds(digits: &str) -> Option<u32> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let digits = digits.get(..9).unwrap_or(digits);
+    format!("{digits:0<9}").parse().ok()
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a `YYYYMMDD` date.
+pub(crate) fn days(date: &str) -> Result<i64> {
+    parse(date)?;
+    let number = |range: std::ops::Range<usize>| -> Result<i64> {
+        date.get(range)
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| Error::parse(format!("expected a `YYYYMMDD` date, got `{date}`")))
+    };
+    let (year, month, day) = (number(0..4)?, number(4..6)?, number(6..8)?);
+    // Howard Hinnant's `days_from_civil`
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Ok(era * 146_097 + day_of_era - 719_468)
+}
+
+/// The `YYYYMMDD` date `days` after 1970-01-01, Hinnant's `civil_from_days`.
+pub(crate) fn date(days: i64) -> String {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}{month:02}{day:02}")
+}
+
+/// Rejects what [`parse_parts`] lets through but the spec does not allow: the `YYYYMMDDHHMM`
+/// layout, fractions of other than three digits, bare or malformed zones, and impossible
+/// components like February 30 or hour 25.
+fn check_strict(
+    s: &str,
+    dt_str: &str,
+    fraction: Option<&str>,
+    offset_str: Option<&str>,
+    parts: &Parts<'_>,
+) -> std::result::Result<(), String> {
+    if dt_str.len() == 12 {
+        return Err("missing seconds".into());
+    }
+    if let Some(fraction) = fraction
+        && fraction.len() != 3
+    {
+        return Err(format!(
+            "expected 3 fractional digits, found {}",
+            fraction.len()
+        ));
+    }
+    if let Some(idx) = s.find(['[', ' ']) {
+        let zone = &s[idx..];
+        let Some(zone) = zone.strip_prefix('[').and_then(|z| z.strip_suffix(']')) else {
+            return Err(format!(
+                "time zone `{zone}` is not of the form `[offset:NAME]`"
+            ));
+        };
+        let Some(offset_str) = offset_str else {
+            return Err(format!("time zone `{zone}` has no offset"));
+        };
+        let unsigned = offset_str.trim_start_matches(['+', '-']);
+        let (hours, fraction) = unsigned.split_once('.').unwrap_or((unsigned, "0"));
+        let digits =
+            |s: &str, max| (1..=max).contains(&s.len()) && s.bytes().all(|b| b.is_ascii_digit());
+        if unsigned.len() + 1 < offset_str.len() || !digits(hours, 2) || !digits(fraction, 2) {
+            return Err(format!("malformed timezone offset `{offset_str}`"));
+        }
+    }
+
+    let Parts {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        ..
+    } = *parts;
+    if !(1..=12).contains(&month) {
+        return Err(format!("month {month} is out of range (1-12)"));
+    }
+    let last_day = days_in_month(year, month);
+    if !(1..=last_day).contains(&day) {
+        return Err(format!(
+            "day {day} is out of range for {year:04}-{month:02} (1-{last_day})"
+        ));
+    }
+    for (name, value, max) in [
+        ("hour", hour, 23),
+        ("minute", minute, 59),
+        ("second", second, 59),
+    ] {
+        if value > max {
+            return Err(format!("{name} {value} is out of range (0-{max})"));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_parts(s: &str) -> std::result::Result<Parts<'_>, String> {
+    // Per 1.6 spec, 3.2.8.2:
+    // Note that times zones are specified by an offset and optionally, a time zone name. The offset
+    // defines the time zone. Valid offset values are in the range from –12 to +12 for whole number
+    // offsets. Formatting is +12.00 to -12.00 for fractional offsets, plus sign may be omitted.
+    //
+    // Some files instead have a bare zone name, as in `[EST]` or `20190510120000 GMT`.
+    let s = s.trim();
+    let (dt_str, zone) = match s.find(['[', ' ']) {
+        Some(idx) => (
+            &s[..idx],
+            s[idx..]
+                .trim_start_matches([' ', '['])
+                .trim_end_matches(']'),
+        ),
+        None => (s, ""),
+    };
+    let (offset_str, name) = match zone.split_once(':') {
+        Some((offset, name)) => (Some(offset), Some(name)),
+        None if zone.is_empty() => (None, None),
+        None if zone.starts_with(|c: char| c.is_ascii_alphabetic()) => (None, Some(zone)),
+        None => (Some(zone), None),
+    };
+
+    let (offset_seconds, has_offset) = match (offset_str, name) {
+        (Some(offset_str), _) => match offset(offset_str) {
+            Ok(offset_seconds) => (offset_seconds, true),
+            Err(err) if ParserOptions::current(|options| options.lenient) => {
+                let offset_seconds = name.and_then(zone_offset).unwrap_or(0);
+                ParserOptions::warn(Warning::Fixup(format!(
+                    "{err} in `{s}`; used {} instead",
+                    name.filter(|_| offset_seconds != 0).unwrap_or("UTC")
+                )));
+                (offset_seconds, false)
+            }
+            Err(err) => return Err(err),
+        },
+        // Unknown names are treated as UTC rather than failing the whole document
+        (None, Some(name)) => (zone_offset(name).unwrap_or(0), false),
+        (None, None) => (0, false),
+    };
+
+    let (dt_str, fraction) = match dt_str.split_once('.') {
+        Some((dt_str, fraction)) => (dt_str, Some(fraction)),
+        None => (dt_str, None),
+    };
+    let len = dt_str.len();
+    if !matches!(len, 8 | 12 | 14) || (fraction.is_some() && len != 14) {
+        return Err(format!("invalid datetime: {dt_str}"));
+    }
+
+    let invalid = || format!("unable to parse '{s}' as datetime");
+    let time = |start, end| {
+        if len >= end {
+            number(dt_str, start, end).ok_or_else(invalid)
+        } else {
+            Ok(0)
+        }
+    };
+
+    let parts = Parts {
+        year: number(dt_str, 0, 4).ok_or_else(invalid)?,
+        month: number(dt_str, 4, 6).ok_or_else(invalid)?,
+        day: number(dt_str, 6, 8).ok_or_else(invalid)?,
+        hour: time(8, 10)?,
+        minute: time(10, 12)?,
+        second: time(12, 14)?,
+        nanosecond: match fraction {
+            Some(digits) => nanoseconds(digits).ok_or_else(invalid)?,
+            None => 0,
+        },
+        offset_seconds,
+        has_offset,
+        zone_name: name.filter(|name| !name.is_empty()),
+    };
+    if ParserOptions::current(|options| options.strict_datetimes) {
+        check_strict(s, dt_str, fraction, offset_str, &parts)?;
+    }
+    Ok(parts)
+}
+
+#[cfg(feature = "chrono")]
+fn from_parts(parts: &Parts<'_>) -> std::result::Result<DateTime, String> {
+    use chrono::{FixedOffset, NaiveDate, Utc};
+
+    let offset = FixedOffset::east_opt(parts.offset_seconds)
+        .ok_or_else(|| format!("invalid timezone offset: {}", parts.offset_seconds))?;
+    let local = NaiveDate::from_ymd_opt(parts.year, parts.month.into(), parts.day.into())
+        .and_then(|date| {
+            date.and_hms_nano_opt(
+                parts.hour.into(),
+                parts.minute.into(),
+                parts.second.into(),
+                parts.nanosecond,
+            )
+        })
+        .ok_or_else(|| "out of range datetime component".to_owned())?;
+    #[cfg(feature = "chrono_tz")]
+    let resolved = parts
+        .zone_name
+        .filter(|_| !parts.has_offset && ParserOptions::current(|options| options.zone_names))
+        .and_then(|name| resolve_zone(name, &local, offset));
+    #[cfg(not(feature = "chrono_tz"))]
+    let resolved = None;
+    let utc = match resolved {
+        Some(dt) => dt,
+        None => local
+            .and_local_timezone(offset)
+            .single()
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| "ambiguous or invalid local datetime".to_owned())?,
+    };
+    Ok(match ParserOptions::current(|options| options.time_zone) {
+        TimeZonePolicy::Utc => utc,
+        TimeZonePolicy::BankLocal => local.and_utc(),
+        TimeZonePolicy::Offset(seconds) => FixedOffset::east_opt(seconds).map_or(utc, |offset| {
+            utc.with_timezone(&offset).naive_local().and_utc()
+        }),
+    })
+}
+
+/// IANA zones for the abbreviations banks put after the offset.
+#[cfg(feature = "chrono_tz")]
+const ZONE_NAMES: &[(&str, chrono_tz::Tz)] = &[
+    ("EST", chrono_tz::America::New_York),
+    ("EDT", chrono_tz::America::New_York),
+    ("CST", chrono_tz::America::Chicago),
+    ("CDT", chrono_tz::America::Chicago),
+    ("MST", chrono_tz::America::Denver),
+    ("MDT", chrono_tz::America::Denver),
+    ("PST", chrono_tz::America::Los_Angeles),
+    ("PDT", chrono_tz::America::Los_Angeles),
+    ("AKST", chrono_tz::America::Anchorage),
+    ("AKDT", chrono_tz::America::Anchorage),
+    ("HST", chrono_tz::Pacific::Honolulu),
+];
+
+/// Interprets `local` in the zone `name` refers to, so the DST rules in effect on that date
+/// apply rather than the standard time offset of the name. Where the local time is ambiguous,
+/// that offset picks between the candidates.
+///
+/// Returns `None` for unknown names and for local times skipped by a DST transition.
+#[cfg(feature = "chrono_tz")]
+fn resolve_zone(
+    name: &str,
+    local: &chrono::NaiveDateTime,
+    offset: chrono::FixedOffset,
+) -> Option<DateTime> {
+    use chrono::{LocalResult, Offset, TimeZone, Utc};
+
+    let (_, tz) = ZONE_NAMES
+        .iter()
+        .find(|(zone, _)| zone.eq_ignore_ascii_case(name))?;
+    let dt = match tz.from_local_datetime(local) {
+        LocalResult::Single(dt) => dt,
+        LocalResult::Ambiguous(earliest, latest) => {
+            if latest.offset().fix() == offset {
+                latest
+            } else {
+                earliest
+            }
+        }
+        LocalResult::None => return None,
+    };
+    Some(dt.with_timezone(&Utc))
+}
+
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+fn from_parts(parts: &Parts<'_>) -> std::result::Result<DateTime, String> {
+    use time::{Date, Month, PrimitiveDateTime, Time, UtcOffset};
+
+    let offset = UtcOffset::from_whole_seconds(parts.offset_seconds).map_err(|e| e.to_string())?;
+    let month = Month::try_from(parts.month).map_err(|e| e.to_string())?;
+    let date = Date::from_calendar_date(parts.year, month, parts.day).map_err(|e| e.to_string())?;
+    let time = Time::from_hms_nano(parts.hour, parts.minute, parts.second, parts.nanosecond)
+        .map_err(|e| e.to_string())?;
+    let dt = PrimitiveDateTime::new(date, time).assume_offset(offset);
+    Ok(match ParserOptions::current(|options| options.time_zone) {
+        TimeZonePolicy::Utc => dt.to_offset(UtcOffset::UTC),
+        TimeZonePolicy::BankLocal => dt,
+        TimeZonePolicy::Offset(seconds) => {
+            dt.to_offset(UtcOffset::from_whole_seconds(seconds).unwrap_or(UtcOffset::UTC))
+        }
+    })
+}
+
+#[cfg(all(feature = "jiff", not(any(feature = "chrono", feature = "time"))))]
+fn from_parts(parts: &Parts<'_>) -> std::result::Result<DateTime, String> {
+    use jiff::{
+        civil,
+        tz::{Offset, TimeZone},
+    };
+
+    let offset = Offset::from_seconds(parts.offset_seconds).map_err(|e| e.to_string())?;
+    let zoned = civil::DateTime::new(
+        i16::try_from(parts.year).map_err(|e| e.to_string())?,
+        i8::try_from(parts.month).map_err(|e| e.to_string())?,
+        i8::try_from(parts.day).map_err(|e| e.to_string())?,
+        i8::try_from(parts.hour).map_err(|e| e.to_string())?,
+        i8::try_from(parts.minute).map_err(|e| e.to_string())?,
+        i8::try_from(parts.second).map_err(|e| e.to_string())?,
+        i32::try_from(parts.nanosecond).map_err(|e| e.to_string())?,
+    )
+    .and_then(|dt| dt.to_zoned(TimeZone::fixed(offset)))
+    .map_err(|e| e.to_string())?;
+    Ok(match ParserOptions::current(|options| options.time_zone) {
+        TimeZonePolicy::Utc => zoned.with_time_zone(TimeZone::UTC),
+        TimeZonePolicy::BankLocal => zoned,
+        TimeZonePolicy::Offset(seconds) => zoned.with_time_zone(TimeZone::fixed(
+            Offset::from_seconds(seconds).unwrap_or(Offset::UTC),
+        )),
+    })
+}
+
+/// Formats the calendar date of `dt` as `YYYY-MM-DD`, in UTC unless the backend keeps the parsed
+/// offset.
+#[cfg(feature = "chrono")]
+pub(crate) fn iso_date(dt: &DateTime) -> String {
+    dt.date_naive().to_string()
+}
+
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+pub(crate) fn iso_date(dt: &DateTime) -> String {
+    dt.date().to_string()
+}
+
+#[cfg(all(feature = "jiff", not(any(feature = "chrono", feature = "time"))))]
+pub(crate) fn iso_date(dt: &DateTime) -> String {
+    dt.date().to_string()
+}
+
+/// How many components of a datetime [`Format`] writes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Precision {
+    /// `YYYYMMDD`
+    Date,
+    /// `YYYYMMDDHHMMSS`
+    Seconds,
+    /// `YYYYMMDDHHMMSS.XXX`
+    #[default]
+    Milliseconds,
+}
+
+/// Settings for writing a [`DateTime`] as an OFX datetime, the inverse of [`parse`].
+///
+/// The default writes milliseconds in UTC, e.g. `20250901120000.000[0:GMT]`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Format {
+    pub precision: Precision,
+    /// The offset from UTC to express the time in, in seconds. Offsets of a day or more are
+    /// treated as zero.
+    pub offset_seconds: i32,
+    /// The zone name written after the offset, e.g. `EST`
+    pub zone_name: Option<String>,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            precision: Precision::default(),
+            offset_seconds: 0,
+            zone_name: Some("GMT".into()),
+        }
+    }
+}
+
+/// The calendar and clock components of `dt` at `offset_seconds` from UTC, with the
+/// sub-second part in milliseconds.
+#[cfg(feature = "chrono")]
+fn components(dt: &DateTime, offset_seconds: i32) -> [u32; 7] {
+    use chrono::{Datelike, FixedOffset, Offset, Timelike, Utc};
+
+    let offset = FixedOffset::east_opt(offset_seconds).unwrap_or(Utc.fix());
+    let dt = dt.with_timezone(&offset);
+    [
+        dt.year().try_into().unwrap_or_default(),
+        dt.month(),
+        dt.day(),
+        dt.hour(),
+        dt.minute(),
+        dt.second(),
+        dt.nanosecond() / 1_000_000,
+    ]
+}
+
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+fn components(dt: &DateTime, offset_seconds: i32) -> [u32; 7] {
+    let offset =
+        time::UtcOffset::from_whole_seconds(offset_seconds).unwrap_or(time::UtcOffset::UTC);
+    let dt = dt.to_offset(offset);
+    [
+        dt.year().try_into().unwrap_or_default(),
+        u8::from(dt.month()).into(),
+        dt.day().into(),
+        dt.hour().into(),
+        dt.minute().into(),
+        dt.second().into(),
+        dt.millisecond().into(),
+    ]
+}
+
+#[cfg(all(feature = "jiff", not(any(feature = "chrono", feature = "time"))))]
+fn components(dt: &DateTime, offset_seconds: i32) -> [u32; 7] {
+    use jiff::tz::{Offset, TimeZone};
+
+    let offset = Offset::from_seconds(offset_seconds).unwrap_or(Offset::UTC);
+    let dt = dt.with_time_zone(TimeZone::fixed(offset));
+    [
+        dt.year().try_into().unwrap_or_default(),
+        dt.month().try_into().unwrap_or_default(),
+        dt.day().try_into().unwrap_or_default(),
+        dt.hour().try_into().unwrap_or_default(),
+        dt.minute().try_into().unwrap_or_default(),
+        dt.second().try_into().unwrap_or_default(),
+        dt.millisecond().try_into().unwrap_or_default(),
+    ]
+}
+
+impl Format {
+    /// Writes `dt` as an OFX datetime.
+    #[must_use]
+    pub fn format(&self, dt: &DateTime) -> String {
+        use std::fmt::Write;
+
+        let offset_seconds = if self.offset_seconds.abs() < 86_400 {
+            self.offset_seconds
+        } else {
+            0
+        };
+        let [year, month, day, hour, minute, second, millisecond] = components(dt, offset_seconds);
+        let mut out = format!("{year:04}{month:02}{day:02}");
+        if self.precision != Precision::Date {
+            let _ = write!(out, "{hour:02}{minute:02}{second:02}");
+        }
+        if self.precision == Precision::Milliseconds {
+            let _ = write!(out, ".{millisecond:03}");
+        }
+
+        // Offsets are decimal hours, e.g. `-3.50` for UTC-03:30
+        let sign = if offset_seconds < 0 { "-" } else { "" };
+        let (hours, seconds) = (offset_seconds.abs() / 3600, offset_seconds.abs() % 3600);
+        let _ = write!(out, "[{sign}{hours}");
+        if seconds != 0 {
+            let _ = write!(out, ".{:02}", seconds * 100 / 3600);
+        }
+        if let Some(name) = &self.zone_name {
+            out.push(':');
+            out.push_str(name);
+        }
+        out.push(']');
+        out
+    }
+}
+
+/// Writes `dt` as an OFX datetime in UTC with millisecond precision, e.g.
+/// `20250901120000.000[0:GMT]`.
+#[must_use]
+pub fn format(dt: &DateTime) -> String {
+    Format::default().format(dt)
+}
+
+/// Serializes a [`DateTime`] as an OFX datetime, for use with `#[serde(serialize_with)]`.
+///
+/// # Errors
+///
+/// Returns any error from the serializer.
+pub fn serialize<S: Serializer>(
+    dt: &DateTime,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(dt))
+}
+
+/// Parses an OFX datetime such as `19961005132200.124[-5:EST]`.
+///
+/// The accepted layouts are `YYYYMMDD`, `YYYYMMDDHHMM` (which some banks send, omitting the
+/// seconds), `YYYYMMDDHHMMSS`, and `YYYYMMDDHHMMSS.XXX`, each optionally followed by a
+/// `[offset:NAME]` time zone. Missing time components are zero. The spec's three fractional
+/// digits may also be one or two, or more than three, which are truncated to nanoseconds.
+///
+/// A zone name without an offset, as in `[EST]` or `20190510120000 GMT`, is resolved from a
+/// table of common names, or treated as UTC if unknown.
+///
+/// # Errors
+///
+/// Returns an error if the value is not a valid OFX datetime.
+pub fn parse(s: &str) -> Result<DateTime> {
+    parse_parts(s)
+        .and_then(|parts| from_parts(&parts))
+        .map_err(|err| Error::parse(format!("unable to parse '{s}' as datetime: {err}")))
+}
+
+/// Parses an OFX datetime as [`parse`] does with [`ParserOptions::strict_datetimes`] set,
+/// accepting only the layouts the spec defines and rejecting impossible dates and times.
+///
+/// # Errors
+///
+/// Returns an error naming the offending component, e.g. `day 30 is out of range for 2025-02`.
+pub fn parse_strict(s: &str) -> Result<DateTime> {
+    let options = ParserOptions {
+        strict_datetimes: true,
+        ..ParserOptions::default()
+    };
+    options.scoped(|| parse(s)).0
+}
+
+pub(crate) fn deserialize_datetime<'de, D>(
+    deserializer: D,
+) -> std::result::Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::{Error as SerdeErr, IntoDeserializer, value::StrDeserializer};
+    let s = String::deserialize(deserializer)?;
+    parse_parts(&s)
+        .and_then(|parts| from_parts(&parts))
+        .or_else(|err| {
+            // Documents serialized to other formats hold the datetime type's own representation,
+            // e.g. RFC 3339 for chrono
+            let native: StrDeserializer<D::Error> = s.as_str().into_deserializer();
+            DateTime::deserialize(native).map_err(|_| err)
+        })
+        .map_err(|err| SerdeErr::custom(format!("unable to parse '{s}' as datetime: {err}")))
+}
+
+/// Like [`deserialize_datetime`], for optional fields. Pair with `#[serde(default)]`.
+pub(crate) fn deserialize_optional_datetime<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<DateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::{IntoDeserializer, value::StrDeserializer};
+    Option::<String>::deserialize(deserializer)?
+        .map(|raw| {
+            let de: StrDeserializer<D::Error> = raw.as_str().into_deserializer();
+            deserialize_datetime(de)
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "chrono")]
+    use chrono::{TimeZone, Timelike, Utc};
+
+    #[cfg(feature = "chrono")]
+    use serde::de::IntoDeserializer;
+    #[cfg(feature = "chrono")]
+    use serde::de::value::{Error as SerdeError, StrDeserializer};
+
+    #[test]
+    fn converts_dates() {
+        for date_str in ["19700101", "20000229", "20241231", "20250301"] {
+            assert_eq!(date(days(date_str).unwrap()), date_str);
+        }
+        assert_eq!(days("20250102").unwrap() - days("20241231").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_parse_parts() {
+        assert_eq!(
+            parse_parts("19961005132200.124[-5:EST]"),
+            Ok(Parts {
+                year: 1996,
+                month: 10,
+                day: 5,
+                hour: 13,
+                minute: 22,
+                second: 0,
+                nanosecond: 124_000_000,
+                offset_seconds: -5 * 3600,
+                has_offset: true,
+                zone_name: Some("EST"),
+            })
+        );
+        assert_eq!(
+            parse_parts("199610051322").map(|parts| (parts.hour, parts.minute, parts.second)),
+            Ok((13, 22, 0))
+        );
+        assert_eq!(
+            parse_parts("20190510120000.1[-5:EST]").map(|parts| parts.nanosecond),
+            Ok(100_000_000)
+        );
+        assert_eq!(
+            parse_parts("20190510120000.1234567891").map(|parts| parts.nanosecond),
+            Ok(123_456_789)
+        );
+        for (input, offset_seconds) in [
+            ("20190510120000 GMT", 0),
+            ("20190510120000[EST]", -5 * 3600),
+            ("20190510120000[XYZ]", 0),
+        ] {
+            assert_eq!(
+                parse_parts(input).map(|parts| parts.offset_seconds),
+                Ok(offset_seconds),
+                "{input}"
+            );
+        }
+        assert!(parse_parts("20190510120000[-:EST]").is_err());
+        let lenient = ParserOptions {
+            lenient: true,
+            ..ParserOptions::default()
+        };
+        let (parts, warnings) = lenient.scoped(|| parse_parts("20190510120000[-:EST]"));
+        assert_eq!(parts.map(|parts| parts.offset_seconds), Ok(-5 * 3600));
+        assert_eq!(warnings.len(), 1);
+        assert!(parse_parts("1996100513").is_err());
+        assert!(parse_parts("19961005132200.").is_err());
+        assert!(parse_parts("19961005132200,124").is_err());
+        assert!(parse_parts("1996AB05").is_err());
+    }
+
+    #[cfg(feature = "chrono_tz")]
+    #[test]
+    fn resolves_zone_names_without_offsets() {
+        // Two days after DST began: the offset defines the zone even where the name disagrees
+        let expected = Utc.with_ymd_and_hms(2025, 3, 11, 17, 0, 0).unwrap();
+        assert_eq!(parse("20250311120000[-5:EST]").unwrap(), expected);
+        assert_eq!(parse("20250311120000[EST]").unwrap(), expected);
+
+        let options = ParserOptions {
+            zone_names: true,
+            ..ParserOptions::default()
+        };
+        let (dt, _) = options.scoped(|| parse("20250311120000[-5:EST]"));
+        assert_eq!(dt.unwrap(), expected);
+        let (dt, _) = options.scoped(|| parse("20250311120000[EST]"));
+        assert_eq!(
+            dt.unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 11, 16, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_impossible_datetimes_strictly() {
+        for input in [
+            "19961005132200.124[-5:EST]",
+            "19961005132200.124[-5.0:]",
+            "19961005[+3]",
+            "20240229235959",
+        ] {
+            assert!(parse_strict(input).is_ok(), "{input}");
+        }
+        for (input, message) in [
+            ("20250230", "day 30 is out of range for 2025-02 (1-28)"),
+            ("20251301", "month 13 is out of range (1-12)"),
+            ("20250101250000", "hour 25 is out of range (0-23)"),
+            ("20250101120060", "second 60 is out of range (0-59)"),
+            ("202501011200", "missing seconds"),
+            ("20250101120000.1", "expected 3 fractional digits, found 1"),
+            ("20250101120000[EST]", "time zone `EST` has no offset"),
+            ("20250101120000 GMT", "is not of the form"),
+            ("20250101120000[-5:EST]x", "is not of the form"),
+            ("20250101120000[nan:EST]", "malformed timezone offset `nan`"),
+            (
+                "20250101120000[-5.125]",
+                "malformed timezone offset `-5.125`",
+            ),
+        ] {
+            let err = parse_strict(input).unwrap_err().to_string();
+            assert!(err.contains(message), "{input}: {err}");
+        }
+        assert!(parse("202501011200").is_ok());
+    }
+
+    #[test]
+    fn formats_datetimes() {
+        let dt = || parse("19961005132200.124[-5:EST]").unwrap();
+        assert_eq!(format(&dt()), "19961005182200.124[0:GMT]");
+        let eastern = Format {
+            precision: Precision::Seconds,
+            offset_seconds: -5 * 3600,
+            zone_name: Some("EST".into()),
+        };
+        assert_eq!(eastern.format(&dt()), "19961005132200[-5:EST]");
+        let newfoundland = Format {
+            precision: Precision::Date,
+            offset_seconds: -(3 * 3600 + 1800),
+            zone_name: None,
+        };
+        assert_eq!(newfoundland.format(&dt()), "19961005[-3.50]");
+        let roundtrip = parse(&format(&dt())).unwrap();
+        assert_eq!(format(&roundtrip), format(&dt()));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_parse_date_spec_example() {
+        // Per 1.6 spec, 3.2.8.1
+        // There is one format for representing dates, times, and time zones. The complete form is:
+        // YYYYMMDDHHMMSS.XXX [gmt offset[:tz name]]
+        //
+        // 3.2.8.2
+        // For example, “19961005132200.124[-5:EST]”
+        // This is the same as 6:22 p.m. Greenwich Mean Time (GMT).
+
+        let examples = [
+            (
+                "19961005132200.124[-5:EST]",
+                Utc.with_ymd_and_hms(1996, 10, 5, 18, 22, 0)
+                    .unwrap()
+                    .with_nanosecond(124_000_000)
+                    .unwrap(),
+            ),
+            (
+                "19961005132200.124[-5]",
+                Utc.with_ymd_and_hms(1996, 10, 5, 18, 22, 0)
+                    .unwrap()
+                    .with_nanosecond(124_000_000)
+                    .unwrap(),
+            ),
+            (
+                "19961005132200.124",
+                Utc.with_ymd_and_hms(1996, 10, 5, 13, 22, 0)
+                    .unwrap()
+                    .with_nanosecond(124_000_000)
+                    .unwrap(),
+            ),
+            (
+                "19961005132200",
+                Utc.with_ymd_and_hms(1996, 10, 5, 13, 22, 0).unwrap(),
+            ),
+            (
+                "19961005",
+                Utc.with_ymd_and_hms(1996, 10, 5, 0, 0, 0).unwrap(),
+            ),
+        ];
+
+        for (input, expected) in examples {
+            let deserializer: StrDeserializer<SerdeError> = input.into_deserializer();
+            assert_eq!(deserialize_datetime(deserializer), Ok(expected));
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_alt_timezone_formats() {
+        // Per 1.6 spec, 3.2.8.2:
+        // Note that times zones are specified by an offset and optionally, a time zone name. The offset
+        // defines the time zone. Valid offset values are in the range from –12 to +12 for whole number
+        // offsets. Formatting is +12.00 to -12.00 for fractional offsets, plus sign may be omitted.
+        let examples = [
+            "19961005132200.124[-5:EST]",
+            "19961005132200.124[-5]",
+            "19961005132200.124[-5:]",
+            "19961005132200.124[-5.0:EST]",
+            "19961005212200.124[+3:EST]",
+            "19961005212200.124[+3]",
+            "19961005212200.124[+3:]",
+            "19961005212200.124[+3.0:EST]",
+        ];
+
+        let expected = Utc
+            .with_ymd_and_hms(1996, 10, 5, 18, 22, 0)
+            .unwrap()
+            .with_nanosecond(124_000_000)
+            .unwrap();
+
+        for input in examples {
+            let deserializer: StrDeserializer<SerdeError> = input.into_deserializer();
+            assert_eq!(deserialize_datetime(deserializer), Ok(expected));
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn applies_time_zone_policy() {
+        let parse_with = |time_zone| {
+            let options = ParserOptions {
+                time_zone,
+                ..ParserOptions::default()
+            };
+            options
+                .scoped(|| parse("20250101013000[-5:EST]"))
+                .0
+                .unwrap()
+        };
+        let hm = |dt: DateTime| (dt.date_naive().to_string(), dt.hour(), dt.minute());
+        assert_eq!(
+            hm(parse_with(TimeZonePolicy::Utc)),
+            ("2025-01-01".into(), 6, 30)
+        );
+        assert_eq!(
+            hm(parse_with(TimeZonePolicy::BankLocal)),
+            ("2025-01-01".into(), 1, 30)
+        );
+        assert_eq!(
+            hm(parse_with(TimeZonePolicy::Offset(-8 * 3600))),
+            ("2024-12-31".into(), 22, 30)
+        );
+    }
+
+    #[cfg(all(feature = "time", not(feature = "chrono")))]
+    #[test]
+    fn test_time_backend() {
+        use time::macros::datetime;
+
+        assert_eq!(
+            parse("19961005132200.124[-5:EST]").unwrap(),
+            datetime!(1996-10-05 18:22:00.124 UTC)
+        );
+        assert_eq!(
+            parse("19961005").unwrap(),
+            datetime!(1996-10-05 00:00:00 UTC)
+        );
+    }
+
+    #[cfg(all(feature = "jiff", not(any(feature = "chrono", feature = "time"))))]
+    #[test]
+    fn test_jiff_backend() {
+        let dt = parse("19961005132200.124[-5:EST]").unwrap();
+        assert_eq!(dt.offset().seconds(), -5 * 3600);
+        assert_eq!(
+            dt.timestamp(),
+            "1996-10-05T18:22:00.124Z"
+                .parse::<jiff::Timestamp>()
+                .unwrap()
+        );
+
+        let utc = ParserOptions {
+            time_zone: TimeZonePolicy::Utc,
+            ..ParserOptions::default()
+        };
+        let dt = utc
+            .scoped(|| parse("19961005132200.124[-5:EST]"))
+            .0
+            .unwrap();
+        assert_eq!(dt.offset().seconds(), 0);
+        assert_eq!(dt.timestamp().to_string(), "1996-10-05T18:22:00.124Z");
+    }
+}
@@ -0,0 +1,209 @@
+//! The decimal type used for amounts in the public API.
+//!
+//! With the default `rust_decimal` feature, [`Decimal`] is `rust_decimal::Decimal`. Without it,
+//! amounts are exposed as [`Amount`], a validated string newtype, for minimal builds such as
+//! embedded or wasm targets.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Result, error::Error};
+
+#[cfg(feature = "rust_decimal")]
+pub type Decimal = rust_decimal::Decimal;
+
+#[cfg(not(feature = "rust_decimal"))]
+pub type Decimal = Amount;
+
+/// A decimal amount, validated and stored in canonical form (e.g. `-16.4`).
+///
+/// Leading zeros, trailing fractional zeros, and a leading `+` are removed, so amounts that are
+/// numerically equal compare equal.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Amount(String);
+
+impl Amount {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Amount {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if (int.is_empty() && frac.is_empty())
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(Error::parse(format!("invalid amount: {s}")));
+        }
+
+        let int = int.trim_start_matches('0');
+        let frac = frac.trim_end_matches('0');
+        let mut canonical = String::with_capacity(unsigned.len() + 2);
+        if negative && !(int.is_empty() && frac.is_empty()) {
+            canonical.push('-');
+        }
+        canonical.push_str(if int.is_empty() { "0" } else { int });
+        if !frac.is_empty() {
+            canonical.push('.');
+            canonical.push_str(frac);
+        }
+        Ok(Self(canonical))
+    }
+}
+
+impl From<i32> for Amount {
+    fn from(value: i32) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<i64> for Amount {
+    fn from(value: i64) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        s.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(d)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Rewrites an amount as some exports write it to the form the spec requires, e.g. `-16,40`
+/// (decimal comma), `1,234.56` or `1.234,56` (thousands separators), or `(16.40)` (negative).
+///
+/// The last separator is the decimal one when both kinds appear. A lone comma is a thousands
+/// separator if exactly three digits follow it, and a decimal comma otherwise. Returns `None`
+/// for amounts that are already valid or cannot be fixed.
+pub(crate) fn lenient(s: &str) -> Option<String> {
+    if s.parse::<Amount>().is_ok() {
+        return None;
+    }
+    let (sign, unsigned) = match s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        Some(inner) if !inner.starts_with(['-', '+']) => ("-", inner.trim()),
+        Some(_) => return None,
+        None => match s.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", s.strip_prefix('+').unwrap_or(s)),
+        },
+    };
+
+    let (thousands, decimal) = match (unsigned.rfind(','), unsigned.rfind('.')) {
+        (Some(comma), Some(point)) if comma > point => ('.', ','),
+        (Some(comma), None)
+            if unsigned.matches(',').count() == 1 && unsigned.len() - comma - 1 != 3 =>
+        {
+            ('.', ',')
+        }
+        _ => (',', '.'),
+    };
+    let (int, frac) = match unsigned.split_once(decimal) {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (unsigned, None),
+    };
+    let mut groups = int.split(thousands);
+    let first_ok = groups
+        .next()
+        .is_some_and(|group| group.len() <= 3 || !int.contains(thousands));
+    if !first_ok || !groups.all(|group| group.len() == 3) {
+        return None;
+    }
+
+    let mut fixed = format!("{sign}{}", int.replace(thousands, ""));
+    if let Some(frac) = frac {
+        fixed.push('.');
+        fixed.push_str(frac);
+    }
+    fixed.parse::<Amount>().is_ok().then_some(fixed)
+}
+
+/// `value` scaled down by `scale` decimal places, e.g. `scaled(-1250, 2)` is `-12.50`.
+#[cfg(feature = "rust_decimal")]
+pub(crate) fn scaled(value: i64, scale: u32) -> Decimal {
+    Decimal::new(value, scale)
+}
+
+/// `value` scaled down by `scale` decimal places, e.g. `scaled(-1250, 2)` is `-12.5`.
+#[cfg(not(feature = "rust_decimal"))]
+pub(crate) fn scaled(value: i64, scale: u32) -> Decimal {
+    let digits = format!("{:0>1$}", value.unsigned_abs(), scale as usize + 1);
+    let (int, frac) = digits.split_at(digits.len() - scale as usize);
+    let frac = frac.trim_end_matches('0');
+    let sign = if value < 0 { "-" } else { "" };
+    Amount(if frac.is_empty() {
+        format!("{sign}{int}")
+    } else {
+        format!("{sign}{int}.{frac}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_canonical_form() {
+        let examples = [
+            ("-16.40", "-16.4"),
+            ("+0010", "10"),
+            ("-0.00", "0"),
+            (".5", "0.5"),
+            ("1000.", "1000"),
+        ];
+        for (input, expected) in examples {
+            assert_eq!(input.parse::<Amount>().unwrap().as_str(), expected);
+        }
+        assert_eq!("-10.00".parse::<Amount>().unwrap(), Amount::from(-10));
+        assert_eq!(
+            scaled(-1250, 2).to_string(),
+            "-12.50".parse::<Decimal>().unwrap().to_string()
+        );
+        assert_eq!(
+            scaled(7, 3).to_string(),
+            "0.007".parse::<Decimal>().unwrap().to_string()
+        );
+        assert!("".parse::<Amount>().is_err());
+        assert!("1.2.3".parse::<Amount>().is_err());
+        assert!("12a".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn fixes_lenient_amounts() {
+        assert_eq!(lenient("-16,40").as_deref(), Some("-16.40"));
+        assert_eq!(lenient("-16.40"), None);
+        assert_eq!(lenient("1,2,3"), None);
+        assert_eq!(lenient("abc,1"), None);
+
+        assert_eq!(lenient("1,234.56").as_deref(), Some("1234.56"));
+        assert_eq!(lenient("-1.234.567,8").as_deref(), Some("-1234567.8"));
+        assert_eq!(lenient("1,234").as_deref(), Some("1234"));
+        assert_eq!(lenient("(16.40)").as_deref(), Some("-16.40"));
+        assert_eq!(lenient("(1,234.56)").as_deref(), Some("-1234.56"));
+        assert_eq!(lenient("(-16.40)"), None);
+        assert_eq!(lenient("12,34.56"), None);
+    }
+}
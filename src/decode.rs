@@ -0,0 +1,164 @@
+//! Decoding documents from bytes.
+//!
+//! With the `encoding` feature, the charset declared in the header (e.g. `CHARSET:1252` is
+//! Windows-1252) is used to decode, and detection is only a fallback for documents that declare
+//! none or are invalid in the one they declare. Without it, bytes are read as UTF-8, replacing
+//! invalid sequences with U+FFFD.
+//!
+//! With the `flate2` feature, gzipped input, such as an `.ofx.gz` download, is decompressed
+//! first.
+
+use std::borrow::Cow;
+
+use crate::warning::Warning;
+
+#[cfg(feature = "encoding")]
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+
+/// The magic bytes that start a gzip stream.
+#[cfg(feature = "flate2")]
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Decompresses `bytes` if they are gzipped, refusing to inflate past `max` bytes.
+#[cfg(feature = "flate2")]
+pub(crate) fn gunzip(bytes: &[u8], max: usize) -> crate::Result<Cow<'_, [u8]>> {
+    use std::io::Read;
+
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Ok(Cow::Borrowed(bytes));
+    }
+    let limit = u64::try_from(max).unwrap_or(u64::MAX).saturating_add(1);
+    let mut out = Vec::new();
+    flate2::read::MultiGzDecoder::new(bytes)
+        .take(limit)
+        .read_to_end(&mut out)?;
+    crate::ParserOptions::check("file size", out.len(), max)?;
+    Ok(Cow::Owned(out))
+}
+
+/// The encoding declared by the `ENCODING` and `CHARSET` headers or the XML declaration.
+#[cfg(feature = "encoding")]
+fn declared(bytes: &[u8]) -> Option<&'static Encoding> {
+    let info = crate::detect::detect(bytes);
+    let encoding = info.encoding?;
+    match encoding.trim().to_ascii_uppercase().as_str() {
+        "UNICODE" | "UTF-8" | "UTF8" => Some(UTF_8),
+        "USASCII" => match info.charset?.trim() {
+            "1252" => Some(WINDOWS_1252),
+            charset => Encoding::for_label(charset.as_bytes()),
+        },
+        label => Encoding::for_label(label.as_bytes()),
+    }
+}
+
+#[cfg(feature = "encoding")]
+fn detected(bytes: &[u8]) -> &'static Encoding {
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, true)
+}
+
+/// Decodes a document, returning the text and any warnings about its encoding.
+#[cfg(feature = "encoding")]
+#[must_use]
+pub fn decode(bytes: &[u8]) -> (Cow<'_, str>, Vec<Warning>) {
+    let mut warnings = Vec::new();
+    let detected = detected(bytes);
+    let Some(declared) = declared(bytes) else {
+        return (detected.decode(bytes).0, warnings);
+    };
+
+    let (text, _, had_errors) = declared.decode(bytes);
+    if had_errors {
+        warnings.push(Warning::Fixup(format!(
+            "content is not valid {}; decoded as {}",
+            declared.name(),
+            detected.name()
+        )));
+        return (detected.decode(bytes).0, warnings);
+    }
+    // Pure ASCII reads the same in either, so only disagreements that matter are reported
+    if declared != detected && !bytes.is_ascii() {
+        warnings.push(Warning::EncodingMismatch {
+            declared: declared.name().into(),
+            detected: detected.name().into(),
+        });
+    }
+    (text, warnings)
+}
+
+/// Decodes a document with `charset` rather than the declared charset if it is a known label
+/// and the bytes are valid in it, otherwise as [`decode`] does.
+#[cfg(feature = "encoding")]
+pub(crate) fn decode_as<'a>(
+    bytes: &'a [u8],
+    charset: Option<&str>,
+) -> (Cow<'a, str>, Vec<Warning>) {
+    if let Some(encoding) = charset.and_then(|label| Encoding::for_label(label.as_bytes()))
+        && let (text, _, false) = encoding.decode(bytes)
+    {
+        return (text, Vec::new());
+    }
+    decode(bytes)
+}
+
+/// Decodes a document as [`decode`] does; the charset is only honored with the `encoding`
+/// feature.
+#[cfg(not(feature = "encoding"))]
+pub(crate) fn decode_as<'a>(
+    bytes: &'a [u8],
+    _charset: Option<&str>,
+) -> (Cow<'a, str>, Vec<Warning>) {
+    decode(bytes)
+}
+
+/// Decodes a document, returning the text and any warnings about its encoding.
+#[cfg(not(feature = "encoding"))]
+#[must_use]
+pub fn decode(bytes: &[u8]) -> (Cow<'_, str>, Vec<Warning>) {
+    let text = String::from_utf8_lossy(bytes);
+    let mut warnings = Vec::new();
+    if matches!(text, Cow::Owned(_)) {
+        warnings.push(Warning::Fixup(
+            "replaced bytes that are not valid UTF-8".into(),
+        ));
+    }
+    (text, warnings)
+}
+
+#[cfg(all(test, feature = "encoding"))]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\nSECURITY:NONE\r\n";
+
+    #[test]
+    fn decodes_declared_charset() {
+        let mut bytes =
+            format!("{HEADER}ENCODING:USASCII\r\nCHARSET:1252\r\n\r\n<OFX><NAME>CAF").into_bytes();
+        bytes.push(0xc9);
+        let (text, _) = decode(&bytes);
+        assert!(text.ends_with("<NAME>CAFÉ"));
+
+        let mut bytes =
+            format!("{HEADER}ENCODING:UNICODE\r\nCHARSET:NONE\r\n\r\n<OFX><NAME>CAF").into_bytes();
+        bytes.push(0xc9);
+        let (text, warnings) = decode(&bytes);
+        assert!(text.ends_with("<NAME>CAFÉ"));
+        assert!(matches!(&warnings[..], [Warning::Fixup(_)]));
+    }
+
+    #[test]
+    fn decodes_with_charset_override() {
+        let bytes = format!("{HEADER}ENCODING:USASCII\r\nCHARSET:1252\r\n\r\n<OFX><NAME>CAFÉ");
+        let bytes = bytes.as_bytes();
+        assert!(decode(bytes).0.ends_with("<NAME>CAFÃ‰"));
+        let (text, warnings) = decode_as(bytes, Some("UTF-8"));
+        assert!(text.ends_with("<NAME>CAFÉ"));
+        assert!(warnings.is_empty());
+
+        let mut bytes = bytes[..bytes.len() - 2].to_vec();
+        bytes.push(0xc9);
+        assert!(decode_as(&bytes, Some("UTF-8")).0.ends_with("<NAME>CAFÉ"));
+    }
+}
@@ -0,0 +1,110 @@
+//! Cheap format detection for triaging files before a full parse.
+
+/// How many leading bytes [`detect`] inspects.
+const SNIFF_LEN: usize = 4096;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Syntax {
+    /// OFX 1.x, with colon-delimited headers and an SGML body
+    Sgml,
+    /// OFX 2.x, with an `<?OFX ...?>` processing instruction and an XML body
+    Xml,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FormatInfo {
+    pub is_ofx: bool,
+    /// The raw `VERSION` header value, e.g. `102` or `211`
+    pub version: Option<String>,
+    pub syntax: Option<Syntax>,
+    /// The raw `ENCODING` header, or the XML declaration's `encoding`
+    pub encoding: Option<String>,
+    pub charset: Option<String>,
+}
+
+fn xml_attribute(s: &str, name: &str) -> Option<String> {
+    let start = s.find(&format!("{name}=\""))? + name.len() + 2;
+    let len = s[start..].find('"')?;
+    Some(s[start..start + len].to_owned())
+}
+
+/// Returns the processing instruction starting with `opening`, up to its closing `?>`.
+fn processing_instruction<'a>(s: &'a str, opening: &str) -> Option<&'a str> {
+    let start = s.find(opening)?;
+    let end = s[start..].find("?>").map_or(s.len(), |end| start + end);
+    Some(&s[start..end])
+}
+
+/// Inspects the beginning of `bytes` to determine whether they look like OFX, and if so which
+/// version, syntax, and encoding they declare.
+///
+/// This never fails; fields that cannot be determined are `None`.
+#[must_use]
+pub fn detect(bytes: &[u8]) -> FormatInfo {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(SNIFF_LEN)]);
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+
+    if let Some(prolog) = processing_instruction(head, "<?OFX") {
+        let declaration = processing_instruction(head, "<?xml");
+        return FormatInfo {
+            is_ofx: true,
+            version: xml_attribute(prolog, "VERSION"),
+            syntax: Some(Syntax::Xml),
+            encoding: declaration.and_then(|decl| xml_attribute(decl, "encoding")),
+            charset: None,
+        };
+    }
+
+    if head.starts_with("OFXHEADER:") {
+        let mut info = FormatInfo {
+            is_ofx: true,
+            syntax: Some(Syntax::Sgml),
+            ..FormatInfo::default()
+        };
+        for line in crate::header::normalize_line_endings(head)
+            .lines()
+            .map(str::trim)
+            .take_while(|line| !line.starts_with('<'))
+        {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = Some(value.trim().to_owned());
+            match key.trim() {
+                "VERSION" => info.version = value,
+                "ENCODING" => info.encoding = value,
+                "CHARSET" => info.charset = value,
+                _ => {}
+            }
+        }
+        return info;
+    }
+
+    FormatInfo {
+        is_ofx: head.contains("<OFX>"),
+        ..FormatInfo::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_formats() {
+        let sgml = detect(b"OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\nENCODING:USASCII\r\nCHARSET:1252\r\n\r\n<OFX>");
+        assert_eq!(sgml.syntax, Some(Syntax::Sgml));
+        assert_eq!(sgml.version.as_deref(), Some("102"));
+        assert_eq!(sgml.encoding.as_deref(), Some("USASCII"));
+        assert_eq!(sgml.charset.as_deref(), Some("1252"));
+
+        let xml = detect(
+            br#"<?xml version="1.0" encoding="UTF-8"?><?OFX OFXHEADER="200" VERSION="211"?><OFX>"#,
+        );
+        assert_eq!(xml.syntax, Some(Syntax::Xml));
+        assert_eq!(xml.version.as_deref(), Some("211"));
+        assert_eq!(xml.encoding.as_deref(), Some("UTF-8"));
+
+        assert!(!detect(b"Date,Amount,Payee\n").is_ofx);
+    }
+}
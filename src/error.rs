@@ -1,20 +1,173 @@
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+use std::error::Error as StdError;
+
+type Source = Box<dyn StdError + Send + Sync + 'static>;
 
+/// The category of an [`Error`], for callers that need to handle failures programmatically.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    #[error("I/O error")]
+    Io,
+
+    /// The input is not valid OFX (or MT940, OFX Home XML, ...)
     #[error("parse error: {0}")]
-    ParseError(String),
+    Parse(String),
+
+    #[error("builder error: {0}")]
+    Builder(String),
+
+    /// The SGML could not be tokenized or its end tags could not be normalized
+    #[error("invalid SGML")]
+    Sgml,
+
+    /// The SGML is well formed but does not match the expected aggregates
+    #[error("unexpected document structure")]
+    Deserialize,
+
+    /// The document uses an OFX version Ofxy cannot parse, e.g. a 2.x (XML) file
+    #[error("Ofxy does not yet support OFX version: {found} (supported: {})", .supported.join(", "))]
+    UnsupportedVersion {
+        found: String,
+        supported: &'static [&'static str],
+    },
+
+    /// The input exceeds a resource limit, e.g. it is nested too deeply
+    #[error("input exceeds the {limit} limit of {max}")]
+    LimitExceeded { limit: &'static str, max: usize },
+
+    /// A response's `<TRNUID>` does not echo the one sent in the request
+    #[error("response TRNUID {found} does not match request {expected}")]
+    TransactionMismatch { expected: String, found: String },
+
+    /// A response's `OLDFILEUID`/`NEWFILEUID` does not continue the client's chain of files,
+    /// so a download may have been missed
+    #[error("file UID {found} does not continue from {expected}")]
+    FileUidGap { expected: String, found: String },
+
+    /// The header declares `SECURITY:TYPE1` and the body is not readable SGML, so it is likely
+    /// encrypted
+    #[error(
+        "the document uses SECURITY:TYPE1 and its body could not be read, so it is likely \
+         encrypted; download it again without Type 1 security"
+    )]
+    EncryptedBody,
+
+    #[cfg(feature = "keyring")]
+    #[error("keyring error")]
+    Keyring,
+
+    #[cfg(feature = "ledger")]
+    #[error("JSON error")]
+    Json,
+
+    #[cfg(feature = "zip")]
+    #[error("ZIP archive error")]
+    Zip,
+}
+
+/// An error with its [`ErrorKind`], the underlying error if any, and where in the document it
+/// occurred if known.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "{}{kind}{}",
+    .location.as_ref().map(|location| format!("in {location}: ")).unwrap_or_default(),
+    .source.as_ref().map(|source| format!(": {source}")).unwrap_or_default(),
+)]
+#[non_exhaustive]
+pub struct Error {
+    kind: ErrorKind,
+    #[source]
+    source: Option<Source>,
+    location: Option<String>,
+}
+
+impl Error {
+    #[must_use]
+    pub fn new(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            source: None,
+            location: None,
+        }
+    }
+
+    pub(crate) fn parse(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Parse(message.into()))
+    }
+
+    /// Attaches the underlying error that caused this one.
+    #[must_use]
+    pub fn with_source(mut self, source: impl Into<Source>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Attaches the path to the aggregate that caused this error, e.g.
+    /// `OFX > BANKMSGSRSV1 > STMTTRNRS > STMTRS > BANKTRANLIST > STMTTRN[3]`.
+    #[must_use]
+    pub fn with_location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    #[must_use]
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty => $kind:expr),+ $(,)?) => {
+        $(
+            impl From<$ty> for Error {
+                fn from(err: $ty) -> Self {
+                    Self::new($kind).with_source(err)
+                }
+            }
+        )+
+    };
+}
+
+impl_from!(
+    std::io::Error => ErrorKind::Io,
+    std::num::ParseIntError => ErrorKind::Parse("invalid integer".into()),
+    sgmlish::Error => ErrorKind::Sgml,
+    sgmlish::transforms::NormalizationError => ErrorKind::Sgml,
+    sgmlish::de::DeserializationError => ErrorKind::Deserialize,
+);
+
+#[cfg(feature = "keyring")]
+impl_from!(keyring::Error => ErrorKind::Keyring);
 
-    #[error(transparent)]
-    Sgmlish(#[from] sgmlish::Error),
+#[cfg(feature = "ledger")]
+impl_from!(serde_json::Error => ErrorKind::Json);
 
-    #[error(transparent)]
-    Normalization(#[from] sgmlish::transforms::NormalizationError),
+#[cfg(feature = "zip")]
+impl_from!(zip::result::ZipError => ErrorKind::Zip);
 
-    #[error(transparent)]
-    SgmlishDe(#[from] sgmlish::de::DeserializationError),
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[error(transparent)]
-    ParseInt(#[from] std::num::ParseIntError),
+    #[test]
+    fn displays_location_and_source() {
+        let err = Error::from("x".parse::<u8>().unwrap_err()).with_location("OFX > SIGNONMSGSRSV1");
+        assert_eq!(err.kind(), &ErrorKind::Parse("invalid integer".into()));
+        assert_eq!(
+            err.to_string(),
+            "in OFX > SIGNONMSGSRSV1: parse error: invalid integer: invalid digit found in string"
+        );
+        assert!(err.source().is_some());
+    }
 }
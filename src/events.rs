@@ -0,0 +1,101 @@
+//! A low-level, SAX-style event API.
+//!
+//! Useful for consuming message sets that do not (yet) have typed support. Events are reported
+//! after OFX-aware normalization: entities are expanded, omitted end tags are filled in, and
+//! whitespace-only text is skipped.
+
+use sgmlish::SgmlEvent;
+
+use crate::{ParserOptions, Result, body::normalized_fragment};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    StartTag(String),
+    Text(String),
+    EndTag(String),
+}
+
+/// Receives events in document order. All methods default to doing nothing.
+pub trait Visitor {
+    fn start_tag(&mut self, _name: &str) {}
+    fn text(&mut self, _text: &str) {}
+    fn end_tag(&mut self, _name: &str) {}
+}
+
+impl Visitor for Vec<Event> {
+    fn start_tag(&mut self, name: &str) {
+        self.push(Event::StartTag(name.to_owned()));
+    }
+
+    fn text(&mut self, text: &str) {
+        self.push(Event::Text(text.to_owned()));
+    }
+
+    fn end_tag(&mut self, name: &str) {
+        self.push(Event::EndTag(name.to_owned()));
+    }
+}
+
+/// Walks an OFX document (or a bare SGML fragment) and reports its events to `visitor`.
+///
+/// # Errors
+///
+/// Returns an error if the SGML cannot be parsed or its end tags cannot be normalized.
+pub fn visit<V: Visitor + ?Sized>(s: &str, visitor: &mut V) -> Result<()> {
+    let body = s.find("<OFX>").map_or(s, |start| &s[start..]);
+    let mut open = Vec::new();
+    for event in &normalized_fragment(body, &ParserOptions::default())? {
+        match event {
+            SgmlEvent::OpenStartTag { name } => {
+                visitor.start_tag(name);
+                open.push(name.to_string());
+            }
+            SgmlEvent::Character(text) if !text.trim().is_empty() => visitor.text(text),
+            SgmlEvent::EndTag { name } => {
+                open.pop();
+                visitor.end_tag(name);
+            }
+            SgmlEvent::XmlCloseEmptyElement => {
+                if let Some(name) = open.pop() {
+                    visitor.end_tag(&name);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Collects all events of a document.
+///
+/// # Errors
+///
+/// See [`visit`].
+pub fn events(s: &str) -> Result<Vec<Event>> {
+    let mut events = Vec::new();
+    visit(s, &mut events)?;
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_are_normalized() {
+        let events = events("OFXHEADER:100\n\n<OFX><CODE>0<MEMO>A &amp; B</OFX>").unwrap();
+        assert_eq!(
+            events,
+            [
+                Event::StartTag("OFX".into()),
+                Event::StartTag("CODE".into()),
+                Event::Text("0".into()),
+                Event::EndTag("CODE".into()),
+                Event::StartTag("MEMO".into()),
+                Event::Text("A & B".into()),
+                Event::EndTag("MEMO".into()),
+                Event::EndTag("OFX".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Re-indenting OFX documents and tabulating transactions, for human diffing and inspection.
+
+use crate::{
+    ParserOptions, Result,
+    body::{Transaction, normalized_fragment},
+    datetime,
+    tree::Element,
+};
+
+fn escape(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+fn write_element(element: &Element, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    out.push_str(&indent);
+    out.push('<');
+    out.push_str(&element.name);
+    out.push('>');
+    if element.children.is_empty() {
+        // Leaves keep the OFX 1.x style of omitting their end tag
+        escape(element.text.as_deref().unwrap_or_default().trim(), out);
+        out.push('\n');
+        return;
+    }
+    out.push('\n');
+    for child in &element.children {
+        write_element(child, depth + 1, out);
+    }
+    out.push_str(&indent);
+    out.push_str("</");
+    out.push_str(&element.name);
+    out.push_str(">\n");
+}
+
+/// Re-indents a document with one element per line, two spaces per level of nesting.
+///
+/// Header lines are trimmed, line endings become `\n`, and leaf values are trimmed. Only the
+/// SGML structure needs to be valid: the document is never deserialized into the typed model,
+/// so unknown aggregates or bad values are kept as they are.
+///
+/// # Errors
+///
+/// Returns an error if the SGML cannot be parsed or its end tags cannot be normalized.
+pub fn pretty(s: &str) -> Result<String> {
+    let start = s.find("<OFX>").unwrap_or(0);
+    let mut out = String::new();
+    for line in s[..start].lines().map(str::trim).filter(|l| !l.is_empty()) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    let fragment = normalized_fragment(&s[start..], &ParserOptions::default())?;
+    write_element(&Element::from_fragment(&fragment)?, 0, &mut out);
+    Ok(out)
+}
+
+const HEADINGS: [&str; 5] = ["Date", "Type", "Amount", "Payee", "Memo"];
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders transactions as a table of date, type, amount, payee, and memo, with aligned columns.
+///
+/// Amounts are right-aligned. With `color`, negative amounts are wrapped in red ANSI escapes and
+/// positive ones in green; leave it off when the output is not a terminal.
+#[must_use]
+pub fn table<'a>(transactions: impl IntoIterator<Item = &'a Transaction>, color: bool) -> String {
+    let rows: Vec<[String; 5]> = transactions
+        .into_iter()
+        .map(|transaction| {
+            [
+                datetime::iso_date(&transaction.date_posted),
+                transaction.transaction_type.code().to_owned(),
+                transaction.amount.to_string(),
+                transaction
+                    .name
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_owned(),
+                transaction
+                    .memo
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_owned(),
+            ]
+        })
+        .collect();
+
+    let mut widths = HEADINGS.map(|heading| heading.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut write_row = |cells: [&str; 5], amount_color: Option<&str>| {
+        let mut line = String::new();
+        for (i, (cell, width)) in cells.into_iter().zip(widths).enumerate() {
+            if i > 0 {
+                line.push_str("  ");
+            }
+            let padded = if i == 2 {
+                format!("{cell:>width$}")
+            } else {
+                format!("{cell:<width$}")
+            };
+            match amount_color.filter(|_| i == 2) {
+                Some(code) => {
+                    line.push_str(code);
+                    line.push_str(&padded);
+                    line.push_str(RESET);
+                }
+                None => line.push_str(&padded),
+            }
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    };
+
+    write_row(HEADINGS, None);
+    for row in &rows {
+        let amount_color = color.then(|| if row[2].starts_with('-') { RED } else { GREEN });
+        write_row(row.each_ref().map(String::as_str), amount_color);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reindents_documents() {
+        let input = "OFXHEADER:100\r\n  VERSION:102\r\n\r\n\
+            <OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1<UNKNOWN><CODE>x &amp; y</UNKNOWN>\
+            </STMTTRNRS></BANKMSGSRSV1></OFX>";
+        assert_eq!(
+            pretty(input).unwrap(),
+            "OFXHEADER:100\nVERSION:102\n\n\
+             <OFX>\n  <BANKMSGSRSV1>\n    <STMTTRNRS>\n      <TRNUID>1\n      <UNKNOWN>\n        \
+             <CODE>x &amp; y\n      </UNKNOWN>\n    </STMTTRNRS>\n  </BANKMSGSRSV1>\n</OFX>\n"
+        );
+    }
+
+    #[test]
+    fn tabulates_transactions() {
+        let input = std::fs::read_to_string("tests/files/simple.ofx").unwrap();
+        let ofx: crate::Ofx = input.parse().unwrap();
+        let statement = ofx.body.credit_card.unwrap().transaction_response.statement;
+        let transactions = statement.unwrap().bank_transactions.unwrap().transactions;
+
+        let table = table(&transactions[2..], false);
+        assert_eq!(
+            table,
+            "Date        Type      Amount  Payee                                                      Memo\n\
+             2024-04-30  PAYMENT  3220.56  ACH DEPOSIT INTERNET TRANSFER FROM ACCOUNT ENDING IN 1234\n\
+             2024-04-29  DEBIT     -46.05  AN AWESOME RESTAURANT\n"
+        );
+    }
+}
@@ -1,13 +1,27 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{borrow::Cow, collections::HashMap, fmt, str::FromStr};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{Result, error::Error};
+use crate::{
+    ParserOptions, Result,
+    error::{Error, ErrorKind},
+    warning::Warning,
+};
+
+/// Converts bare `\r` and `\r\n` line endings to `\n`, since some exports use old Mac line
+/// endings or mix them within the header block.
+pub(crate) fn normalize_line_endings(s: &str) -> Cow<'_, str> {
+    if s.contains('\r') {
+        Cow::Owned(s.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
 
 // Per the 1.6 spec, 2.2:
 // All OFX headers are required. NONE should be returned if client or server does not make use of
 // an individual element, e.g., COMPRESSION:NONE, OLDFILEUID:NONE
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Header {
     pub ofxheader: u32,
     pub data: Data,
@@ -15,60 +29,149 @@ pub struct Header {
     pub security: Security,
     pub encoding: Encoding,
     pub charset: String,
-    pub compression: String,
+    pub compression: Compression,
     pub oldfileuid: String,
     pub newfileuid: String,
 }
 
+impl Default for Header {
+    fn default() -> Self {
+        Self {
+            ofxheader: 100,
+            data: Data::default(),
+            version: Version::V160,
+            security: Security::default(),
+            encoding: Encoding::default(),
+            charset: "1252".into(),
+            compression: Compression::default(),
+            oldfileuid: "NONE".into(),
+            newfileuid: "NONE".into(),
+        }
+    }
+}
+
+impl Header {
+    /// Parses only the header of an OFX document, stopping before the SGML body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header is missing required fields or contains invalid values.
+    pub fn sniff(s: &str) -> Result<Self> {
+        s[..s.find("<OFX>").unwrap_or(s.len())].parse()
+    }
+}
+
+/// Builds a [`Header`] for a chosen version, filling in the spec defaults: `DATA:OFXSGML`,
+/// `ENCODING:USASCII` with `CHARSET:1252`, `NONE` for security, compression, and `OLDFILEUID`,
+/// and a newly generated `NEWFILEUID`.
+#[derive(Clone, Debug)]
+pub struct HeaderBuilder {
+    header: Header,
+}
+
+impl HeaderBuilder {
+    #[must_use]
+    pub fn new(version: Version) -> Self {
+        Self {
+            header: Header {
+                version,
+                newfileuid: uuid::Uuid::new_v4().to_string(),
+                ..Header::default()
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn security(mut self, security: Security) -> Self {
+        self.header.security = security;
+        self
+    }
+
+    #[must_use]
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.header.encoding = encoding;
+        self
+    }
+
+    #[must_use]
+    pub fn charset(mut self, charset: impl Into<String>) -> Self {
+        self.header.charset = charset.into();
+        self
+    }
+
+    /// Sets `OLDFILEUID`, the `NEWFILEUID` of the last file received.
+    #[must_use]
+    pub fn old_file_uid(mut self, uid: impl Into<String>) -> Self {
+        self.header.oldfileuid = uid.into();
+        self
+    }
+
+    /// Replaces the generated `NEWFILEUID`.
+    #[must_use]
+    pub fn new_file_uid(mut self, uid: impl Into<String>) -> Self {
+        self.header.newfileuid = uid.into();
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Header {
+        self.header
+    }
+}
+
+/// Writes the header lines as sent on the wire, each ending in CRLF, without the blank line that
+/// separates them from the body.
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OFXHEADER:{}\r\n", self.ofxheader)?;
+        write!(f, "DATA:{}\r\n", self.data.as_str())?;
+        write!(f, "VERSION:{}\r\n", self.version.as_str())?;
+        write!(f, "SECURITY:{}\r\n", self.security.as_str())?;
+        write!(f, "ENCODING:{}\r\n", self.encoding.as_str())?;
+        write!(f, "CHARSET:{}\r\n", self.charset)?;
+        write!(f, "COMPRESSION:{}\r\n", self.compression.as_str())?;
+        write!(f, "OLDFILEUID:{}\r\n", self.oldfileuid)?;
+        write!(f, "NEWFILEUID:{}\r\n", self.newfileuid)
+    }
+}
+
 impl FromStr for Header {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        // Prolog is only valid for XML-based OFX files (2.0 and later), but leaving this in case
-        // we can support it in the future.
-        let prolog_flag = "<?OFX ";
-        let headers_map: HashMap<_, _> = if let Some(start) = s.find(prolog_flag) {
-            let Some(end_delta) = s[start..].find("?>") else {
-                return Err(Error::ParseError("invalid OFX header".into()));
-            };
-            let end = start + end_delta;
-            let prolog_contents = &s[start + prolog_flag.len()..end];
-            prolog_contents
-                .split(r#"" "#)
-                .map(|s| {
-                    let s = s.trim();
-                    let Some((key, value)) = s.split_once('=') else {
-                        return Err(Error::ParseError(format!("invalid OFX header at {s}")));
-                    };
-                    Ok((key.to_string(), value.trim_matches('"').to_string()))
-                })
-                .collect::<Result<HashMap<_, _>>>()?
-        } else {
-            s.lines()
-                .filter(|line| !line.trim().is_empty())
-                .map(|line| {
-                    let line = line.trim();
-                    let Some((key, value)) = line.split_once(':') else {
-                        return Err(Error::ParseError(format!("invalid OFX header at {line}")));
-                    };
-                    Ok((key.to_string(), value.to_string()))
-                })
-                .collect::<Result<HashMap<_, _>>>()?
-        };
+        Self::parse_with_options(s, &ParserOptions::default(), &mut Vec::new())
+    }
+}
+
+impl Header {
+    pub(crate) fn parse_with_options(
+        s: &str,
+        options: &ParserOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<Self> {
+        let mut headers_map = Self::parse_lines(s, warnings)?;
+        ParserOptions::check("header count", headers_map.len(), options.max_headers)?;
+        Self::fold_values(&mut headers_map, options, warnings);
 
         Ok(Self {
             ofxheader: headers_map
                 .get("OFXHEADER")
-                .ok_or_else(|| Error::ParseError("headers missing 'OFXHEADER'".into()))?
+                .ok_or_else(|| Error::parse("headers missing 'OFXHEADER'"))?
                 .parse()?,
             data: headers_map
                 .get("DATA")
-                .map(|s| s.parse())
+                .map(|s| match s.parse::<Data>() {
+                    Err(_) if options.lenient => {
+                        warnings.push(Warning::Fixup(format!("kept nonstandard DATA `{s}`")));
+                        Ok(Data::Other(s.clone()))
+                    }
+                    data => data,
+                })
                 .transpose()?
                 .unwrap_or_default(),
             version: headers_map
                 .get("VERSION")
-                .ok_or_else(|| Error::ParseError("headers missing 'VERSION'".into()))?
+                .ok_or_else(|| Error::parse("headers missing 'VERSION'"))?
                 .parse()?,
             security: headers_map
                 .get("SECURITY")
@@ -83,21 +186,93 @@ impl FromStr for Header {
             charset: headers_map
                 .get("CHARSET")
                 .cloned()
-                .ok_or_else(|| Error::ParseError("headers missing 'CHARSET'".into()))?,
-            compression: headers_map.get("COMPRESSION").cloned().unwrap_or_default(),
+                .ok_or_else(|| Error::parse("headers missing 'CHARSET'"))?,
+            compression: headers_map
+                .get("COMPRESSION")
+                .map(|s| s.parse())
+                .transpose()?
+                .unwrap_or_default(),
             oldfileuid: headers_map
                 .get("OLDFILEUID")
                 .cloned()
-                .ok_or_else(|| Error::ParseError("headers missing 'OLDFILEUID'".into()))?,
+                .ok_or_else(|| Error::parse("headers missing 'OLDFILEUID'"))?,
             newfileuid: headers_map
                 .get("NEWFILEUID")
                 .cloned()
-                .ok_or_else(|| Error::ParseError("headers missing 'NEWFILEUID'".into()))?,
+                .ok_or_else(|| Error::parse("headers missing 'NEWFILEUID'"))?,
         })
     }
+
+    /// Reads the `KEY:VALUE` header lines, or the attributes of an XML prolog.
+    fn parse_lines(s: &str, warnings: &mut Vec<Warning>) -> Result<HashMap<String, String>> {
+        // Prolog is only valid for XML-based OFX files (2.0 and later), but leaving this in case
+        // we can support it in the future.
+        let prolog_flag = "<?OFX ";
+        if let Some(start) = s.find(prolog_flag) {
+            let Some(end_delta) = s[start..].find("?>") else {
+                return Err(Error::parse("invalid OFX header"));
+            };
+            let end = start + end_delta;
+            let prolog_contents = &s[start + prolog_flag.len()..end];
+            prolog_contents
+                .split(r#"" "#)
+                .map(|s| {
+                    let s = s.trim();
+                    let Some((key, value)) = s.split_once('=') else {
+                        return Err(Error::parse(format!("invalid OFX header at {s}")));
+                    };
+                    Ok((key.to_string(), value.trim_matches('"').to_string()))
+                })
+                .collect()
+        } else {
+            normalize_line_endings(s)
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    let trimmed = line.trim();
+                    if trimmed.len() != line.len() {
+                        warnings.push(Warning::WhitespaceTrimmed(format!("header `{trimmed}`")));
+                    }
+                    let line = trimmed;
+                    let Some((key, value)) = line.split_once(':') else {
+                        return Err(Error::parse(format!("invalid OFX header at {line}")));
+                    };
+                    Ok((key.to_string(), value.to_string()))
+                })
+                .collect()
+        }
+    }
+
+    /// Folds enumerated values in lenient mode, and warns about headers that will be defaulted.
+    fn fold_values(
+        headers_map: &mut HashMap<String, String>,
+        options: &ParserOptions,
+        warnings: &mut Vec<Warning>,
+    ) {
+        // Real exports have values like ` ofxsgml ` or `None` for these enumerated headers
+        if options.lenient {
+            for name in ["DATA", "ENCODING", "SECURITY"] {
+                if let Some(value) = headers_map.get_mut(name) {
+                    let folded = value.trim().to_ascii_uppercase();
+                    if folded != *value {
+                        warnings.push(Warning::Fixup(format!(
+                            "read {name} `{value}` as `{folded}`"
+                        )));
+                        *value = folded;
+                    }
+                }
+            }
+        }
+
+        for name in ["DATA", "SECURITY", "ENCODING", "COMPRESSION"] {
+            if !headers_map.contains_key(name) {
+                warnings.push(Warning::DefaultedHeader(name.into()));
+            }
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[non_exhaustive]
 pub enum Version {
     V102,
@@ -106,6 +281,22 @@ pub enum Version {
     V160,
 }
 
+impl Version {
+    /// The `VERSION` header values Ofxy can parse.
+    pub const SUPPORTED: &'static [&'static str] = &["102", "103", "151", "160"];
+
+    /// The `VERSION` header value, e.g. `160`.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::V102 => "102",
+            Self::V103 => "103",
+            Self::V151 => "151",
+            Self::V160 => "160",
+        }
+    }
+}
+
 impl FromStr for Version {
     type Err = Error;
 
@@ -116,42 +307,71 @@ impl FromStr for Version {
             "151" => Ok(Version::V151),
             "160" => Ok(Version::V160),
             // Will need to fix this if OFX eventually gets to V10
-            _ if s.starts_with('1') => Err(Error::ParseError(format!(
+            _ if s.starts_with('1') => Err(Error::parse(format!(
                 "Ofxy was not aware of OFX version {s}. \
                  Please submit an issue so we can take a closer look!"
             ))),
-            _ => Err(Error::ParseError(format!(
-                "Ofxy does not yet support OFX version: {s}"
-            ))),
+            _ => Err(ErrorKind::UnsupportedVersion {
+                found: s.to_owned(),
+                supported: Self::SUPPORTED,
+            }
+            .into()),
         }
     }
 }
 
-#[derive(Debug, Default, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[non_exhaustive]
 pub enum Encoding {
     Unicode,
     #[default]
     UsAscii,
+    /// `UTF-8`, as OFX 2.x requires
+    Utf8,
+}
+
+impl Encoding {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unicode => "UNICODE",
+            Self::UsAscii => "USASCII",
+            Self::Utf8 => "UTF-8",
+        }
+    }
 }
 
 impl FromStr for Encoding {
     type Err = Error;
 
+    /// Parses an `ENCODING` value, ignoring case, and accepting `UTF8` as seen in some files.
     fn from_str(s: &str) -> Result<Self> {
-        match s {
+        match s.to_ascii_uppercase().as_str() {
             "UNICODE" => Ok(Encoding::Unicode),
             "USASCII" => Ok(Encoding::UsAscii),
-            _ => Err(Error::ParseError("invalid encoding type".into())),
+            "UTF-8" | "UTF8" => Ok(Encoding::Utf8),
+            _ => Err(Error::parse(format!("invalid encoding type: {s}"))),
         }
     }
 }
 
-#[derive(Debug, Default, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[non_exhaustive]
 pub enum Data {
     #[default]
     Ofxsgml,
+    /// A nonstandard value, only accepted in lenient mode
+    Other(String),
+}
+
+impl Data {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Ofxsgml => "OFXSGML",
+            Self::Other(value) => value,
+        }
+    }
 }
 
 impl FromStr for Data {
@@ -160,18 +380,28 @@ impl FromStr for Data {
     fn from_str(s: &str) -> Result<Self> {
         match s {
             "OFXSGML" => Ok(Data::Ofxsgml),
-            _ => Err(Error::ParseError("invalid data type".into())),
+            _ => Err(Error::parse("invalid data type")),
         }
     }
 }
 
-#[derive(Debug, Default, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub enum Security {
     #[default]
     None,
     Type1,
 }
 
+impl Security {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "NONE",
+            Self::Type1 => "TYPE1",
+        }
+    }
+}
+
 impl FromStr for Security {
     type Err = Error;
 
@@ -179,7 +409,39 @@ impl FromStr for Security {
         match s {
             "NONE" => Ok(Security::None),
             "TYPE1" => Ok(Security::Type1),
-            _ => Err(Error::ParseError("invalid security type".into())),
+            _ => Err(Error::parse("invalid security type")),
+        }
+    }
+}
+
+// Per the 1.6 spec, 2.2.6:
+// A future version of the specification will define compression.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub enum Compression {
+    #[default]
+    None,
+}
+
+impl Compression {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "NONE",
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "NONE" => Ok(Compression::None),
+            _ => Err(Error::parse(format!(
+                "unsupported compression: {s} (the OFX spec does not yet define any compression \
+                 schemes)"
+            ))),
         }
     }
 }
@@ -210,6 +472,107 @@ NEWFILEUID:NONE
         assert_eq!(header.data, Data::Ofxsgml);
         assert_eq!(header.encoding, Encoding::UsAscii);
         assert_eq!(header.charset, "1252");
-        assert_eq!(header.compression, "NONE");
+        assert_eq!(header.compression, Compression::None);
+        assert_eq!(header.to_string().replace("\r\n", "\n") + "\n", input);
+    }
+
+    #[test]
+    fn sniffs_header() {
+        let input = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:160
+SECURITY:NONE
+ENCODING:USASCII
+CHARSET:1252
+COMPRESSION:NONE
+OLDFILEUID:NONE
+NEWFILEUID:NONE
+
+<OFX><this is not valid SGML";
+        let header = Header::sniff(input).unwrap();
+        assert_eq!(header.version, Version::V160);
+    }
+
+    #[test]
+    fn parses_encoding_variants() {
+        for (input, expected) in [
+            ("USASCII", Encoding::UsAscii),
+            ("unicode", Encoding::Unicode),
+            ("UTF-8", Encoding::Utf8),
+            ("utf8", Encoding::Utf8),
+        ] {
+            assert_eq!(input.parse::<Encoding>().unwrap(), expected);
+        }
+        assert!("EBCDIC".parse::<Encoding>().is_err());
+    }
+
+    #[test]
+    fn keeps_unknown_data_when_lenient() {
+        let input = "OFXHEADER:100\nDATA:OFXSGMLX\nVERSION:102\nCHARSET:1252\n\
+            OLDFILEUID:NONE\nNEWFILEUID:NONE\n";
+        assert!(input.parse::<Header>().is_err());
+
+        let options = ParserOptions {
+            lenient: true,
+            ..ParserOptions::default()
+        };
+        let mut warnings = Vec::new();
+        let header = Header::parse_with_options(input, &options, &mut warnings).unwrap();
+        assert_eq!(header.data, Data::Other("OFXSGMLX".into()));
+        assert!(warnings.contains(&Warning::Fixup("kept nonstandard DATA `OFXSGMLX`".into())));
+    }
+
+    #[test]
+    fn builds_header_with_defaults() {
+        let header = HeaderBuilder::new(Version::V102).build();
+        assert_eq!(header.version, Version::V102);
+        assert_eq!(header.security, Security::None);
+        assert_eq!(header.oldfileuid, "NONE");
+        assert_ne!(header.newfileuid, "NONE");
+        assert_ne!(
+            header.newfileuid,
+            HeaderBuilder::new(Version::V102).build().newfileuid
+        );
+
+        let reparsed: Header = header.to_string().parse().unwrap();
+        assert_eq!(reparsed, header);
+    }
+
+    #[test]
+    fn parses_mixed_line_endings() {
+        let input = "OFXHEADER:100\rDATA:OFXSGML\r\nVERSION:102\nSECURITY:NONE\r\
+            ENCODING:USASCII\rCHARSET:1252\r\nCOMPRESSION:NONE\rOLDFILEUID:NONE\r\
+            NEWFILEUID:NONE\r\r";
+        let header: Header = input.parse().unwrap();
+        assert_eq!(header.version, Version::V102);
+        assert_eq!(header.newfileuid, "NONE");
+    }
+
+    #[test]
+    fn folds_header_values_when_lenient() {
+        let input = "OFXHEADER:100\nDATA: ofxsgml \nVERSION:102\nSECURITY:None\n\
+            ENCODING:usascii\nCHARSET:1252\nOLDFILEUID:NONE\nNEWFILEUID:NONE\n";
+        assert!(input.parse::<Header>().is_err());
+
+        let options = ParserOptions {
+            lenient: true,
+            ..ParserOptions::default()
+        };
+        let mut warnings = Vec::new();
+        let header = Header::parse_with_options(input, &options, &mut warnings).unwrap();
+        assert_eq!(header.data, Data::Ofxsgml);
+        assert_eq!(header.security, Security::None);
+        assert_eq!(header.encoding, Encoding::UsAscii);
+        assert!(warnings.contains(&Warning::Fixup("read SECURITY `None` as `NONE`".into())));
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let err = "220".parse::<Version>().unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::UnsupportedVersion { found, supported }
+                if found == "220" && supported.contains(&"160")
+        ));
     }
 }
@@ -0,0 +1,179 @@
+//! Presets for the quirks of known financial institutions.
+//!
+//! Some banks consistently get the same things wrong, such as declaring `CHARSET:1252` for files
+//! written in UTF-8. [`INSTITUTIONS`] records what is known about each, and
+//! [`Institution::apply`] adjusts [`ParserOptions`] to match, so their files parse correctly
+//! without per-bank configuration:
+//!
+//! ```no_run
+//! # fn main() -> ofxy::Result<()> {
+//! let bytes = std::fs::read("statement.ofx")?;
+//! let mut options = ofxy::ParserOptions::default();
+//! if let Some(institution) = ofxy::institution::identify(&bytes) {
+//!     institution.apply(&mut options);
+//! }
+//! let (ofx, _) = options.parse_bytes(&bytes)?;
+//! # drop(ofx);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Institutions listed without a charset or quirks have been seen to parse with the defaults.
+
+use crate::{options::ParserOptions, testing::Quirk};
+
+/// How many leading bytes [`identify`] searches for the `<FI>` aggregate.
+const SNIFF_LEN: usize = 8192;
+
+/// What is known about the files a financial institution produces.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Institution {
+    pub name: &'static str,
+    /// The `<ORG>` values the institution signs on with.
+    pub organizations: &'static [&'static str],
+    /// The `<FID>` values the institution signs on with, without leading zeros.
+    pub ids: &'static [&'static str],
+    /// The charset files are actually written in, when it differs from the declared one.
+    pub charset: Option<&'static str>,
+    /// The mistakes its files are known to make.
+    pub quirks: &'static [Quirk],
+}
+
+/// Known institutions, alphabetically.
+pub const INSTITUTIONS: &[Institution] = &[
+    Institution {
+        name: "American Express",
+        organizations: &["AMEX"],
+        ids: &["3101"],
+        charset: None,
+        quirks: &[],
+    },
+    Institution {
+        name: "Banco do Brasil",
+        organizations: &["Banco do Brasil", "Banco do Brasil S/A"],
+        ids: &["1"],
+        charset: Some("UTF-8"),
+        quirks: &[Quirk::MissingBlankLine],
+    },
+    Institution {
+        name: "Citigroup",
+        organizations: &["Citigroup"],
+        ids: &["24909"],
+        charset: None,
+        quirks: &[],
+    },
+    Institution {
+        name: "Fidelity",
+        organizations: &["fidelity.com"],
+        ids: &["7776"],
+        charset: None,
+        quirks: &[],
+    },
+    Institution {
+        name: "TD Ameritrade",
+        organizations: &["ameritrade.com", "Ameritrade Technology Group"],
+        ids: &["5024", "AIS"],
+        charset: None,
+        quirks: &[],
+    },
+    Institution {
+        name: "TIAA-CREF",
+        organizations: &["TIAA-CREF"],
+        ids: &["1304"],
+        charset: None,
+        quirks: &[],
+    },
+    Institution {
+        name: "USAA",
+        organizations: &["USAA"],
+        ids: &["24591"],
+        charset: None,
+        quirks: &[],
+    },
+    Institution {
+        name: "Vanguard",
+        organizations: &["Vanguard", "The Vanguard Group"],
+        ids: &["15103", "84022"],
+        charset: None,
+        quirks: &[],
+    },
+];
+
+impl Institution {
+    /// Adjusts `options` for this institution's files: decoding with its actual charset, and
+    /// enabling [`ParserOptions::lenient`] if it writes values that need it.
+    pub fn apply(&self, options: &mut ParserOptions) {
+        if let Some(charset) = self.charset {
+            options.charset = Some(charset.into());
+        }
+        if self.quirks.contains(&Quirk::DecimalComma) {
+            options.lenient = true;
+        }
+    }
+}
+
+/// Finds an institution by its `<ORG>` and `<FID>`.
+///
+/// The organization is compared case-insensitively and takes precedence, since FIDs are not
+/// unique across institutions. The FID alone is used only when no organization is given.
+#[must_use]
+pub fn find(organization: Option<&str>, id: Option<&str>) -> Option<&'static Institution> {
+    if let Some(organization) = organization.map(str::trim) {
+        return INSTITUTIONS.iter().find(|institution| {
+            institution
+                .organizations
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(organization))
+        });
+    }
+    let id = id?.trim();
+    let id = id.trim_start_matches('0');
+    INSTITUTIONS
+        .iter()
+        .find(|institution| institution.ids.contains(&id))
+}
+
+/// The text of the first `<tag>` in `s`, up to the next tag or line break.
+fn value<'a>(s: &'a str, tag: &str) -> Option<&'a str> {
+    let start = s.find(tag)? + tag.len();
+    let rest = &s[start..];
+    let end = rest.find(['<', '\r', '\n']).unwrap_or(rest.len());
+    Some(rest[..end].trim()).filter(|value| !value.is_empty())
+}
+
+/// Identifies the institution that produced a document from the `<ORG>` and `<FID>` of its
+/// signon, before parsing it, so its preset can be applied.
+#[must_use]
+pub fn identify(bytes: &[u8]) -> Option<&'static Institution> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(SNIFF_LEN)]);
+    find(value(&head, "<ORG>"), value(&head, "<FID>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifies_institutions() {
+        let bytes = std::fs::read("tests/files/ofxparser/ofxdata-bb.ofx").unwrap();
+        let institution = identify(&bytes).unwrap();
+        assert_eq!(institution.name, "Banco do Brasil");
+
+        let mut options = ParserOptions::default();
+        institution.apply(&mut options);
+        assert_eq!(options.charset.as_deref(), Some("UTF-8"));
+        let (ofx, _) = options.parse_bytes(&bytes).unwrap();
+        #[cfg(feature = "encoding")]
+        assert!(format!("{ofx:?}").contains("DOC CRÉDITO EM CONTA"));
+        assert!(ofx.body.bank.is_some());
+
+        assert_eq!(find(Some("usaa"), Some("1")).unwrap().name, "USAA");
+        assert_eq!(find(None, Some("001")).unwrap().name, "Banco do Brasil");
+        assert_eq!(find(Some("Some Credit Union"), Some("1")), None);
+        assert_eq!(
+            identify(&std::fs::read("tests/files/simple.ofx").unwrap()),
+            None
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Remembering which transactions have already been imported.
+//!
+//! Bank downloads usually overlap the previous one. An [`ImportLedger`] records the FITIDs seen
+//! for each account so repeated imports only yield transactions that are actually new.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::ErrorKind,
+    path::PathBuf,
+};
+
+use crate::{
+    Result,
+    body::{AccountId, BankTransactionList, Transaction},
+};
+
+/// FITIDs already imported, keyed by account ID.
+pub type Seen = BTreeMap<String, BTreeSet<String>>;
+
+/// Where an [`ImportLedger`] persists its state.
+pub trait LedgerStore {
+    /// Loads the previously saved state, or an empty one if nothing has been saved yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if existing state cannot be read.
+    fn load(&mut self) -> Result<Seen>;
+
+    /// Persists `seen`, replacing any previous state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state cannot be written.
+    fn save(&mut self, seen: &Seen) -> Result<()>;
+}
+
+/// Stores the ledger as a JSON file.
+#[derive(Clone, Debug)]
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl LedgerStore for JsonFileStore {
+    fn load(&mut self) -> Result<Seen> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Seen::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&mut self, seen: &Seen) -> Result<()> {
+        fs::write(&self.path, serde_json::to_vec_pretty(seen)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportLedger<S: LedgerStore = JsonFileStore> {
+    store: S,
+    seen: Seen,
+}
+
+impl<S: LedgerStore> ImportLedger<S> {
+    /// Opens a ledger, loading any state previously saved to `store`.
+    ///
+    /// # Errors
+    ///
+    /// See [`LedgerStore::load`].
+    pub fn open(mut store: S) -> Result<Self> {
+        let seen = store.load()?;
+        Ok(Self { store, seen })
+    }
+
+    #[must_use]
+    pub fn is_seen(&self, account: &AccountId, fitid: &str) -> bool {
+        self.seen
+            .get(account.expose())
+            .is_some_and(|fitids| fitids.contains(fitid))
+    }
+
+    /// Returns the transactions in `list` not seen before for `account`, recording them as seen.
+    ///
+    /// Changes are kept in memory until [`ImportLedger::save`] is called, so an import that fails
+    /// partway can simply be retried.
+    pub fn filter_new(
+        &mut self,
+        account: &AccountId,
+        list: &BankTransactionList,
+    ) -> Vec<Transaction> {
+        let fitids = self.seen.entry(account.expose().to_owned()).or_default();
+        list.transactions
+            .iter()
+            .filter(|transaction| fitids.insert(transaction.id.clone()))
+            .cloned()
+            .collect()
+    }
+
+    /// Persists everything recorded so far.
+    ///
+    /// # Errors
+    ///
+    /// See [`LedgerStore::save`].
+    pub fn save(&mut self) -> Result<()> {
+        self.store.save(&self.seen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-memory store standing in for a database.
+    #[derive(Default)]
+    struct MemoryStore(Seen);
+
+    impl LedgerStore for MemoryStore {
+        fn load(&mut self) -> Result<Seen> {
+            Ok(self.0.clone())
+        }
+
+        fn save(&mut self, seen: &Seen) -> Result<()> {
+            self.0.clone_from(seen);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn yields_only_new_transactions() {
+        let first: BankTransactionList = "<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1</STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250902<TRNAMT>-6<FITID>2</STMTTRN>\
+            </BANKTRANLIST>"
+            .parse()
+            .unwrap();
+        let second: BankTransactionList = "<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250902<TRNAMT>-6<FITID>2</STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250903<TRNAMT>-7<FITID>3</STMTTRN>\
+            </BANKTRANLIST>"
+            .parse()
+            .unwrap();
+        let account = AccountId::new("1234");
+
+        let mut ledger = ImportLedger::open(MemoryStore::default()).unwrap();
+        assert_eq!(ledger.filter_new(&account, &first).len(), 2);
+        ledger.save().unwrap();
+
+        let mut ledger = ImportLedger::open(ledger.store).unwrap();
+        let new = ledger.filter_new(&account, &second);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].id, "3");
+        assert!(ledger.is_seen(&account, "1"));
+        assert!(!ledger.is_seen(&AccountId::new("5678"), "1"));
+    }
+}
@@ -1,28 +1,192 @@
 #![deny(unsafe_code)]
+// Parsing untrusted input must return errors rather than panic
+#![cfg_attr(
+    not(test),
+    deny(clippy::expect_used, clippy::panic, clippy::unwrap_used)
+)]
 #![doc = include_str!("../README.md")]
 
 use std::str::FromStr;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "zip")]
+pub mod archive;
+#[cfg(feature = "rayon")]
+pub mod batch;
 pub mod body;
+pub mod builder;
+pub mod client;
+#[cfg(feature = "rust_decimal")]
+pub mod continuity;
+pub mod credentials;
+pub mod csv;
+pub mod datetime;
+pub mod decimal;
+pub mod decode;
+pub mod detect;
 pub mod error;
+pub mod events;
+pub mod format;
 pub mod header;
-use error::Error;
+pub mod institution;
+#[cfg(feature = "ledger")]
+pub mod ledger;
+pub mod lint;
+pub mod load;
+#[cfg(feature = "test-util")]
+pub mod mock;
+#[cfg(feature = "rusty_money")]
+pub mod money;
+pub mod mt940;
+pub mod node;
+pub mod normalized;
+#[cfg(feature = "ofxhome")]
+pub mod ofxhome;
+pub mod options;
+pub mod profile;
+pub mod redact;
+pub mod request;
+pub mod server_error;
+#[cfg(feature = "rust_decimal")]
+pub mod stats;
+pub mod stream;
+pub mod summary;
+pub mod testing;
+mod tree;
+pub mod validate;
+#[cfg(feature = "rust_decimal")]
+pub mod valuation;
+pub mod warning;
+// `wasm-bindgen` generates unsafe glue code
+#[cfg(feature = "wasm")]
+#[allow(unsafe_code)]
+pub mod wasm;
+#[cfg(feature = "rayon")]
+pub use batch::parse_many;
+pub use builder::OfxBuilder;
+pub use datetime::DateTime;
+pub use decimal::Decimal;
+pub use detect::{FormatInfo, detect};
+use error::{Error, ErrorKind};
+pub use load::load_dir;
+pub use node::OfxNode;
+pub use options::ParserOptions;
+pub use warning::Warning;
 
 pub type Result<T> = std::result::Result<T, crate::error::Error>;
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Ofx {
     pub header: header::Header,
     #[serde(rename = "OFX")]
     pub body: body::Body,
+    /// The parsed `<OFX>` element tree, for [`Ofx::tree`]
+    #[serde(skip)]
+    tree: Option<OfxNode>,
 }
 
-impl FromStr for Ofx {
-    type Err = Error;
+/// Compares the header and body, ignoring whether the element tree was retained.
+impl PartialEq for Ofx {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header && self.body == other.body
+    }
+}
+
+impl Ofx {
+    #[must_use]
+    pub fn new(header: header::Header, body: body::Body) -> Self {
+        Self {
+            header,
+            body,
+            tree: None,
+        }
+    }
+
+    /// The text of the first element at `path`, a `/`-separated list of tags below `<OFX>`,
+    /// such as `BANKMSGSRSV1/STMTTRNRS/STMTRS/LEDGERBAL/BALAMT`.
+    ///
+    /// This reads the document as parsed, so it reaches tags the typed structs do not model. A
+    /// step written `NAME[i]` picks the `i`th (from 0) child with that name. Returns `None` if
+    /// nothing matches, the match is an aggregate, or the document was not parsed from OFX
+    /// text, e.g. built in code.
+    ///
+    /// This is how to read raw values: text and tags are as the institution sent them, before
+    /// normalization, so an amount read as `-16.40` under a lenient parse may come back as
+    /// `-16,40`, and a `<BANKMSGSRSV2>` wrapper is found under that name rather than
+    /// `BANKMSGSRSV1`. Only whole documents keep them; fragments parsed with [`FromStr`] and
+    /// those from [`stream`] do not.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.get_all(path).into_iter().next()
+    }
+
+    /// The text of every element at `path`, as described in [`Ofx::get`], e.g. each FITID with
+    /// `BANKMSGSRSV1/STMTTRNRS/STMTRS/BANKTRANLIST/STMTTRN/FITID`.
+    #[must_use]
+    pub fn get_all(&self, path: &str) -> Vec<&str> {
+        let Some(tree) = &self.tree else {
+            return Vec::new();
+        };
+        let path = path.strip_prefix("OFX/").unwrap_or(path);
+        tree.select(path)
+            .into_iter()
+            .filter_map(OfxNode::text)
+            .collect()
+    }
+
+    /// The `<OFX>` element as parsed, including tags the typed structs do not model, or `None`
+    /// if the document was not parsed from OFX text.
+    #[must_use]
+    pub fn tree(&self) -> Option<&OfxNode> {
+        self.tree.as_ref()
+    }
+
+    /// Parses only the header, for quickly classifying files by version, encoding, or charset.
+    ///
+    /// # Errors
+    ///
+    /// See [`header::Header::sniff`].
+    pub fn parse_header_only(s: &str) -> Result<header::Header> {
+        header::Header::sniff(s)
+    }
+
+    /// Parses a document, also returning any non-fatal issues found along the way.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be parsed.
+    pub fn parse_with_warnings(s: &str) -> Result<(Self, Vec<Warning>)> {
+        Self::parse_with_options(s, &ParserOptions::default())
+    }
+
+    /// Parses a document from bytes, decoding it as described in [`decode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be parsed.
+    pub fn parse_bytes(bytes: &[u8]) -> Result<(Self, Vec<Warning>)> {
+        ParserOptions::default().parse_bytes(bytes)
+    }
+
+    /// Reads and parses a file, decoding it as described in [`decode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::Io`] error if the file cannot be read, or an error if it cannot be
+    /// parsed.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        ParserOptions::default()
+            .parse_file(path)
+            .map(|(ofx, _)| ofx)
+    }
+
+    pub(crate) fn parse_with_options(
+        s: &str,
+        options: &ParserOptions,
+    ) -> Result<(Self, Vec<Warning>)> {
+        ParserOptions::check("file size", s.len(), options.max_file_size)?;
 
-    fn from_str(s: &str) -> Result<Self> {
         // Per 1.6 spec, 1.2.1:
         // A blank line also separates the Open Financial Exchange headers and the request.
         // (See Chapter 2, “Structure” for more information about the Open Financial Exchange
@@ -32,14 +196,74 @@ impl FromStr for Ofx {
         // So in theory we could `split_once` and be done, but it seems that some banks provide
         // OFX files without this blank line, so we will be more flexible by just starting at the
         // `<OFX>` tag.
-        let start = s
-            .find("<OFX>")
-            .ok_or(Error::ParseError("no `<OFX>` found".into()))?;
+        let Some(start) = s.find("<OFX>") else {
+            let type1 = header::normalize_line_endings(s)
+                .lines()
+                .take(options.max_headers)
+                .filter_map(|line| line.split_once(':'))
+                .any(|(key, value)| {
+                    key.trim().eq_ignore_ascii_case("SECURITY")
+                        && value.trim().eq_ignore_ascii_case("TYPE1")
+                });
+            return Err(if type1 {
+                ErrorKind::EncryptedBody.into()
+            } else {
+                Error::parse("no `<OFX>` found")
+            });
+        };
 
         let (raw_header, raw_body) = (&s[..start], &s[start..]);
 
-        let header = raw_header.parse()?;
-        let body = raw_body.parse()?;
-        Ok(Self { header, body })
+        let mut warnings = Vec::new();
+        if !header::normalize_line_endings(raw_header)
+            .lines()
+            .last()
+            .is_some_and(|line| line.trim().is_empty())
+        {
+            warnings.push(Warning::Fixup(
+                "no blank line between headers and body".into(),
+            ));
+        }
+
+        let (result, fixups) = options.scoped(|| -> Result<Self> {
+            let header = header::Header::parse_with_options(raw_header, options, &mut warnings)?;
+            // Type 1 bodies are usually plaintext, so only blame the security for unreadable SGML
+            let (body, tree) =
+                body::Body::parse_tree_with_options(raw_body, options, &mut warnings).map_err(
+                    |err| match (&header.security, err.kind()) {
+                        (header::Security::Type1, ErrorKind::Sgml) => {
+                            Error::from(ErrorKind::EncryptedBody).with_source(err)
+                        }
+                        _ => err,
+                    },
+                )?;
+            Ok(Self {
+                header,
+                body,
+                tree: Some(tree.into()),
+            })
+        });
+        let ofx = result?;
+        warnings.extend(fixups);
+        Ok((ofx, warnings))
+    }
+}
+
+impl FromStr for Ofx {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_with_warnings(s).map(|(ofx, _)| ofx)
     }
 }
+
+/// Parses `body` (from `<OFX>` on) under the standard OFX 1.0.2 headers, for unit tests.
+#[cfg(test)]
+pub(crate) fn document(body: &str) -> Ofx {
+    format!(
+        "OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\nSECURITY:NONE\r\nENCODING:USASCII\r\n\
+         CHARSET:1252\r\nCOMPRESSION:NONE\r\nOLDFILEUID:NONE\r\nNEWFILEUID:NONE\r\n\r\n{body}"
+    )
+    .parse()
+    .unwrap()
+}
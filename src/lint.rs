@@ -0,0 +1,127 @@
+//! The validation checks as lint rules with stable codes, for tooling that suppresses or gates
+//! on specific rules.
+//!
+//! | Code      | Rule                                | Severity |
+//! |-----------|-------------------------------------|----------|
+//! | `OFXY001` | [`Rule::DuplicateFitId`]            | error    |
+//! | `OFXY002` | [`Rule::AmountSign`]                | warning  |
+//! | `OFXY003` | [`Rule::DateRange`]                 | warning  |
+//! | `OFXY004` | [`Rule::FieldLength`]               | error    |
+//! | `OFXY005` | [`Rule::CurrencyCode`]              | error    |
+//! | `OFXY006` | [`Rule::VersionMismatch`]           | info     |
+
+use std::fmt;
+
+use crate::{
+    Ofx,
+    header::Version,
+    validate::{Rule, Severity, Violation},
+};
+
+/// A [`Violation`] reported by a [`Linter`], with its rule's code and severity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Finding {
+    pub code: &'static str,
+    pub severity: Severity,
+    pub violation: Violation,
+}
+
+impl From<Violation> for Finding {
+    fn from(violation: Violation) -> Self {
+        Self {
+            code: violation.rule.code(),
+            severity: violation.rule.severity(),
+            violation,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        };
+        write!(f, "{} {severity}: {}", self.code, self.violation)
+    }
+}
+
+/// Runs the checks of [`Ofx::validate`], skipping any rules that were allowed.
+#[derive(Clone, Debug, Default)]
+pub struct Linter {
+    allowed: Vec<Rule>,
+    profile: Option<Version>,
+}
+
+impl Linter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Suppresses findings for `rule`. Use [`Rule::from_code`] to allow rules by code.
+    #[must_use]
+    pub fn allow(mut self, rule: Rule) -> Self {
+        self.allowed.push(rule);
+        self
+    }
+
+    /// Also checks the document's `VERSION` against `profile`, as
+    /// [`Ofx::conformance_report`] does.
+    #[must_use]
+    pub fn profile(mut self, profile: Version) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Lints `ofx`, returning findings ordered by severity, most serious first.
+    #[must_use]
+    pub fn run(&self, ofx: &Ofx) -> Vec<Finding> {
+        let violations = match self.profile {
+            Some(profile) => ofx.conformance_report(profile).findings,
+            None => ofx.validate(),
+        };
+        let mut findings: Vec<Finding> = violations
+            .into_iter()
+            .filter(|violation| !self.allowed.contains(&violation.rule))
+            .map(Finding::from)
+            .collect();
+        findings.sort_by_key(|finding| finding.severity);
+        findings
+    }
+}
+
+/// Lints `ofx` with every rule enabled.
+#[must_use]
+pub fn lint(ofx: &Ofx) -> Vec<Finding> {
+    Linter::new().run(ofx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_and_suppresses_by_code() {
+        let input = std::fs::read_to_string("tests/files/simple.ofx")
+            .unwrap()
+            .replace("<TRNTYPE>PAYMENT", "<TRNTYPE>DEBIT")
+            .replace("<FITID>abcd-1234", "<FITID>abc-123");
+        let ofx: Ofx = input.parse().unwrap();
+
+        // The fixture's 57-character NAME is over the spec's 32
+        let codes: Vec<_> = lint(&ofx).iter().map(|finding| finding.code).collect();
+        assert_eq!(codes, ["OFXY001", "OFXY004", "OFXY002"]);
+        assert!(lint(&ofx)[0].to_string().starts_with("OFXY001 error: "));
+
+        let linter = Linter::new().allow(Rule::from_code("ofxy002").unwrap());
+        let codes: Vec<_> = linter
+            .run(&ofx)
+            .iter()
+            .map(|finding| finding.code)
+            .collect();
+        assert_eq!(codes, ["OFXY001", "OFXY004"]);
+        assert_eq!(Rule::from_code("OFXY999"), None);
+    }
+}
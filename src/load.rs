@@ -0,0 +1,79 @@
+//! Loading every OFX file in a directory, for bulk importers.
+
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{Ofx, ParserOptions, Result, warning::Warning};
+
+/// The outcome of parsing one file.
+#[derive(Debug)]
+pub struct FileResult {
+    pub path: PathBuf,
+    pub result: Result<(Ofx, Vec<Warning>)>,
+}
+
+/// Whether `path` has an `.ofx` or `.qfx` extension, ignoring case.
+pub(crate) fn is_ofx(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ofx") || ext.eq_ignore_ascii_case("qfx"))
+}
+
+/// Parses every `.ofx` and `.qfx` file directly inside `dir`, ignoring case, sorted by path.
+///
+/// A file that fails to read or parse does not stop the others.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read.
+pub fn load_dir(dir: impl AsRef<Path>) -> Result<Vec<FileResult>> {
+    load_dir_with_options(dir, &ParserOptions::default())
+}
+
+/// Like [`load_dir`], parsing with `options`.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read.
+pub fn load_dir_with_options(
+    dir: impl AsRef<Path>,
+    options: &ParserOptions,
+) -> Result<Vec<FileResult>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && is_ofx(&path) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let result = options.parse_file(&path);
+            FileResult { path, result }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_ofx_files() {
+        let results = load_dir("tests/files/ofxparse").unwrap();
+        assert!(results.iter().all(|file| is_ofx(&file.path)));
+        assert!(results.windows(2).all(|pair| pair[0].path < pair[1].path));
+        let fidelity = results
+            .iter()
+            .find(|file| file.path.ends_with("fidelity.ofx"))
+            .unwrap();
+        assert!(fidelity.result.is_ok());
+        assert!(results.iter().any(|file| file.result.is_err()));
+        assert!(load_dir("tests/files/does-not-exist").is_err());
+    }
+}
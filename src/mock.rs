@@ -0,0 +1,304 @@
+//! An in-process OFX server for integration tests.
+//!
+//! [`MockServer`] listens on a local port and answers each POST with the next queued [`Reply`],
+//! recording the request bodies it received. It speaks just enough HTTP/1.1 for one request per
+//! connection, which is all OFX clients need.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    header::Header,
+    testing::{self, Config},
+    tree::Element,
+};
+
+/// The server date in canned responses, fixed so they are reproducible.
+const SERVER_DATE: &str = "20250101120000.000[0:GMT]";
+
+/// A canned response.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Reply {
+    /// An OFX document, sent as is.
+    Ofx(String),
+    /// A successful signon and a bank statement from [`testing::generate`].
+    Statement(Config),
+    /// A signon rejected with a status code, e.g. 15500 for a wrong password.
+    SignOnError(u32),
+    /// A signon that requires multi-factor authentication (status 3000), followed by the
+    /// `<MFACHALLENGE>`s asking each `(MFAPHRASEID, MFAPHRASELABEL)` question.
+    MfaChallenge(Vec<(String, String)>),
+    /// An HTTP error status with an empty body, e.g. 503 for a server that is down.
+    Http(u16),
+}
+
+fn status(code: u32) -> Element {
+    let severity = if code == 0 { "INFO" } else { "ERROR" };
+    Element::aggregate(
+        "STATUS",
+        vec![
+            Element::leaf("CODE", code.to_string()),
+            Element::leaf("SEVERITY", severity),
+        ],
+    )
+}
+
+/// A document holding only the signon message set, with `extra` aggregates after `<SONRS>`.
+fn sign_on(code: u32, extra: Vec<Element>) -> String {
+    let response = Element::aggregate(
+        "SONRS",
+        vec![
+            status(code),
+            Element::leaf("DTSERVER", SERVER_DATE),
+            Element::leaf("LANGUAGE", "ENG"),
+        ],
+    );
+    let mut message_set = vec![response];
+    message_set.extend(extra);
+    let body = Element::aggregate(
+        "OFX",
+        vec![Element::aggregate("SIGNONMSGSRSV1", message_set)],
+    );
+    format!("{}\r\n{}", Header::default(), body.to_sgml())
+}
+
+impl Reply {
+    /// The HTTP status and body to send.
+    fn render(&self) -> (u16, String) {
+        match self {
+            Self::Ofx(ofx) => (200, ofx.clone()),
+            Self::Statement(config) => match testing::generate(config) {
+                Ok(ofx) => (200, ofx),
+                Err(err) => (500, err.to_string()),
+            },
+            Self::SignOnError(code) => (200, sign_on(*code, Vec::new())),
+            Self::MfaChallenge(questions) => {
+                let challenges = questions
+                    .iter()
+                    .map(|(id, label)| {
+                        Element::aggregate(
+                            "MFACHALLENGE",
+                            vec![
+                                Element::leaf("MFAPHRASEID", id),
+                                Element::leaf("MFAPHRASELABEL", label),
+                            ],
+                        )
+                    })
+                    .collect();
+                let transaction = Element::aggregate(
+                    "MFACHALLENGETRNRS",
+                    vec![
+                        Element::leaf("TRNUID", "0"),
+                        status(0),
+                        Element::aggregate("MFACHALLENGERS", challenges),
+                    ],
+                );
+                (200, sign_on(3000, vec![transaction]))
+            }
+            Self::Http(code) => (*code, String::new()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    replies: VecDeque<Reply>,
+    requests: Vec<String>,
+}
+
+impl State {
+    /// The next reply, repeating the last one once the queue is down to it.
+    fn next_reply(&mut self) -> Option<Reply> {
+        if self.replies.len() > 1 {
+            self.replies.pop_front()
+        } else {
+            self.replies.front().cloned()
+        }
+    }
+}
+
+/// A local OFX server, stopped when dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stopped: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts listening on an unused port on `127.0.0.1`.
+    ///
+    /// Until a reply is queued, requests are answered with HTTP 500.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no port can be bound.
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let handle = thread::spawn({
+            let (state, stopped) = (Arc::clone(&state), Arc::clone(&stopped));
+            move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        // A client that hangs up early is its own problem
+                        serve(stream, &state).ok();
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            stopped,
+            handle: Some(handle),
+        })
+    }
+
+    /// The URL to POST requests to.
+    #[must_use]
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    #[must_use]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Queues a reply. Replies are sent in order, and the last one is repeated.
+    pub fn reply(&self, reply: Reply) {
+        self.state().replies.push_back(reply);
+    }
+
+    /// The bodies of the requests received so far.
+    #[must_use]
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the listener so it sees the flag
+        TcpStream::connect(self.addr).ok();
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+/// Reads one request, records its body, and writes the next reply.
+fn serve(mut stream: TcpStream, state: &Mutex<State>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or_default();
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let reply = {
+        let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+        state
+            .requests
+            .push(String::from_utf8_lossy(&body).into_owned());
+        state.next_reply()
+    };
+    let (code, body) = reply
+        .as_ref()
+        .map_or_else(|| (500, "no reply queued".into()), Reply::render);
+    write!(
+        stream,
+        "HTTP/1.1 {code} {}\r\nContent-Type: application/x-ofx\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        if code == 200 { "OK" } else { "Error" },
+        body.len(),
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Ofx;
+
+    fn post(server: &MockServer, body: &str) -> (String, String) {
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nContent-Type: application/x-ofx\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.lines().next().unwrap().to_owned(), body.to_owned())
+    }
+
+    #[test]
+    fn serves_queued_replies() {
+        let server = MockServer::start().unwrap();
+        assert!(post(&server, "").0.contains("500"));
+
+        server.reply(Reply::SignOnError(15500));
+        server.reply(Reply::MfaChallenge(vec![(
+            "MFA13".into(),
+            "Please enter the last four digits of your SSN".into(),
+        )]));
+        server.reply(Reply::Statement(Config::new(1)));
+        server.reply(Reply::Http(503));
+
+        let (status, body) = post(&server, "<OFX>first</OFX>");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        let ofx: Ofx = body.parse().unwrap();
+        assert_eq!(ofx.body.sign_on.unwrap().response.status.code, 15500);
+
+        let (_, body) = post(&server, "<OFX>second</OFX>");
+        assert!(body.contains("<CODE>3000</CODE>"));
+        assert!(body.contains("<MFAPHRASEID>MFA13</MFAPHRASEID>"));
+
+        let (_, body) = post(&server, "");
+        let ofx: Ofx = body.parse().unwrap();
+        assert!(ofx.body.bank.is_some());
+
+        assert_eq!(post(&server, "").0, "HTTP/1.1 503 Error");
+        assert_eq!(post(&server, "").0, "HTTP/1.1 503 Error");
+        assert_eq!(
+            &server.requests()[1..3],
+            ["<OFX>first</OFX>", "<OFX>second</OFX>"]
+        );
+    }
+}
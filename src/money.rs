@@ -0,0 +1,46 @@
+//! Integration with [`rusty_money`].
+
+use rusty_money::{Money, iso};
+
+use crate::body::Transaction;
+
+impl Transaction {
+    /// The amount as [`Money`] in the currency it is actually denominated in.
+    ///
+    /// Per the 1.6 spec, 5.2, a `<CURRENCY>` aggregate means the amount was not converted and is
+    /// in `CURSYM`, while `<ORIGCURRENCY>` means it was already converted to the statement's
+    /// `CURDEF`. Pass the statement's `CURDEF` as `default_currency`.
+    ///
+    /// Returns `None` if the currency is not a known ISO-4217 code.
+    #[must_use]
+    pub fn money(&self, default_currency: &str) -> Option<Money<'static, iso::Currency>> {
+        let code = self
+            .currency
+            .as_ref()
+            .map_or(default_currency, |currency| currency.symbol.as_str());
+        let currency = iso::find(code)?;
+        Some(Money::from_decimal(self.amount, currency))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::Currency;
+
+    #[test]
+    fn resolves_currency() {
+        let transaction: Transaction =
+            "<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5.25<FITID>1</STMTTRN>"
+                .parse()
+                .unwrap();
+        let money = transaction.money("USD").unwrap();
+        assert_eq!(money.currency(), iso::USD);
+        assert_eq!(money.amount().to_string(), "-5.25");
+        assert!(transaction.money("ZZZ").is_none());
+
+        let transaction =
+            transaction.with_currency(Currency::new(rust_decimal::Decimal::new(11, 1), "EUR"));
+        assert_eq!(transaction.money("USD").unwrap().currency(), iso::EUR);
+    }
+}
@@ -0,0 +1,239 @@
+//! Importing SWIFT MT940 statements into the OFX statement model.
+//!
+//! Many European banks offer MT940 exports instead of OFX. [`parse`] maps each statement onto a
+//! [`StatementResponse`] so both formats can be handled with the same code.
+
+use crate::{
+    Result,
+    body::{
+        AccountType, Balance, BankAccount, BankTransactionList, StatementResponse, Transaction,
+        TransactionType,
+    },
+    datetime,
+    decimal::Decimal,
+    error::Error,
+};
+
+fn error(message: impl std::fmt::Display) -> Error {
+    Error::parse(format!("invalid MT940: {message}"))
+}
+
+/// Splits the text block into `(tag, value)` fields, joining continuation lines with `\n`.
+fn fields(s: &str) -> Vec<(&str, String)> {
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    for line in s.lines().map(str::trim_end) {
+        // Skip SWIFT block headers like `{1:F01...}{4:` and trailers like `-}`
+        if line.is_empty() || line.starts_with('{') || line == "-" || line.starts_with("-}") {
+            continue;
+        }
+        let tagged = line
+            .strip_prefix(':')
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(tag, _)| tag.len() <= 3 && tag.bytes().all(|b| b.is_ascii_alphanumeric()));
+        match (tagged, fields.last_mut()) {
+            (Some((tag, value)), _) => fields.push((tag, value.to_owned())),
+            (None, Some((_, value))) => {
+                value.push('\n');
+                value.push_str(line);
+            }
+            (None, None) => {}
+        }
+    }
+    fields
+}
+
+/// Expands a `YYMMDD` date to an OFX `YYYYMMDD` date.
+fn date(yymmdd: &str) -> Result<String> {
+    let yy: u32 = yymmdd
+        .get(..2)
+        .and_then(|yy| yy.parse().ok())
+        .ok_or_else(|| error(format!("bad date `{yymmdd}`")))?;
+    let century = if yy < 80 { "20" } else { "19" };
+    Ok(format!("{century}{yymmdd}"))
+}
+
+/// Converts an amount like `1234,56` to `1234.56`, negated for debits.
+fn amount(s: &str, debit: bool) -> String {
+    let sign = if debit { "-" } else { "" };
+    match s.split_once(',') {
+        Some((int, frac)) if !frac.is_empty() => format!("{sign}{int}.{frac}"),
+        Some((int, _)) => format!("{sign}{int}"),
+        None => format!("{sign}{s}"),
+    }
+}
+
+/// Parses a balance such as `C250901EUR1234,56` into its date, currency, and signed amount.
+fn balance(s: &str) -> Result<(String, String, String)> {
+    let bad = || error(format!("bad balance `{s}`"));
+    let debit = match s.get(..1) {
+        Some("C") => false,
+        Some("D") => true,
+        _ => return Err(bad()),
+    };
+    let date = date(s.get(1..7).ok_or_else(bad)?)?;
+    let currency = s.get(7..10).ok_or_else(bad)?.to_owned();
+    Ok((date, currency, amount(s.get(10..).ok_or_else(bad)?, debit)))
+}
+
+/// Parses a `:61:` statement line, using `fallback_id` if it carries no usable reference.
+fn transaction(s: &str, fallback_id: String) -> Result<Transaction> {
+    let bad = || error(format!("bad statement line `{s}`"));
+    let line = s.lines().next().unwrap_or_default();
+
+    let posted = date(line.get(..6).ok_or_else(bad)?)?;
+    let mut rest = &line[6..];
+    // Optional MMDD entry date
+    if rest.len() >= 4 && rest.as_bytes()[..4].iter().all(u8::is_ascii_digit) {
+        rest = &rest[4..];
+    }
+
+    // A reversal of a credit is a debit, and vice versa
+    let (credit, len) = match rest.get(..2) {
+        Some("RC") => (false, 2),
+        Some("RD") => (true, 2),
+        _ => match rest.get(..1) {
+            Some("C") => (true, 1),
+            Some("D") => (false, 1),
+            _ => return Err(bad()),
+        },
+    };
+    rest = &rest[len..];
+    // Optional funds code, the last letter of the currency code
+    if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        rest = &rest[1..];
+    }
+
+    let amount_len = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ',')
+        .unwrap_or(rest.len());
+    let amount: Decimal = amount(&rest[..amount_len], !credit)
+        .parse()
+        .map_err(|_| bad())?;
+    // Skip the four-character transaction type, e.g. `NTRF`
+    let references = rest.get(amount_len + 4..).unwrap_or_default();
+    let (customer, bank) = references.split_once("//").unwrap_or((references, ""));
+    let id = [bank.trim(), customer.trim()]
+        .into_iter()
+        .find(|id| !id.is_empty() && *id != "NONREF")
+        .map_or(fallback_id, str::to_owned);
+
+    let transaction_type = if credit {
+        TransactionType::Credit
+    } else {
+        TransactionType::Debit
+    };
+    Ok(Transaction::new(
+        transaction_type,
+        datetime::parse(&posted)?,
+        amount,
+        id,
+    ))
+}
+
+/// Parses every statement in an MT940 file.
+///
+/// Each statement's closing balance (`:62F:`/`:62M:`) becomes the ledger balance and `:64:` the
+/// available balance. `:25:` is split into bank and account IDs when written as
+/// `BANKCODE/ACCOUNT`; otherwise (e.g. an IBAN) the bank ID is left empty. `:86:` information
+/// becomes the preceding transaction's memo.
+///
+/// # Errors
+///
+/// Returns an error if a balance or statement line is malformed.
+pub fn parse(s: &str) -> Result<Vec<StatementResponse>> {
+    let mut statements = Vec::new();
+    let mut current: Option<(String, StatementResponse)> = None;
+
+    for (tag, value) in fields(s) {
+        if tag == "20" {
+            statements.extend(current.take().map(|(_, statement)| statement));
+            current = Some((value.trim().to_owned(), StatementResponse::new("")));
+            continue;
+        }
+        let Some((reference, statement)) = &mut current else {
+            return Err(error(format!("`:{tag}:` before `:20:`")));
+        };
+        let list = statement
+            .bank_transactions
+            .get_or_insert_with(BankTransactionList::default);
+        match tag {
+            "25" => {
+                let value = value.trim();
+                let (bank_id, account_id) = value.split_once('/').unwrap_or(("", value));
+                statement.account =
+                    Some(BankAccount::new(bank_id, account_id, AccountType::Checking));
+            }
+            "60F" | "60M" => {
+                let (date, currency, _) = balance(value.trim())?;
+                statement.currency = currency;
+                list.dtstart = Some(date);
+            }
+            "61" => {
+                let fallback_id = format!("{reference}-{}", list.transactions.len());
+                list.transactions.push(transaction(&value, fallback_id)?);
+            }
+            "86" => {
+                if let Some(transaction) = list.transactions.last_mut() {
+                    let memo = value.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+                    transaction.memo = Some(memo);
+                }
+            }
+            "62F" | "62M" => {
+                let (date, currency, amount) = balance(value.trim())?;
+                statement.currency = currency;
+                let date_asof = datetime::parse(&date)?;
+                list.dtend = Some(date);
+                statement.ledger_balance = Some(Balance::new(amount, date_asof));
+            }
+            "64" => {
+                let (date, _, amount) = balance(value.trim())?;
+                statement.available_balance = Some(Balance::new(amount, datetime::parse(&date)?));
+            }
+            _ => {}
+        }
+    }
+    statements.extend(current.map(|(_, statement)| statement));
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_statements() {
+        let input = "{1:F01BANKDEFFXXXX0000000000}{2:I940BANKDEFFXXXXN}{4:
+:20:STARTUMSE
+:25:10020030/1234567
+:28C:00001/001
+:60F:C250901EUR1000,00
+:61:2509020902DR12,50NTRFNONREF//PAY-1
+:86:Coffee shop
+ Berlin
+:61:250903C200,NTRFINV-42
+:62F:C250903EUR1187,50
+:64:C250903EUR1187,50
+-}";
+        let statements = parse(input).unwrap();
+        assert_eq!(statements.len(), 1);
+        let statement = &statements[0];
+        assert_eq!(statement.currency, "EUR");
+        let account = statement.account.as_ref().unwrap();
+        assert_eq!(account.bank_id, "10020030");
+        assert_eq!(account.id.expose(), "1234567");
+        assert_eq!(statement.ledger_balance.as_ref().unwrap().amount, "1187.50");
+
+        let list = statement.bank_transactions.as_ref().unwrap();
+        assert_eq!(list.dtstart.as_deref(), Some("20250901"));
+        assert_eq!(list.dtend.as_deref(), Some("20250903"));
+        let [debit, credit] = &list.transactions[..] else {
+            panic!("expected two transactions");
+        };
+        assert_eq!(debit.transaction_type, TransactionType::Debit);
+        assert_eq!(debit.amount, "-12.5".parse::<Decimal>().unwrap());
+        assert_eq!(debit.id, "PAY-1");
+        assert_eq!(debit.memo.as_deref(), Some("Coffee shop Berlin"));
+        assert_eq!(credit.transaction_type, TransactionType::Credit);
+        assert_eq!(credit.id, "INV-42");
+    }
+}
@@ -0,0 +1,190 @@
+//! The untyped element tree of an OFX body.
+//!
+//! [`OfxNode`] is the SGML with omitted end tags filled in, but tags and text otherwise as the
+//! institution wrote them, before any of it is mapped onto the typed structs. It is handy for
+//! debugging a bank's file, and for reading message sets this crate does not model yet.
+
+use std::{fmt, str::FromStr};
+
+use crate::{Result, body::Body, error::Error, options::ParserOptions, tree::Element};
+
+/// An element: a leaf holding text, or an aggregate holding other elements.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OfxNode {
+    name: String,
+    text: Option<String>,
+    children: Vec<OfxNode>,
+}
+
+impl From<Element> for OfxNode {
+    fn from(element: Element) -> Self {
+        Self {
+            name: element.name,
+            text: element.text.map(|text| text.trim().to_owned()),
+            children: element.children.into_iter().map(Self::from).collect(),
+        }
+    }
+}
+
+impl OfxNode {
+    /// Parses the `<OFX>` element of a document, ignoring its headers, without the typed layer.
+    ///
+    /// The `<OFX>` element alone is accepted too, so an excerpt can be pasted in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no `<OFX>` element or it is not well-formed SGML.
+    pub fn parse(s: &str) -> Result<Self> {
+        let options = ParserOptions::default();
+        ParserOptions::check("file size", s.len(), options.max_file_size)?;
+        let start = s
+            .find("<OFX>")
+            .ok_or_else(|| Error::parse("no `<OFX>` found"))?;
+        Body::parse_element(&s[start..], &options).map(Self::from)
+    }
+
+    /// The tag name, e.g. `STMTTRN`.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The text of a leaf, trimmed, or `None` for an aggregate.
+    #[must_use]
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    #[must_use]
+    pub fn children(&self) -> &[OfxNode] {
+        &self.children
+    }
+
+    /// The first child named `name`.
+    #[must_use]
+    pub fn child(&self, name: &str) -> Option<&OfxNode> {
+        self.children_named(name).next()
+    }
+
+    pub fn children_named<'a, 'n>(
+        &'a self,
+        name: &'n str,
+    ) -> impl Iterator<Item = &'a OfxNode> + use<'a, 'n> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// The first element named `name`, depth-first, including `self`.
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<&OfxNode> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+
+    /// Every element named `name`, depth-first, including `self`.
+    #[must_use]
+    pub fn find_all<'a>(&'a self, name: &str) -> Vec<&'a OfxNode> {
+        let mut found = Vec::new();
+        self.collect(name, &mut found);
+        found
+    }
+
+    fn collect<'a>(&'a self, name: &str, found: &mut Vec<&'a OfxNode>) {
+        if self.name == name {
+            found.push(self);
+        }
+        for child in &self.children {
+            child.collect(name, found);
+        }
+    }
+
+    /// Follows a `/`-separated path of child names, matching every child with each name, or only
+    /// the `i`th (from 0) for a step written `NAME[i]`.
+    #[must_use]
+    pub fn select<'a>(&'a self, path: &str) -> Vec<&'a OfxNode> {
+        let mut selected = vec![self];
+        for step in path.split('/').filter(|step| !step.is_empty()) {
+            let (name, index) = match step.strip_suffix(']').and_then(|s| s.split_once('[')) {
+                Some((name, index)) => (name, index.parse::<usize>().ok()),
+                None => (step, None),
+            };
+            selected = selected
+                .into_iter()
+                .flat_map(|node| {
+                    let children = node.children_named(name);
+                    match index {
+                        Some(index) => children.skip(index).take(1).collect::<Vec<_>>(),
+                        None => children.collect(),
+                    }
+                })
+                .collect();
+        }
+        selected
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        match &self.text {
+            Some(text) if self.children.is_empty() => {
+                let text = text
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;");
+                writeln!(f, "{indent}<{}>{text}", self.name)
+            }
+            _ => {
+                writeln!(f, "{indent}<{}>", self.name)?;
+                for child in &self.children {
+                    child.write_indented(f, depth + 1)?;
+                }
+                writeln!(f, "{indent}</{}>", self.name)
+            }
+        }
+    }
+}
+
+/// Writes SGML with one element per line, indented by depth, and end tags only for aggregates.
+impl fmt::Display for OfxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+impl FromStr for OfxNode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn navigates_the_tree() {
+        let input = std::fs::read_to_string("tests/files/simple.ofx").unwrap();
+        let root = OfxNode::parse(&input).unwrap();
+        assert_eq!(root.name(), "OFX");
+        assert_eq!(root.children().len(), 2);
+        assert_eq!(root.find("ORG").and_then(OfxNode::text), Some("Apple Card"));
+        assert_eq!(root.find_all("STMTTRN").len(), 4);
+        assert_eq!(root.find_all("BALAMT").len(), 2);
+
+        let list = root.find("BANKTRANLIST").unwrap();
+        assert_eq!(list.text(), None);
+        assert_eq!(
+            list.children_named("STMTTRN")
+                .filter_map(|t| t.child("TRNAMT")?.text())
+                .collect::<Vec<_>>(),
+            ["-5.00", "-3.00", "3220.56", "-46.05"]
+        );
+
+        let shown = root.to_string();
+        assert!(shown.starts_with("<OFX>\n  <SIGNONMSGSRSV1>\n    <SONRS>\n"));
+        assert!(shown.contains("\n        <FID>23456\n"));
+        assert_eq!(shown.parse::<OfxNode>().unwrap(), root);
+    }
+}
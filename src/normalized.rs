@@ -0,0 +1,150 @@
+//! A flat, stable transaction model for apps that just want clean transactions.
+//!
+//! OFX nests transactions several aggregates deep and spreads the account and currency across
+//! them. [`Normalized`] flattens a document into one record per transaction:
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "transactions": [
+//!     {
+//!       "account": "1234567890",
+//!       "id": "20250901-1",
+//!       "date": "2025-09-01",
+//!       "amount": "-5.25",
+//!       "currency": "USD",
+//!       "payee": "Coffee shop",
+//!       "memo": null,
+//!       "category": null
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Fields are only ever added, and doing so bumps [`SCHEMA_VERSION`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Ofx,
+    body::{AccountId, BankTransactionList},
+    datetime,
+    decimal::Decimal,
+};
+
+/// The version of the normalized schema produced by this release.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Normalized {
+    pub schema_version: u32,
+    pub transactions: Vec<NormalizedTransaction>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct NormalizedTransaction {
+    /// The `ACCTID` of the statement the transaction came from
+    pub account: AccountId,
+    /// The `FITID`
+    pub id: String,
+    /// The posted date as `YYYY-MM-DD`
+    pub date: String,
+    /// Negative for money leaving the account
+    pub amount: Decimal,
+    /// The transaction's `CURRENCY`, falling back to the statement's `CURDEF`
+    pub currency: String,
+    pub payee: Option<String>,
+    pub memo: Option<String>,
+    /// Never set by ofxy; a slot for apps to fill in
+    pub category: Option<String>,
+}
+
+fn push_transactions(
+    out: &mut Vec<NormalizedTransaction>,
+    account: &AccountId,
+    currency: &str,
+    list: Option<&BankTransactionList>,
+) {
+    let Some(list) = list else {
+        return;
+    };
+    out.extend(list.transactions.iter().map(|transaction| {
+        NormalizedTransaction {
+            account: account.clone(),
+            id: transaction.id.clone(),
+            date: datetime::iso_date(&transaction.date_posted),
+            amount: Clone::clone(&transaction.amount),
+            currency: transaction
+                .currency
+                .as_ref()
+                .map_or(currency, |own| own.symbol.as_str())
+                .to_owned(),
+            payee: transaction.name.clone(),
+            memo: transaction.memo.clone(),
+            category: None,
+        }
+    }));
+}
+
+impl From<&Ofx> for Normalized {
+    fn from(ofx: &Ofx) -> Self {
+        let mut transactions = Vec::new();
+        if let Some(credit_card) = &ofx.body.credit_card
+            && let Some(statement) = &credit_card.transaction_response.statement
+        {
+            push_transactions(
+                &mut transactions,
+                &statement.account.id,
+                &statement.currency,
+                statement.bank_transactions.as_ref(),
+            );
+        }
+        if let Some(bank) = &ofx.body.bank
+            && let Some(statement) = &bank.transaction_response.statement
+        {
+            let account = statement
+                .account
+                .as_ref()
+                .map_or_else(|| AccountId::new(""), |account| account.id.clone());
+            push_transactions(
+                &mut transactions,
+                &account,
+                &statement.currency,
+                statement.bank_transactions.as_ref(),
+            );
+        }
+        Self {
+            schema_version: SCHEMA_VERSION,
+            transactions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flattens_transactions() {
+        let ofx = crate::document(
+            "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>USD\
+            <BANKACCTFROM><BANKID>121000248<ACCTID>1234567890<ACCTTYPE>CHECKING</BANKACCTFROM>\
+            <BANKTRANLIST><STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5.25\
+            <FITID>20250901-1<NAME>Coffee shop</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>",
+        );
+        let normalized = Normalized::from(&ofx);
+        assert_eq!(normalized.schema_version, SCHEMA_VERSION);
+        let [transaction] = &normalized.transactions[..] else {
+            panic!("expected one transaction");
+        };
+        assert_eq!(transaction.account.expose(), "1234567890");
+        assert_eq!(transaction.date, "2025-09-01");
+        assert_eq!(transaction.amount, "-5.25".parse::<Decimal>().unwrap());
+        assert_eq!(transaction.currency, "USD");
+        assert_eq!(transaction.payee.as_deref(), Some("Coffee shop"));
+        assert_eq!(transaction.category, None);
+    }
+}
@@ -0,0 +1,113 @@
+//! Parsing the [OFX Home](https://www.ofxhome.com) institution directory.
+//!
+//! OFX Home lists the connection parameters (URL, `ORG`, `FID`, `BROKERID`) that clients need to
+//! talk to each institution. Ofxy does not make network requests itself; fetch
+//! `https://www.ofxhome.com/api.php?lookup=<id>` (or a saved dump of several entries) with the
+//! HTTP client of your choice and pass the XML to [`parse`].
+
+use crate::{Result, error::Error};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Institution {
+    /// OFX Home's own identifier for the institution
+    pub id: String,
+    pub name: String,
+    /// The `FID` to send in `<FI>`
+    pub fid: Option<String>,
+    /// The `ORG` to send in `<FI>`
+    pub org: Option<String>,
+    /// The OFX server endpoint
+    pub url: Option<String>,
+    /// The `BROKERID` for investment accounts
+    pub broker_id: Option<String>,
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// The trimmed text of the first `<tag>` in `s`, if present and non-empty.
+fn child_text(s: &str, tag: &str) -> Option<String> {
+    let start = s.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let len = s[start..].find(&format!("</{tag}>"))?;
+    Some(unescape(s[start..start + len].trim())).filter(|text| !text.is_empty())
+}
+
+/// Parses every `<institution>` element in an OFX Home API response.
+///
+/// # Errors
+///
+/// Returns an error if an institution element is unterminated or has no `id` attribute.
+pub fn parse(xml: &str) -> Result<Vec<Institution>> {
+    let mut institutions = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<institution ") {
+        rest = &rest[start..];
+        let end = rest
+            .find("</institution>")
+            .ok_or_else(|| Error::parse("unterminated OFX Home institution"))?;
+        let (element, tail) = rest.split_at(end);
+        let open_tag = &element[..element.find('>').unwrap_or(element.len())];
+        let id = open_tag
+            .split_once("id=\"")
+            .and_then(|(_, id)| id.split_once('"'))
+            .map(|(id, _)| id.to_owned())
+            .ok_or_else(|| Error::parse("OFX Home institution without an id"))?;
+        institutions.push(Institution {
+            id,
+            name: child_text(element, "name").unwrap_or_default(),
+            fid: child_text(element, "fid"),
+            org: child_text(element, "org"),
+            url: child_text(element, "url"),
+            broker_id: child_text(element, "brokerid"),
+        });
+        rest = tail;
+    }
+    Ok(institutions)
+}
+
+/// Finds institutions whose name contains `query`, ignoring case.
+#[must_use]
+pub fn find_by_name<'a>(institutions: &'a [Institution], query: &str) -> Vec<&'a Institution> {
+    let query = query.to_lowercase();
+    institutions
+        .iter()
+        .filter(|institution| institution.name.to_lowercase().contains(&query))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_directory() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<institution id="424">
+<name>Example Bank &amp; Trust</name>
+<fid>12345</fid>
+<org>EXB</org>
+<url>https://ofx.example.com/ofx</url>
+<brokerid></brokerid>
+<ofxfail>0</ofxfail>
+</institution>"#;
+        let institutions = parse(xml).unwrap();
+        assert_eq!(
+            institutions,
+            [Institution {
+                id: "424".into(),
+                name: "Example Bank & Trust".into(),
+                fid: Some("12345".into()),
+                org: Some("EXB".into()),
+                url: Some("https://ofx.example.com/ofx".into()),
+                broker_id: None,
+            }]
+        );
+        assert_eq!(find_by_name(&institutions, "example bank").len(), 1);
+        assert!(find_by_name(&institutions, "credit union").is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+//! Settings controlling how documents are parsed.
+
+use std::{cell::RefCell, fs, path::Path};
+
+use crate::{Ofx, Result, datetime::TimeZonePolicy, error::ErrorKind, warning::Warning};
+
+/// The options and warnings of the parse in progress on this thread, for code that serde calls
+/// without a way to pass them in, like datetime deserialization.
+struct Context {
+    options: ParserOptions,
+    warnings: Vec<Warning>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+/// Options for parsing a document.
+///
+/// Settings are added over time, so construct this with [`ParserOptions::default`] and adjust
+/// the fields you need. The limits bound the resources spent on untrusted input; exceeding one
+/// fails with [`ErrorKind::LimitExceeded`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ParserOptions {
+    /// The largest document accepted, in bytes. Unlimited by default.
+    pub max_file_size: usize,
+    /// The most transactions accepted in a single transaction list. Unlimited by default.
+    pub max_transactions: usize,
+    /// The deepest nesting of aggregates accepted. Real documents stay under 20 levels.
+    pub max_depth: usize,
+    /// The most header lines accepted. The spec defines nine.
+    pub max_headers: usize,
+    /// The longest text value accepted, in bytes. The longest spec field, `MEMO`, is 255
+    /// characters.
+    pub max_value_len: usize,
+    /// The largest aggregate [`crate::stream`] holds in memory, in bytes. 1 MiB by default,
+    /// far more than a transaction needs.
+    pub max_aggregate_size: usize,
+    /// Recover from malformed values that would otherwise fail the whole document, such as the
+    /// garbled offset in `[-:EST]` or amounts like `-16,40`, `1,234.56`, and `(16.40)`,
+    /// recording a [`Warning`] instead. Off by default.
+    pub lenient: bool,
+    /// Reject datetimes the spec does not allow rather than reading them as best as possible,
+    /// with an error naming the offending component, e.g. `day 30 is out of range for 2025-02`.
+    /// This overrides [`ParserOptions::lenient`] for datetimes. Off by default.
+    pub strict_datetimes: bool,
+    /// Which time zone parsed datetimes are expressed in. UTC by default, except under `jiff`,
+    /// which keeps the bank's offset.
+    pub time_zone: TimeZonePolicy,
+    /// Read a zone name written without an offset, as in `[EST]`, as its IANA zone, so the DST
+    /// rules in effect on the date apply. A numeric offset always takes precedence, since the
+    /// spec defines the zone by it. Needs the `chrono_tz` feature. Off by default.
+    pub zone_names: bool,
+    /// A charset to decode bytes with instead of the one the header declares, for banks that
+    /// mislabel their files, e.g. `UTF-8` for UTF-8 files declaring `CHARSET:1252`. Bytes that
+    /// are invalid in it are decoded as usual. Needs the `encoding` feature.
+    pub charset: Option<String>,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            max_file_size: usize::MAX,
+            max_transactions: usize::MAX,
+            max_depth: 64,
+            max_headers: 64,
+            max_value_len: 64 * 1024,
+            max_aggregate_size: 1024 * 1024,
+            lenient: false,
+            strict_datetimes: false,
+            time_zone: TimeZonePolicy::default(),
+            zone_names: false,
+            charset: None,
+        }
+    }
+}
+
+impl ParserOptions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a document with these options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be parsed or exceeds a limit.
+    pub fn parse(&self, s: &str) -> Result<(Ofx, Vec<Warning>)> {
+        Ofx::parse_with_options(s, self)
+    }
+
+    /// Parses a document from bytes, decoding it as described in [`crate::decode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be parsed or exceeds a limit.
+    pub fn parse_bytes(&self, bytes: &[u8]) -> Result<(Ofx, Vec<Warning>)> {
+        Self::check("file size", bytes.len(), self.max_file_size)?;
+        #[cfg(feature = "flate2")]
+        let decompressed = crate::decode::gunzip(bytes, self.max_file_size)?;
+        #[cfg(feature = "flate2")]
+        let bytes = &*decompressed;
+        let (text, mut warnings) = crate::decode::decode_as(bytes, self.charset.as_deref());
+        let (ofx, parse_warnings) = self.parse(&text)?;
+        warnings.extend(parse_warnings);
+        Ok((ofx, warnings))
+    }
+
+    /// Reads and parses a file, decoding it as described in [`crate::decode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::Io`] error if the file cannot be read, or an error if it cannot be
+    /// parsed or exceeds a limit.
+    pub fn parse_file(&self, path: impl AsRef<Path>) -> Result<(Ofx, Vec<Warning>)> {
+        let path = path.as_ref();
+        // Check the size before reading, so oversized files are never loaded
+        let size = usize::try_from(fs::metadata(path)?.len()).unwrap_or(usize::MAX);
+        Self::check("file size", size, self.max_file_size)?;
+        self.parse_bytes(&fs::read(path)?)
+    }
+
+    /// Runs `f` with these options available to [`ParserOptions::current`], returning the
+    /// warnings recorded with [`ParserOptions::warn`] along the way.
+    pub(crate) fn scoped<T>(&self, f: impl FnOnce() -> T) -> (T, Vec<Warning>) {
+        let context = Context {
+            options: self.clone(),
+            warnings: Vec::new(),
+        };
+        let outer = CONTEXT.with(|cell| cell.replace(Some(context)));
+        let value = f();
+        let context = CONTEXT.with(|cell| cell.replace(outer));
+        (value, context.map(|c| c.warnings).unwrap_or_default())
+    }
+
+    /// Calls `f` with the options of the parse in progress, or the defaults outside of one.
+    pub(crate) fn current<T>(f: impl FnOnce(&ParserOptions) -> T) -> T {
+        CONTEXT.with(|cell| match &*cell.borrow() {
+            Some(context) => f(&context.options),
+            None => f(&ParserOptions::default()),
+        })
+    }
+
+    /// Records a warning for the parse in progress, if any.
+    pub(crate) fn warn(warning: Warning) {
+        CONTEXT.with(|cell| {
+            if let Some(context) = &mut *cell.borrow_mut() {
+                context.warnings.push(warning);
+            }
+        });
+    }
+
+    /// Fails with [`ErrorKind::LimitExceeded`] if `value` is over `max`.
+    pub(crate) fn check(limit: &'static str, value: usize, max: usize) -> Result<()> {
+        if value > max {
+            return Err(ErrorKind::LimitExceeded { limit, max }.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sets one limit low enough for `simple.ofx` to exceed it.
+    type Adjust = fn(&mut ParserOptions);
+
+    #[test]
+    fn enforces_limits() {
+        let input = std::fs::read_to_string("tests/files/simple.ofx").unwrap();
+        assert!(ParserOptions::default().parse(&input).is_ok());
+
+        let limited = |adjust: Adjust| {
+            let mut options = ParserOptions::default();
+            adjust(&mut options);
+            options.parse(&input).unwrap_err().kind().clone()
+        };
+        let cases: [(Adjust, &str); 5] = [
+            (|o| o.max_file_size = 100, "file size"),
+            (|o| o.max_transactions = 1, "transactions per list"),
+            (|o| o.max_depth = 3, "nesting depth"),
+            (|o| o.max_headers = 2, "header count"),
+            (|o| o.max_value_len = 2, "value length"),
+        ];
+        for (adjust, name) in cases {
+            assert!(
+                matches!(limited(adjust), ErrorKind::LimitExceeded { limit, .. } if limit == name),
+                "{name}"
+            );
+        }
+    }
+}
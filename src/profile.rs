@@ -0,0 +1,151 @@
+//! The FI profile response (`PROFRS`), describing which message sets a server supports.
+//!
+//! Clients should request the profile on first connect (1.6 spec, 7) and build later requests
+//! with the message set versions it advertises.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use crate::{ParserOptions, Result, body::normalized_fragment, error::Error, tree::Element};
+
+/// A message set, named after its `<XXXMSGSET>` profile tag.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum MessageSet {
+    SignOn,
+    Signup,
+    Bank,
+    CreditCard,
+    Investment,
+    InterTransfer,
+    WireTransfer,
+    BillPay,
+    Email,
+    SecurityList,
+    PresentmentDirectory,
+    PresentmentDelivery,
+    Profile,
+    Image,
+    /// A message set ofxy does not know, by its tag prefix (e.g. `TAX1099`)
+    Other(String),
+}
+
+impl MessageSet {
+    /// Maps a profile tag such as `BANKMSGSET` to its message set.
+    fn from_tag(tag: &str) -> Option<Self> {
+        let prefix = tag.strip_suffix("MSGSET")?;
+        Some(match prefix {
+            "SIGNON" => Self::SignOn,
+            "SIGNUP" => Self::Signup,
+            "BANK" => Self::Bank,
+            "CREDITCARD" => Self::CreditCard,
+            "INVSTMT" => Self::Investment,
+            "INTERXFER" => Self::InterTransfer,
+            "WIREXFER" => Self::WireTransfer,
+            "BILLPAY" => Self::BillPay,
+            "EMAIL" => Self::Email,
+            "SECLIST" => Self::SecurityList,
+            "PRESDIR" => Self::PresentmentDirectory,
+            "PRESDLV" => Self::PresentmentDelivery,
+            "PROF" => Self::Profile,
+            "IMAGE" => Self::Image,
+            other => Self::Other(other.to_owned()),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    /// The versions advertised for each message set, in ascending order
+    pub message_sets: BTreeMap<MessageSet, Vec<u32>>,
+    /// `FINAME`
+    pub fi_name: Option<String>,
+    /// The raw `DTPROFUP`, for deciding when to request the profile again
+    pub updated: Option<String>,
+}
+
+impl Profile {
+    #[must_use]
+    pub fn supports(&self, message_set: &MessageSet) -> bool {
+        self.message_sets.contains_key(message_set)
+    }
+
+    /// The highest version of `message_set` the server accepts.
+    #[must_use]
+    pub fn max_version(&self, message_set: &MessageSet) -> Option<u32> {
+        self.message_sets
+            .get(message_set)
+            .and_then(|versions| versions.last().copied())
+    }
+
+    fn from_element(profrs: &Element) -> Self {
+        let mut message_sets = BTreeMap::<_, Vec<u32>>::new();
+        let sets = profrs
+            .child("MSGSETLIST")
+            .map_or(&[][..], |list| list.children.as_slice());
+        for set in sets {
+            let Some(message_set) = MessageSet::from_tag(&set.name) else {
+                continue;
+            };
+            let versions = message_sets.entry(message_set).or_default();
+            for versioned in &set.children {
+                // Prefer <VER>, falling back to the `V1` suffix of e.g. <BANKMSGSETV1>
+                let version = versioned
+                    .descendant(&["MSGSETCORE", "VER"])
+                    .and_then(|ver| ver.value().trim().parse::<u32>().ok())
+                    .or_else(|| {
+                        let (_, suffix) = versioned.name.rsplit_once('V')?;
+                        suffix.parse::<u32>().ok()
+                    });
+                versions.extend(version);
+            }
+            versions.sort_unstable();
+            versions.dedup();
+        }
+        let text = |name| profrs.find(name).map(|e| e.value().trim().to_owned());
+        Self {
+            message_sets,
+            fi_name: text("FINAME"),
+            updated: text("DTPROFUP"),
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = Error;
+
+    /// Parses the `PROFRS` from a full profile response document or a bare `<PROFRS>` fragment.
+    fn from_str(s: &str) -> Result<Self> {
+        let start = s.find("<OFX>").or_else(|| s.find("<PROFRS>")).unwrap_or(0);
+        let root = Element::from_fragment(&normalized_fragment(
+            &s[start..],
+            &ParserOptions::default(),
+        )?)?;
+        root.find("PROFRS")
+            .map(Self::from_element)
+            .ok_or_else(|| Error::parse("no `<PROFRS>` found"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_profile() {
+        let input = "<OFX><PROFMSGSRSV1><PROFTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS><PROFRS><MSGSETLIST>\
+            <SIGNONMSGSET><SIGNONMSGSETV1><MSGSETCORE><VER>1<URL>https://ofx.example.com\
+            <OFXSEC>NONE<TRANSPSEC>Y<SIGNONREALM>DEFAULT<LANGUAGE>ENG<SYNCMODE>LITE\
+            <RESPFILEER>N</MSGSETCORE></SIGNONMSGSETV1></SIGNONMSGSET>\
+            <BANKMSGSET><BANKMSGSETV1><MSGSETCORE><VER>1</MSGSETCORE></BANKMSGSETV1>\
+            <BANKMSGSETV2><MSGSETCORE><VER>2</MSGSETCORE></BANKMSGSETV2></BANKMSGSET>\
+            </MSGSETLIST><DTPROFUP>20250101<FINAME>Example Bank\
+            </PROFRS></PROFTRNRS></PROFMSGSRSV1></OFX>";
+        let profile: Profile = input.parse().unwrap();
+        assert!(profile.supports(&MessageSet::SignOn));
+        assert!(!profile.supports(&MessageSet::CreditCard));
+        assert_eq!(profile.max_version(&MessageSet::Bank), Some(2));
+        assert_eq!(profile.fi_name.as_deref(), Some("Example Bank"));
+        assert_eq!(profile.updated.as_deref(), Some("20250101"));
+    }
+}
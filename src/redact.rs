@@ -0,0 +1,166 @@
+//! Masking identifying values so documents can be logged safely.
+
+use crate::{
+    Ofx,
+    body::{
+        AccountId, BankTransactionList, Transaction,
+        investment::{InvestmentAccount, InvestmentTransaction, TransactionInfo},
+    },
+};
+
+/// What masked values are replaced with.
+pub const REDACTED: &str = "[redacted]";
+
+fn mask(value: &mut String) {
+    if !value.is_empty() {
+        REDACTED.clone_into(value);
+    }
+}
+
+fn mask_transaction(transaction: &mut Transaction) {
+    mask(&mut transaction.id);
+    transaction.name.iter_mut().for_each(mask);
+    transaction.memo.iter_mut().for_each(mask);
+    // Unmodeled tags like EXTDNAME or PAYEE may identify people too
+    transaction.extra.values_mut().for_each(mask);
+}
+
+fn mask_transactions(list: &mut BankTransactionList) {
+    list.transactions.iter_mut().for_each(mask_transaction);
+}
+
+fn mask_investment_account(account: &mut InvestmentAccount) {
+    mask(&mut account.broker_id);
+    account.id = AccountId::new(REDACTED);
+}
+
+fn mask_investment_transaction(transaction: &mut InvestmentTransaction) {
+    use InvestmentTransaction as Tx;
+
+    let info: &mut TransactionInfo = match transaction {
+        Tx::BankTransaction(bank) => return mask_transaction(&mut bank.transaction),
+        Tx::BuyDebt(t) => &mut t.buy.info,
+        Tx::BuyMutualFund(t) => &mut t.buy.info,
+        Tx::BuyOption(t) => &mut t.buy.info,
+        Tx::BuyOther(t) => &mut t.buy.info,
+        Tx::BuyStock(t) => &mut t.buy.info,
+        Tx::SellDebt(t) => &mut t.sell.info,
+        Tx::SellMutualFund(t) => &mut t.sell.info,
+        Tx::SellOption(t) => &mut t.sell.info,
+        Tx::SellOther(t) => &mut t.sell.info,
+        Tx::SellStock(t) => &mut t.sell.info,
+        Tx::ClosureOption(t) => &mut t.info,
+        Tx::Income(t) => &mut t.info,
+        Tx::JournalFund(t) => &mut t.info,
+        Tx::JournalSecurity(t) => &mut t.info,
+        Tx::MarginInterest(t) => &mut t.info,
+        Tx::Reinvest(t) => &mut t.info,
+        Tx::ReturnOfCapital(t) => &mut t.info,
+        Tx::Split(t) => &mut t.info,
+        Tx::Transfer(t) => {
+            t.account.iter_mut().for_each(mask_investment_account);
+            &mut t.info
+        }
+    };
+    mask(&mut info.id);
+    info.server_id.iter_mut().for_each(mask);
+    info.reversal_id.iter_mut().for_each(mask);
+    info.memo.iter_mut().for_each(mask);
+}
+
+impl Ofx {
+    /// Returns a copy with account IDs, bank and broker IDs, FITIDs, names, and memos masked,
+    /// suitable for
+    /// `Debug` output in logs and error reports.
+    ///
+    /// Amounts, dates, and transaction types are kept so the redacted copy is still useful for
+    /// diagnosing parse problems. Unmodeled message sets are masked whole, and the element tree
+    /// behind [`Ofx::tree`] is dropped.
+    #[must_use]
+    pub fn redacted(&self) -> Self {
+        let mut ofx = self.clone();
+        ofx.tree = None;
+        ofx.body.other.values_mut().for_each(mask);
+        if let Some(credit_card) = &mut ofx.body.credit_card
+            && let Some(statement) = &mut credit_card.transaction_response.statement
+        {
+            statement.account.id = AccountId::new(REDACTED);
+            if let Some(list) = &mut statement.bank_transactions {
+                mask_transactions(list);
+            }
+        }
+        if let Some(bank) = &mut ofx.body.bank
+            && let Some(statement) = &mut bank.transaction_response.statement
+        {
+            if let Some(account) = &mut statement.account {
+                mask(&mut account.bank_id);
+                account.id = AccountId::new(REDACTED);
+            }
+            if let Some(list) = &mut statement.bank_transactions {
+                mask_transactions(list);
+            }
+        }
+        if let Some(investment) = &mut ofx.body.investment
+            && let Some(statement) = &mut investment.transaction_response.statement
+        {
+            mask_investment_account(&mut statement.account);
+            if let Some(list) = &mut statement.transactions {
+                list.transactions
+                    .iter_mut()
+                    .for_each(mask_investment_transaction);
+            }
+        }
+        ofx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_identifying_values() {
+        let ofx = crate::document(
+            "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>USD\
+            <BANKACCTFROM><BANKID>121000248<ACCTID>1234567890<ACCTTYPE>CHECKING</BANKACCTFROM>\
+            <BANKTRANLIST><STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5\
+            <FITID>20250901-1<NAME>Jane Doe</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1>\
+            <BILLPAYMSGSRSV1><PMTINQTRNRS><TRNUID>2<STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <PMTINQRS><SRVRTID>99887766</PMTINQRS></PMTINQTRNRS></BILLPAYMSGSRSV1>\
+            <INVSTMTMSGSRSV1><INVSTMTTRNRS><TRNUID>3<STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <INVSTMTRS><DTASOF>20250930<CURDEF>USD\
+            <INVACCTFROM><BROKERID>broker.example.com<ACCTID>X98765432</INVACCTFROM>\
+            <INVTRANLIST><DTSTART>20250901<DTEND>20250930\
+            <BUYSTOCK><INVBUY><INVTRAN><FITID>B-20250902-7<DTTRADE>20250902\
+            <MEMO>Gift for John Roe</INVTRAN>\
+            <SECID><UNIQUEID>037833100<UNIQUEIDTYPE>CUSIP</SECID><UNITS>100<UNITPRICE>150\
+            <TOTAL>-15000<SUBACCTSEC>CASH<SUBACCTFUND>CASH</INVBUY><BUYTYPE>BUY</BUYSTOCK>\
+            <INVBANKTRAN><STMTTRN><TRNTYPE>DEP<DTPOSTED>20250930<TRNAMT>500\
+            <FITID>D-20250930-2<NAME>Richard Miles<MEMO>Transfer from savings 4455</STMTTRN>\
+            <SUBACCTFUND>CASH</INVBANKTRAN></INVTRANLIST></INVSTMTRS>\
+            </INVSTMTTRNRS></INVSTMTMSGSRSV1></OFX>",
+        );
+        assert!(ofx.body.investment.is_some());
+        let debug = format!("{:?}", ofx.redacted());
+        for secret in [
+            "121000248",
+            "1234567890",
+            "20250901-1",
+            "Jane Doe",
+            "99887766",
+            "broker.example.com",
+            "X98765432",
+            "B-20250902-7",
+            "John Roe",
+            "D-20250930-2",
+            "Richard Miles",
+            "savings 4455",
+        ] {
+            assert!(!debug.contains(secret), "{secret} leaked");
+        }
+        assert!(debug.contains(REDACTED));
+    }
+}
@@ -0,0 +1,1030 @@
+//! Building the requests a client sends to an OFX server.
+
+use std::{fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Result,
+    body::{
+        Account, BankAccount, FinancialInstitution, Language, SignOnResponse,
+        investment::InvestmentAccount,
+    },
+    credentials::Credentials,
+    datetime::{self, DateTime},
+    error::ErrorKind,
+    header::{Header, HeaderBuilder, Version},
+    tree::Element,
+};
+
+/// A stable identifier for this client installation, sent as `<CLIENTUID>`.
+///
+/// Many institutions now require the same value on every sign-on and ask the user to authorize
+/// each new one, so it should be generated once and persisted, e.g. with
+/// [`ClientUid::load_or_create`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ClientUid(String);
+
+impl ClientUid {
+    /// Wraps an existing identifier.
+    #[must_use]
+    pub fn new(uid: impl Into<String>) -> Self {
+        Self(uid.into())
+    }
+
+    /// Generates a new random (UUID v4) identifier.
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Reads the identifier stored at `path`, generating and saving a new one if the file does
+    /// not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read, or cannot be created.
+    pub fn load_or_create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(uid) if !uid.trim().is_empty() => Ok(Self(uid.trim().to_owned())),
+            Ok(_) => Self::create(path),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Self::create(path),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn create(path: &Path) -> Result<Self> {
+        let uid = Self::generate();
+        fs::write(path, &uid.0)?;
+        Ok(uid)
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ClientUid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// An `APPID`/`APPVER` pair identifying the client software to the server.
+///
+/// Institutions often only accept requests from applications they have whitelisted, so clients
+/// commonly identify as a version of Quicken.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AppPreset {
+    /// The name to select this preset by, e.g. `quicken-2700`
+    pub name: &'static str,
+    pub app_id: &'static str,
+    pub app_version: &'static str,
+}
+
+/// `APPID`/`APPVER` pairs widely accepted by institutions.
+pub const APP_PRESETS: &[AppPreset] = &[
+    AppPreset::new("quicken-2300", "QWIN", "2300"),
+    AppPreset::new("quicken-2500", "QWIN", "2500"),
+    AppPreset::new("quicken-2700", "QWIN", "2700"),
+    AppPreset::new("quicken-2800", "QWIN", "2800"),
+    AppPreset::new("quicken-2900", "QWIN", "2900"),
+    AppPreset::new("money-1700", "Money", "1700"),
+];
+
+impl AppPreset {
+    #[must_use]
+    pub const fn new(name: &'static str, app_id: &'static str, app_version: &'static str) -> Self {
+        Self {
+            name,
+            app_id,
+            app_version,
+        }
+    }
+
+    /// Finds a preset in [`APP_PRESETS`] by name.
+    #[must_use]
+    pub fn by_name(name: &str) -> Option<Self> {
+        APP_PRESETS
+            .iter()
+            .copied()
+            .find(|preset| preset.name == name)
+    }
+}
+
+/// The signon request (`<SONRQ>`) that begins every request document.
+///
+/// The `Debug` output never includes the password.
+#[derive(Clone, Debug)]
+pub struct SignOnRequest {
+    pub date_client: DateTime,
+    pub credentials: Credentials,
+    pub language: Language,
+    pub financial_institution: Option<FinancialInstitution>,
+    /// The `SESSCOOKIE` from the previous `SONRS`, if the server sent one
+    pub session_cookie: Option<String>,
+    pub app_id: String,
+    pub app_version: String,
+    pub client_uid: Option<ClientUid>,
+}
+
+impl SignOnRequest {
+    #[must_use]
+    pub fn new(credentials: Credentials, date_client: DateTime) -> Self {
+        Self {
+            date_client,
+            credentials,
+            language: Language::default(),
+            financial_institution: None,
+            session_cookie: None,
+            app_id: "OFXY".into(),
+            app_version: "0100".into(),
+            client_uid: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_financial_institution(
+        mut self,
+        financial_institution: FinancialInstitution,
+    ) -> Self {
+        self.financial_institution = Some(financial_institution);
+        self
+    }
+
+    /// Echoes the session cookie from a previous signon response, as servers that send one
+    /// expect.
+    #[must_use]
+    pub fn continuing(mut self, response: &SignOnResponse) -> Self {
+        self.session_cookie.clone_from(&response.session_cookie);
+        self
+    }
+
+    #[must_use]
+    pub fn with_app(mut self, app_id: impl Into<String>, app_version: impl Into<String>) -> Self {
+        self.app_id = app_id.into();
+        self.app_version = app_version.into();
+        self
+    }
+
+    #[must_use]
+    pub fn with_app_preset(self, preset: AppPreset) -> Self {
+        self.with_app(preset.app_id, preset.app_version)
+    }
+
+    #[must_use]
+    pub fn with_client_uid(mut self, client_uid: ClientUid) -> Self {
+        self.client_uid = Some(client_uid);
+        self
+    }
+
+    pub(crate) fn to_element(&self) -> Element {
+        let mut children = vec![
+            Element::leaf("DTCLIENT", datetime::format(&self.date_client)),
+            Element::leaf("USERID", &self.credentials.user_id),
+            Element::leaf("USERPASS", self.credentials.password()),
+            Element::leaf("LANGUAGE", self.language.code()),
+        ];
+        if let Some(fi) = &self.financial_institution {
+            let mut fi_children = vec![Element::leaf("ORG", &fi.organization)];
+            fi_children.extend(fi.id.as_ref().map(|id| Element::leaf("FID", id)));
+            children.push(Element::aggregate("FI", fi_children));
+        }
+        children.extend(
+            self.session_cookie
+                .as_ref()
+                .map(|cookie| Element::leaf("SESSCOOKIE", cookie)),
+        );
+        children.push(Element::leaf("APPID", &self.app_id));
+        children.push(Element::leaf("APPVER", &self.app_version));
+        children.extend(
+            self.client_uid
+                .as_ref()
+                .map(|uid| Element::leaf("CLIENTUID", uid.as_str())),
+        );
+        Element::aggregate("SONRQ", children)
+    }
+
+    /// Renders the `<SONRQ>` aggregate as SGML.
+    #[must_use]
+    pub fn to_sgml(&self) -> String {
+        self.to_element().to_sgml()
+    }
+}
+
+fn flag(value: bool) -> &'static str {
+    if value { "Y" } else { "N" }
+}
+
+/// Which transactions a statement request asks for (`<INCTRAN>`).
+///
+/// Without a start or end date the server chooses the window, usually everything it has.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IncludeTransactions {
+    pub start: Option<DateTime>,
+    pub end: Option<DateTime>,
+    /// Whether to include transactions at all; `false` asks for balances only
+    pub include: bool,
+}
+
+impl IncludeTransactions {
+    /// All the transactions the server is willing to send.
+    #[must_use]
+    pub fn all() -> Self {
+        Self {
+            include: true,
+            ..Self::default()
+        }
+    }
+
+    /// Transactions posted on or after `start`.
+    #[must_use]
+    pub fn since(start: DateTime) -> Self {
+        Self {
+            start: Some(start),
+            ..Self::all()
+        }
+    }
+
+    /// Transactions posted on or after `start` and before `end`.
+    #[must_use]
+    pub fn between(start: DateTime, end: DateTime) -> Self {
+        Self {
+            start: Some(start),
+            end: Some(end),
+            include: true,
+        }
+    }
+
+    /// No transactions, only the statement's balances.
+    #[must_use]
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    fn to_element(&self) -> Element {
+        let mut children = Vec::new();
+        children.extend(
+            self.start
+                .as_ref()
+                .map(|start| Element::leaf("DTSTART", datetime::format(start))),
+        );
+        children.extend(
+            self.end
+                .as_ref()
+                .map(|end| Element::leaf("DTEND", datetime::format(end))),
+        );
+        children.push(Element::leaf("INCLUDE", flag(self.include)));
+        Element::aggregate("INCTRAN", children)
+    }
+}
+
+/// A bank statement request (`<STMTRQ>`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementRequest {
+    pub account: BankAccount,
+    pub transactions: Option<IncludeTransactions>,
+    /// Whether to include pending transactions (`<INCLUDEPENDING>`)
+    pub include_pending: bool,
+}
+
+impl StatementRequest {
+    #[must_use]
+    pub fn new(account: BankAccount) -> Self {
+        Self {
+            account,
+            transactions: Some(IncludeTransactions::all()),
+            include_pending: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_transactions(mut self, transactions: IncludeTransactions) -> Self {
+        self.transactions = Some(transactions);
+        self
+    }
+
+    #[must_use]
+    pub fn with_pending(mut self, include_pending: bool) -> Self {
+        self.include_pending = include_pending;
+        self
+    }
+
+    pub(crate) fn to_element(&self) -> Element {
+        let account = Element::aggregate(
+            "BANKACCTFROM",
+            vec![
+                Element::leaf("BANKID", &self.account.bank_id),
+                Element::leaf("ACCTID", self.account.id.expose()),
+                Element::leaf("ACCTTYPE", self.account.account_type.code()),
+            ],
+        );
+        let mut children = vec![account];
+        children.extend(
+            self.transactions
+                .as_ref()
+                .map(IncludeTransactions::to_element),
+        );
+        if self.include_pending {
+            children.push(Element::leaf("INCLUDEPENDING", "Y"));
+        }
+        Element::aggregate("STMTRQ", children)
+    }
+
+    /// Renders the `<STMTRQ>` aggregate as SGML.
+    #[must_use]
+    pub fn to_sgml(&self) -> String {
+        self.to_element().to_sgml()
+    }
+}
+
+/// A credit card statement request (`<CCSTMTRQ>`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreditCardStatementRequest {
+    pub account: Account,
+    pub transactions: Option<IncludeTransactions>,
+    /// Whether to include pending transactions (`<INCLUDEPENDING>`)
+    pub include_pending: bool,
+}
+
+impl CreditCardStatementRequest {
+    #[must_use]
+    pub fn new(account: Account) -> Self {
+        Self {
+            account,
+            transactions: Some(IncludeTransactions::all()),
+            include_pending: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_transactions(mut self, transactions: IncludeTransactions) -> Self {
+        self.transactions = Some(transactions);
+        self
+    }
+
+    #[must_use]
+    pub fn with_pending(mut self, include_pending: bool) -> Self {
+        self.include_pending = include_pending;
+        self
+    }
+
+    pub(crate) fn to_element(&self) -> Element {
+        let account = Element::aggregate(
+            "CCACCTFROM",
+            vec![Element::leaf("ACCTID", self.account.id.expose())],
+        );
+        let mut children = vec![account];
+        children.extend(
+            self.transactions
+                .as_ref()
+                .map(IncludeTransactions::to_element),
+        );
+        if self.include_pending {
+            children.push(Element::leaf("INCLUDEPENDING", "Y"));
+        }
+        Element::aggregate("CCSTMTRQ", children)
+    }
+
+    /// Renders the `<CCSTMTRQ>` aggregate as SGML.
+    #[must_use]
+    pub fn to_sgml(&self) -> String {
+        self.to_element().to_sgml()
+    }
+}
+
+/// An investment statement request (`<INVSTMTRQ>`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvestmentStatementRequest {
+    pub account: InvestmentAccount,
+    pub transactions: Option<IncludeTransactions>,
+    /// Whether to include open orders (`<INCOO>`)
+    pub include_open_orders: bool,
+    /// Whether to include positions (`<INCPOS>`)
+    pub include_positions: bool,
+    /// The date to report positions as of, instead of the server's latest
+    pub positions_as_of: Option<DateTime>,
+    /// Whether to include balances (`<INCBAL>`)
+    pub include_balance: bool,
+}
+
+impl InvestmentStatementRequest {
+    /// Requests all transactions, positions, and balances, but not open orders.
+    #[must_use]
+    pub fn new(account: InvestmentAccount) -> Self {
+        Self {
+            account,
+            transactions: Some(IncludeTransactions::all()),
+            include_open_orders: false,
+            include_positions: true,
+            positions_as_of: None,
+            include_balance: true,
+        }
+    }
+
+    #[must_use]
+    pub fn with_transactions(mut self, transactions: IncludeTransactions) -> Self {
+        self.transactions = Some(transactions);
+        self
+    }
+
+    #[must_use]
+    pub fn with_open_orders(mut self, include_open_orders: bool) -> Self {
+        self.include_open_orders = include_open_orders;
+        self
+    }
+
+    #[must_use]
+    pub fn with_positions(mut self, include_positions: bool) -> Self {
+        self.include_positions = include_positions;
+        self
+    }
+
+    #[must_use]
+    pub fn with_positions_as_of(mut self, date: DateTime) -> Self {
+        self.include_positions = true;
+        self.positions_as_of = Some(date);
+        self
+    }
+
+    #[must_use]
+    pub fn with_balance(mut self, include_balance: bool) -> Self {
+        self.include_balance = include_balance;
+        self
+    }
+
+    pub(crate) fn to_element(&self) -> Element {
+        let account = Element::aggregate(
+            "INVACCTFROM",
+            vec![
+                Element::leaf("BROKERID", &self.account.broker_id),
+                Element::leaf("ACCTID", self.account.id.expose()),
+            ],
+        );
+        let mut positions = Vec::new();
+        positions.extend(
+            self.positions_as_of
+                .as_ref()
+                .map(|date| Element::leaf("DTASOF", datetime::format(date))),
+        );
+        positions.push(Element::leaf("INCLUDE", flag(self.include_positions)));
+
+        let mut children = vec![account];
+        children.extend(
+            self.transactions
+                .as_ref()
+                .map(IncludeTransactions::to_element),
+        );
+        children.push(Element::leaf("INCOO", flag(self.include_open_orders)));
+        children.push(Element::aggregate("INCPOS", positions));
+        children.push(Element::leaf("INCBAL", flag(self.include_balance)));
+        Element::aggregate("INVSTMTRQ", children)
+    }
+
+    /// Renders the `<INVSTMTRQ>` aggregate as SGML.
+    #[must_use]
+    pub fn to_sgml(&self) -> String {
+        self.to_element().to_sgml()
+    }
+}
+
+/// How the client routes message sets to servers, sent as `<CLIENTROUTING>`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ClientRouting {
+    /// Sends every message set to the same URL (`NONE`)
+    #[default]
+    None,
+    /// Routes by service provider (`SERVICE`)
+    Service,
+    /// Sends each message set to the URL its profile gives (`MSGSET`)
+    MessageSet,
+}
+
+impl ClientRouting {
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::None => "NONE",
+            Self::Service => "SERVICE",
+            Self::MessageSet => "MSGSET",
+        }
+    }
+}
+
+/// A profile request (`<PROFRQ>`), asking which message sets and versions the server supports,
+/// as answered by a [`crate::profile::Profile`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileRequest {
+    pub client_routing: ClientRouting,
+    /// The `DTPROFUP` of the last profile received, as kept in
+    /// [`crate::profile::Profile::updated`], so the server can answer that nothing changed
+    pub updated: Option<String>,
+}
+
+impl ProfileRequest {
+    /// Requests the whole profile, as on first connect.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_client_routing(mut self, client_routing: ClientRouting) -> Self {
+        self.client_routing = client_routing;
+        self
+    }
+
+    #[must_use]
+    pub fn with_updated(mut self, updated: impl Into<String>) -> Self {
+        self.updated = Some(updated.into());
+        self
+    }
+
+    pub(crate) fn to_element(&self) -> Element {
+        Element::aggregate(
+            "PROFRQ",
+            vec![
+                Element::leaf("CLIENTROUTING", self.client_routing.code()),
+                // A date before any profile, so the server sends the whole one
+                Element::leaf("DTPROFUP", self.updated.as_deref().unwrap_or("19900101")),
+            ],
+        )
+    }
+
+    /// Renders the `<PROFRQ>` aggregate as SGML.
+    #[must_use]
+    pub fn to_sgml(&self) -> String {
+        self.to_element().to_sgml()
+    }
+}
+
+/// A `<TRNUID>` pairing a request with its response.
+///
+/// The spec requires a value unique to each request, up to 36 characters;
+/// [`TransactionUid::generate`] creates a UUID, which is what servers expect.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TransactionUid(String);
+
+impl TransactionUid {
+    /// Wraps an existing identifier, e.g. to retry a request with the same TRNUID.
+    #[must_use]
+    pub fn new(uid: impl Into<String>) -> Self {
+        Self(uid.into())
+    }
+
+    /// Generates a new random (UUID v4) identifier.
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().to_string())
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for TransactionUid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A request that the server answers in its own transaction.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Request {
+    Statement(StatementRequest),
+    CreditCardStatement(CreditCardStatementRequest),
+    InvestmentStatement(InvestmentStatementRequest),
+    Profile(ProfileRequest),
+}
+
+impl Request {
+    /// The tag of the message set this request is sent in, e.g. `BANKMSGSRQV1`.
+    #[must_use]
+    pub fn message_set(&self) -> &'static str {
+        match self {
+            Self::Statement(_) => "BANKMSGSRQV1",
+            Self::CreditCardStatement(_) => "CREDITCARDMSGSRQV1",
+            Self::InvestmentStatement(_) => "INVSTMTMSGSRQV1",
+            Self::Profile(_) => "PROFMSGSRQV1",
+        }
+    }
+
+    /// The tag of the transaction aggregate wrapping this request, e.g. `STMTTRNRQ`.
+    #[must_use]
+    pub fn wrapper(&self) -> &'static str {
+        match self {
+            Self::Statement(_) => "STMTTRNRQ",
+            Self::CreditCardStatement(_) => "CCSTMTTRNRQ",
+            Self::InvestmentStatement(_) => "INVSTMTTRNRQ",
+            Self::Profile(_) => "PROFTRNRQ",
+        }
+    }
+
+    fn to_element(&self) -> Element {
+        match self {
+            Self::Statement(request) => request.to_element(),
+            Self::CreditCardStatement(request) => request.to_element(),
+            Self::InvestmentStatement(request) => request.to_element(),
+            Self::Profile(request) => request.to_element(),
+        }
+    }
+}
+
+impl From<StatementRequest> for Request {
+    fn from(request: StatementRequest) -> Self {
+        Self::Statement(request)
+    }
+}
+
+impl From<CreditCardStatementRequest> for Request {
+    fn from(request: CreditCardStatementRequest) -> Self {
+        Self::CreditCardStatement(request)
+    }
+}
+
+impl From<InvestmentStatementRequest> for Request {
+    fn from(request: InvestmentStatementRequest) -> Self {
+        Self::InvestmentStatement(request)
+    }
+}
+
+impl From<ProfileRequest> for Request {
+    fn from(request: ProfileRequest) -> Self {
+        Self::Profile(request)
+    }
+}
+
+/// A [`Request`] in its transaction aggregate, e.g. `<STMTTRNRQ>`, with its `<TRNUID>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactionRequest {
+    pub uid: TransactionUid,
+    pub request: Request,
+}
+
+impl TransactionRequest {
+    /// Wraps `request` with a newly generated TRNUID.
+    #[must_use]
+    pub fn new(request: impl Into<Request>) -> Self {
+        Self {
+            uid: TransactionUid::generate(),
+            request: request.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_uid(mut self, uid: TransactionUid) -> Self {
+        self.uid = uid;
+        self
+    }
+
+    /// Checks that a response's TRNUID echoes this request's, as the spec requires.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::TransactionMismatch`] if the two differ.
+    pub fn check_response(&self, transaction_id: &str) -> Result<()> {
+        if transaction_id.trim() == self.uid.as_str() {
+            return Ok(());
+        }
+        Err(ErrorKind::TransactionMismatch {
+            expected: self.uid.0.clone(),
+            found: transaction_id.to_owned(),
+        }
+        .into())
+    }
+
+    pub(crate) fn to_element(&self) -> Element {
+        Element::aggregate(
+            self.request.wrapper(),
+            vec![
+                Element::leaf("TRNUID", self.uid.as_str()),
+                self.request.to_element(),
+            ],
+        )
+    }
+
+    /// Renders the transaction aggregate as SGML.
+    #[must_use]
+    pub fn to_sgml(&self) -> String {
+        self.to_element().to_sgml()
+    }
+}
+
+/// A complete request document: the header, the signon, and any number of transactions.
+///
+/// Transactions are grouped into their message sets, so a single document can download every
+/// account at once, as Quicken does.
+#[derive(Clone, Debug)]
+pub struct OfxRequest {
+    pub header: Header,
+    pub sign_on: SignOnRequest,
+    pub transactions: Vec<TransactionRequest>,
+}
+
+impl OfxRequest {
+    /// Starts a version 160 request whose header comes from [`HeaderBuilder`], with a newly
+    /// generated `NEWFILEUID`.
+    #[must_use]
+    pub fn new(sign_on: SignOnRequest) -> Self {
+        Self {
+            header: HeaderBuilder::new(Version::V160).build(),
+            sign_on,
+            transactions: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Adds a request, wrapped in a transaction with a newly generated TRNUID.
+    #[must_use]
+    pub fn request(self, request: impl Into<Request>) -> Self {
+        self.transaction(TransactionRequest::new(request))
+    }
+
+    #[must_use]
+    pub fn transaction(mut self, transaction: TransactionRequest) -> Self {
+        self.transactions.push(transaction);
+        self
+    }
+
+    /// Renders the whole document, headers included, ready to POST.
+    #[must_use]
+    pub fn to_sgml(&self) -> String {
+        let mut children = vec![Element::aggregate(
+            "SIGNONMSGSRQV1",
+            vec![self.sign_on.to_element()],
+        )];
+        // Message sets in the order the spec lists them
+        for message_set in [
+            "BANKMSGSRQV1",
+            "CREDITCARDMSGSRQV1",
+            "INVSTMTMSGSRQV1",
+            "PROFMSGSRQV1",
+        ] {
+            let transactions: Vec<_> = self
+                .transactions
+                .iter()
+                .filter(|transaction| transaction.request.message_set() == message_set)
+                .map(TransactionRequest::to_element)
+                .collect();
+            if !transactions.is_empty() {
+                children.push(Element::aggregate(message_set, transactions));
+            }
+        }
+        format!(
+            "{}\r\n{}",
+            self.header,
+            Element::aggregate("OFX", children).to_sgml()
+        )
+    }
+}
+
+/// Tracks `OLDFILEUID`/`NEWFILEUID` across successive downloads, for the file-based error
+/// recovery in section 2.2 of the spec.
+///
+/// Each request names the last file received in `OLDFILEUID` and a fresh UID in `NEWFILEUID`, and
+/// the server echoes both. The chain implements `Serialize` and `Deserialize` so it can be
+/// persisted between runs.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FileUidChain {
+    last: Option<String>,
+    pending: Option<String>,
+}
+
+impl FileUidChain {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Continues a chain whose last received file had the UID `last`.
+    #[must_use]
+    pub fn resume(last: impl Into<String>) -> Self {
+        Self {
+            last: Some(last.into()),
+            pending: None,
+        }
+    }
+
+    /// The `NEWFILEUID` of the last file received, if any.
+    #[must_use]
+    pub fn last(&self) -> Option<&str> {
+        self.last.as_deref()
+    }
+
+    /// Sets the file UIDs of a request header, generating a new `NEWFILEUID`.
+    ///
+    /// Preparing again before [`FileUidChain::accept`] replaces the pending UID, e.g. when
+    /// retrying a request that got no response.
+    pub fn prepare(&mut self, header: &mut Header) {
+        let uid = uuid::Uuid::new_v4().to_string();
+        header.oldfileuid = self.last.clone().unwrap_or_else(|| "NONE".into());
+        header.newfileuid.clone_from(&uid);
+        self.pending = Some(uid);
+    }
+
+    /// Records a response to the last prepared request, advancing the chain.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::FileUidGap`], leaving the chain unchanged, if the response's
+    /// `OLDFILEUID` is not the last file received or its `NEWFILEUID` is not the pending one.
+    pub fn accept(&mut self, response: &Header) -> Result<()> {
+        let gap = |expected: &str, found: &str| ErrorKind::FileUidGap {
+            expected: expected.to_owned(),
+            found: found.to_owned(),
+        };
+        let last = self.last.as_deref().unwrap_or("NONE");
+        if response.oldfileuid != last {
+            return Err(gap(last, &response.oldfileuid).into());
+        }
+        let pending = self.pending.as_deref().unwrap_or("NONE");
+        if response.newfileuid != pending {
+            return Err(gap(pending, &response.newfileuid).into());
+        }
+        self.last = self.pending.take();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_client_uid() {
+        let uid = ClientUid::generate();
+        assert_eq!(uid.as_str().len(), 36);
+        let request = SignOnRequest::new(
+            Credentials::new("jdoe", "hunter2"),
+            datetime::parse("20250901").unwrap(),
+        )
+        .with_client_uid(uid.clone());
+        let sgml = request.to_sgml();
+        assert!(sgml.starts_with("<SONRQ><DTCLIENT>20250901000000.000[0:GMT]</DTCLIENT>"));
+        assert!(sgml.ends_with(&format!("<CLIENTUID>{uid}</CLIENTUID></SONRQ>")));
+    }
+
+    #[test]
+    fn applies_app_presets() {
+        let preset = AppPreset::by_name("quicken-2700").unwrap();
+        let sgml = SignOnRequest::new(
+            Credentials::new("jdoe", "hunter2"),
+            datetime::parse("20250901").unwrap(),
+        )
+        .with_app_preset(preset)
+        .to_sgml();
+        assert!(sgml.contains("<APPID>QWIN</APPID><APPVER>2700</APPVER>"));
+        assert_eq!(AppPreset::by_name("unknown"), None);
+    }
+
+    #[test]
+    fn echoes_session_cookie() {
+        let date = || datetime::parse("20250901").unwrap();
+        let mut response = SignOnResponse::new(crate::body::Status::default(), date());
+        response.session_cookie = Some("cookie123".into());
+        let sgml = SignOnRequest::new(Credentials::new("jdoe", "hunter2"), date())
+            .continuing(&response)
+            .to_sgml();
+        assert!(sgml.contains("<SESSCOOKIE>cookie123</SESSCOOKIE><APPID>"));
+    }
+
+    #[test]
+    fn persists_client_uid() {
+        let path = std::env::temp_dir().join(format!("ofxy-clientuid-{}", ClientUid::generate()));
+        let created = ClientUid::load_or_create(&path).unwrap();
+        assert_eq!(ClientUid::load_or_create(&path).unwrap(), created);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn renders_statement_requests() {
+        let date = |s| datetime::parse(s).unwrap();
+        let bank = StatementRequest::new(BankAccount::new(
+            "121000248",
+            "1234",
+            crate::body::AccountType::Checking,
+        ))
+        .with_transactions(IncludeTransactions::between(
+            date("20250901"),
+            date("20251001"),
+        ))
+        .with_pending(true)
+        .to_sgml();
+        assert_eq!(
+            bank,
+            "<STMTRQ><BANKACCTFROM><BANKID>121000248</BANKID><ACCTID>1234</ACCTID>\
+             <ACCTTYPE>CHECKING</ACCTTYPE></BANKACCTFROM><INCTRAN>\
+             <DTSTART>20250901000000.000[0:GMT]</DTSTART><DTEND>20251001000000.000[0:GMT]</DTEND>\
+             <INCLUDE>Y</INCLUDE></INCTRAN><INCLUDEPENDING>Y</INCLUDEPENDING></STMTRQ>"
+        );
+
+        let card = CreditCardStatementRequest::new(Account::new("4111"))
+            .with_transactions(IncludeTransactions::none())
+            .to_sgml();
+        assert!(card.ends_with("<INCTRAN><INCLUDE>N</INCLUDE></INCTRAN></CCSTMTRQ>"));
+
+        let investment =
+            InvestmentStatementRequest::new(InvestmentAccount::new("example.com", "5678"))
+                .with_transactions(IncludeTransactions::since(date("20250901")))
+                .with_open_orders(true)
+                .with_balance(false)
+                .to_sgml();
+        assert!(investment.ends_with(
+            "<INCLUDE>Y</INCLUDE></INCTRAN><INCOO>Y</INCOO>\
+             <INCPOS><INCLUDE>Y</INCLUDE></INCPOS><INCBAL>N</INCBAL></INVSTMTRQ>"
+        ));
+
+        assert_eq!(
+            ProfileRequest::new().to_sgml(),
+            "<PROFRQ><CLIENTROUTING>NONE</CLIENTROUTING><DTPROFUP>19900101</DTPROFUP></PROFRQ>"
+        );
+        let profile = ProfileRequest::new()
+            .with_client_routing(ClientRouting::MessageSet)
+            .with_updated("20250901120000")
+            .to_sgml();
+        assert!(profile.contains("<CLIENTROUTING>MSGSET</CLIENTROUTING>"));
+        assert!(profile.contains("<DTPROFUP>20250901120000</DTPROFUP>"));
+    }
+
+    #[test]
+    fn wraps_requests_with_transaction_uid() {
+        let request =
+            TransactionRequest::new(CreditCardStatementRequest::new(Account::new("4111")));
+        assert_eq!(request.uid.as_str().len(), 36);
+        assert_ne!(request.uid, TransactionUid::generate());
+
+        let request = request.with_uid(TransactionUid::new("abc"));
+        assert!(
+            request
+                .to_sgml()
+                .starts_with("<CCSTMTTRNRQ><TRNUID>abc</TRNUID><CCSTMTRQ>")
+        );
+        assert!(request.check_response("abc").is_ok());
+        assert!(matches!(
+            request.check_response("abd").unwrap_err().kind(),
+            ErrorKind::TransactionMismatch { found, .. } if found == "abd"
+        ));
+    }
+
+    #[test]
+    fn chains_file_uids() {
+        let mut chain = FileUidChain::new();
+        let mut first = Header::default();
+        chain.prepare(&mut first);
+        assert_eq!(first.oldfileuid, "NONE");
+        assert_eq!(first.newfileuid.len(), 36);
+        chain.accept(&first).unwrap();
+        assert_eq!(chain.last(), Some(first.newfileuid.as_str()));
+
+        let mut second = Header::default();
+        chain.prepare(&mut second);
+        assert_eq!(second.oldfileuid, first.newfileuid);
+        // A response that skips a file is rejected without advancing the chain
+        let skipped = Header {
+            oldfileuid: "elsewhere".into(),
+            ..second.clone()
+        };
+        assert!(matches!(
+            chain.accept(&skipped).unwrap_err().kind(),
+            ErrorKind::FileUidGap { found, .. } if found == "elsewhere"
+        ));
+        assert_eq!(chain.last(), Some(first.newfileuid.as_str()));
+        chain.accept(&second).unwrap();
+        assert_eq!(chain.last(), Some(second.newfileuid.as_str()));
+    }
+
+    #[test]
+    fn batches_transactions_by_message_set() {
+        let date = datetime::parse("20250901").unwrap();
+        let sign_on = SignOnRequest::new(Credentials::new("jdoe", "hunter2"), date);
+        let checking = BankAccount::new("121000248", "1", crate::body::AccountType::Checking);
+        let savings = BankAccount::new("121000248", "2", crate::body::AccountType::Savings);
+        let document = OfxRequest::new(sign_on)
+            .request(CreditCardStatementRequest::new(Account::new("4111")))
+            .request(StatementRequest::new(checking))
+            .request(StatementRequest::new(savings))
+            .request(ProfileRequest::new())
+            .to_sgml();
+
+        let (header, body) = document.split_once("\r\n\r\n").unwrap();
+        assert!(header.starts_with("OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:160"));
+        assert!(body.starts_with("<OFX><SIGNONMSGSRQV1><SONRQ>"));
+        let bank = body.find("<BANKMSGSRQV1><STMTTRNRQ>").unwrap();
+        let card = body.find("<CREDITCARDMSGSRQV1><CCSTMTTRNRQ>").unwrap();
+        let profile = body.find("<PROFMSGSRQV1><PROFTRNRQ>").unwrap();
+        assert!(bank < card && card < profile);
+        assert_eq!(body.matches("<STMTTRNRQ>").count(), 2);
+        assert_eq!(body.matches("<BANKMSGSRQV1>").count(), 1);
+    }
+}
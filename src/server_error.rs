@@ -0,0 +1,151 @@
+//! Collecting the error statuses a server reported.
+
+use std::fmt;
+
+use crate::{Ofx, body::Status};
+
+/// Common status codes, so callers can react without memorizing the spec's tables.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ServerErrorKind {
+    /// 2000: the server could not process the request
+    GeneralError,
+    /// 2003: the account in the request does not exist
+    AccountNotFound,
+    /// 2019: the TRNUID was already used
+    DuplicateRequest,
+    /// 15000: the user must change their password
+    MustChangePassword,
+    /// 15500: the user ID or password is wrong
+    InvalidCredentials,
+    /// 15501: the account is in use by another session
+    AccountInUse,
+    /// 15502: the password is locked after too many failed attempts
+    PasswordLocked,
+    /// 15510: the `CLIENTUID` is missing or has not been authorized
+    ClientUidError,
+    /// Any other code
+    Other(u32),
+}
+
+impl From<u32> for ServerErrorKind {
+    fn from(code: u32) -> Self {
+        match code {
+            2000 => Self::GeneralError,
+            2003 => Self::AccountNotFound,
+            2019 => Self::DuplicateRequest,
+            15000 => Self::MustChangePassword,
+            15500 => Self::InvalidCredentials,
+            15501 => Self::AccountInUse,
+            15502 => Self::PasswordLocked,
+            15510 => Self::ClientUidError,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// A non-success `<STATUS>` and the wrapper it was found in.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServerError {
+    /// The aggregate holding the status, e.g. `SONRS` or `STMTTRNRS`
+    pub wrapper: &'static str,
+    /// The `TRNUID` of the transaction, for errors outside the signon
+    pub transaction_id: Option<String>,
+    pub code: u32,
+    /// `INFO`, `WARN`, or `ERROR`
+    pub severity: String,
+    pub message: Option<String>,
+}
+
+impl ServerError {
+    fn new(wrapper: &'static str, transaction_id: Option<&str>, status: &Status) -> Self {
+        Self {
+            wrapper,
+            transaction_id: transaction_id.map(str::to_owned),
+            code: status.code,
+            severity: status.severity.clone(),
+            message: status.message.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> ServerErrorKind {
+        self.code.into()
+    }
+
+    /// Whether the server considered this an error rather than a warning or notice.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity.eq_ignore_ascii_case("ERROR")
+    }
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.wrapper, self.severity, self.code)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Ofx {
+    /// Every non-success status in the signon and transaction responses, in document order.
+    #[must_use]
+    pub fn server_errors(&self) -> Vec<ServerError> {
+        let body = &self.body;
+        let mut statuses = Vec::new();
+        if let Some(sign_on) = &body.sign_on {
+            statuses.push(("SONRS", None, &sign_on.response.status));
+        }
+        if let Some(credit_card) = &body.credit_card {
+            let response = &credit_card.transaction_response;
+            let id = Some(response.transaction_id.as_str());
+            statuses.push(("CCSTMTTRNRS", id, &response.status));
+        }
+        if let Some(bank) = &body.bank {
+            let response = &bank.transaction_response;
+            let id = Some(response.transaction_id.as_str());
+            statuses.push(("STMTTRNRS", id, &response.status));
+        }
+        if let Some(investment) = &body.investment {
+            let response = &investment.transaction_response;
+            let id = Some(response.transaction_id.as_str());
+            statuses.push(("INVSTMTTRNRS", id, &response.status));
+        }
+        statuses
+            .into_iter()
+            .filter(|(_, _, status)| !status.is_success())
+            .map(|(wrapper, id, status)| ServerError::new(wrapper, id, status))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_server_errors() {
+        let input = std::fs::read_to_string("tests/files/simple.ofx").unwrap();
+        assert!(input.parse::<Ofx>().unwrap().server_errors().is_empty());
+
+        let start = input.find("<CCSTMTTRNRS>").unwrap();
+        let end = input.find("</CCSTMTTRNRS>").unwrap();
+        let input = format!(
+            "{}<CCSTMTTRNRS><TRNUID>9<STATUS><CODE>2003<SEVERITY>ERROR\
+             <MESSAGE>No such account</STATUS>{}",
+            &input[..start],
+            &input[end..]
+        );
+        let errors = input.parse::<Ofx>().unwrap().server_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), ServerErrorKind::AccountNotFound);
+        assert!(errors[0].is_error());
+        assert_eq!(
+            errors[0].to_string(),
+            "CCSTMTTRNRS ERROR 2003: No such account"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Monthly and per-type breakdowns of bank and credit card transactions, for dashboards and
+//! summaries.
+
+use std::collections::BTreeMap;
+
+use crate::{Decimal, Ofx, body::Transaction, datetime};
+
+/// How many transactions [`Stats::largest`] keeps.
+pub const LARGEST: usize = 5;
+
+/// Money in and out over a period. Both totals are positive.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Flow {
+    pub inflow: Decimal,
+    pub outflow: Decimal,
+}
+
+impl Flow {
+    #[must_use]
+    pub fn net(&self) -> Decimal {
+        self.inflow - self.outflow
+    }
+}
+
+/// The number and signed sum of transactions of one `TRNTYPE`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TypeTotal {
+    pub count: usize,
+    pub sum: Decimal,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats<'a> {
+    /// Keyed by `YYYY-MM` of the posting date.
+    pub months: BTreeMap<String, Flow>,
+    /// Keyed by the `TRNTYPE` code, e.g. `DEBIT`.
+    pub by_type: BTreeMap<&'static str, TypeTotal>,
+    /// Up to [`LARGEST`] transactions with the greatest absolute amounts, largest first.
+    pub largest: Vec<&'a Transaction>,
+}
+
+fn transactions(ofx: &Ofx) -> impl Iterator<Item = &Transaction> {
+    let credit_card = ofx
+        .body
+        .credit_card
+        .iter()
+        .filter_map(|message| message.transaction_response.statement.as_ref())
+        .filter_map(|statement| statement.bank_transactions.as_ref());
+    let bank = ofx
+        .body
+        .bank
+        .iter()
+        .filter_map(|message| message.transaction_response.statement.as_ref())
+        .filter_map(|statement| statement.bank_transactions.as_ref());
+    credit_card
+        .chain(bank)
+        .flat_map(|list| list.transactions.iter())
+}
+
+/// Computes the statistics of every bank and credit card transaction in `ofx`.
+#[must_use]
+pub fn compute(ofx: &Ofx) -> Stats<'_> {
+    let mut stats = Stats::default();
+    for transaction in transactions(ofx) {
+        let amount = transaction.amount;
+
+        let mut month = datetime::iso_date(&transaction.date_posted);
+        month.truncate("YYYY-MM".len());
+        let flow = stats.months.entry(month).or_default();
+        if amount.is_sign_negative() {
+            flow.outflow -= amount;
+        } else {
+            flow.inflow += amount;
+        }
+
+        let total = stats
+            .by_type
+            .entry(transaction.transaction_type.code())
+            .or_default();
+        total.count += 1;
+        total.sum += amount;
+
+        stats.largest.push(transaction);
+    }
+    stats
+        .largest
+        .sort_by_key(|transaction| std::cmp::Reverse(transaction.amount.abs()));
+    stats.largest.truncate(LARGEST);
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaks_down_by_month_and_type() {
+        let input = std::fs::read_to_string("tests/files/simple.ofx").unwrap();
+        let ofx: Ofx = input.parse().unwrap();
+        let stats = compute(&ofx);
+
+        let april = &stats.months["2024-04"];
+        assert_eq!(april.inflow, "3220.56".parse().unwrap());
+        assert_eq!(april.outflow, "54.05".parse().unwrap());
+        assert_eq!(april.net(), "3166.51".parse().unwrap());
+
+        assert_eq!(stats.by_type["DEBIT"].count, 3);
+        assert_eq!(stats.by_type["DEBIT"].sum, "-54.05".parse().unwrap());
+        assert_eq!(stats.by_type["PAYMENT"].count, 1);
+
+        let ids: Vec<_> = stats.largest.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids[..2], ["abcd-1234", "abcde-12345"]);
+    }
+}
@@ -0,0 +1,231 @@
+//! Incremental parsing of very large documents in bounded memory.
+//!
+//! [`Aggregates`] reads its input a tag at a time and yields each occurrence of one aggregate
+//! (e.g. every `<STMTTRN>`) as soon as it is complete, so at most one aggregate plus the reader's
+//! buffer is held in memory regardless of file size. Everything outside those aggregates is
+//! skipped. [`ParserOptions::max_aggregate_size`] bounds the aggregate and
+//! [`ParserOptions::max_value_len`] each run of text, so an unclosed aggregate fails rather than
+//! growing until the end of the input.
+
+use std::{
+    borrow::Cow,
+    fmt::Write,
+    io::{BufRead, Read},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use crate::{ParserOptions, Result, body::Transaction, error::Error};
+
+/// An iterator over the SGML of each `name` aggregate in a document.
+#[derive(Debug)]
+pub struct Aggregates<R> {
+    reader: R,
+    name: String,
+    chunk: Vec<u8>,
+    /// A started aggregate that was implicitly ended by the start of the next one
+    pending: Option<String>,
+    done: bool,
+    max_value_len: usize,
+    max_aggregate_size: usize,
+}
+
+impl<R: BufRead> Aggregates<R> {
+    #[must_use]
+    pub fn new(reader: R, name: impl Into<String>) -> Self {
+        Self::with_options(reader, name, &ParserOptions::default())
+    }
+
+    /// Like [`Aggregates::new`], applying the limits in `options`.
+    #[must_use]
+    pub fn with_options(reader: R, name: impl Into<String>, options: &ParserOptions) -> Self {
+        Self {
+            reader,
+            name: name.into(),
+            chunk: Vec::new(),
+            pending: None,
+            done: false,
+            max_value_len: options.max_value_len,
+            max_aggregate_size: options.max_aggregate_size,
+        }
+    }
+
+    /// Reads up to and including `delimiter`, returning what was read (without it).
+    fn read_until(&mut self, delimiter: u8) -> Result<Option<Cow<'_, str>>> {
+        self.chunk.clear();
+        // Read one byte past the limit, so a longer run is caught without buffering all of it
+        let limit = u64::try_from(self.max_value_len)
+            .unwrap_or(u64::MAX)
+            .saturating_add(1);
+        if (&mut self.reader)
+            .take(limit)
+            .read_until(delimiter, &mut self.chunk)?
+            == 0
+        {
+            return Ok(None);
+        }
+        if self.chunk.last() == Some(&delimiter) {
+            self.chunk.pop();
+        }
+        ParserOptions::check("value length", self.chunk.len(), self.max_value_len)?;
+        Ok(Some(String::from_utf8_lossy(&self.chunk)))
+    }
+
+    fn next_aggregate(&mut self) -> Result<Option<String>> {
+        let start = format!("<{}>", self.name);
+        let mut current = self.pending.take();
+        // The tags opened within the current aggregate, starting with the aggregate itself
+        let mut open: Vec<String> = current.iter().map(|_| self.name.clone()).collect();
+        loop {
+            if let Some(buffer) = &current {
+                ParserOptions::check("aggregate size", buffer.len(), self.max_aggregate_size)?;
+            }
+            let Some(text) = self.read_until(b'<')? else {
+                return match current {
+                    Some(_) => Err(Error::parse(format!(
+                        "unexpected end of input inside `{start}`"
+                    ))),
+                    None => Ok(None),
+                };
+            };
+            if let Some(buffer) = &mut current {
+                buffer.push_str(&text);
+            }
+            let Some(tag) = self.read_until(b'>')? else {
+                continue;
+            };
+            let tag = tag.trim().to_owned();
+            if tag.starts_with(['?', '!']) || tag.ends_with('/') {
+                continue;
+            }
+            let Some(buffer) = &mut current else {
+                if tag == self.name {
+                    current = Some(start.clone());
+                    open.push(tag);
+                }
+                continue;
+            };
+            if let Some(name) = tag.strip_prefix('/') {
+                match open.iter().rposition(|open| open == name) {
+                    Some(0) => {
+                        let _ = write!(buffer, "<{tag}>");
+                        return Ok(current);
+                    }
+                    Some(index) => open.truncate(index),
+                    // The end of an enclosing aggregate also ends this one
+                    None => {
+                        let _ = write!(buffer, "</{}>", self.name);
+                        return Ok(current);
+                    }
+                }
+            } else if tag == self.name {
+                let _ = write!(buffer, "</{}>", self.name);
+                self.pending = Some(start);
+                return Ok(current);
+            } else {
+                open.push(tag.clone());
+            }
+            let _ = write!(buffer, "<{tag}>");
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Aggregates<R> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_aggregate().transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
+    }
+}
+
+/// An iterator parsing each `name` aggregate in a document as a `T`.
+#[derive(Debug)]
+pub struct Parsed<R, T> {
+    aggregates: Aggregates<R>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: FromStr<Err = Error>> Parsed<R, T> {
+    #[must_use]
+    pub fn new(reader: R, name: impl Into<String>) -> Self {
+        Self::with_options(reader, name, &ParserOptions::default())
+    }
+
+    /// Like [`Parsed::new`], applying the limits in `options` while reading.
+    #[must_use]
+    pub fn with_options(reader: R, name: impl Into<String>, options: &ParserOptions) -> Self {
+        Self {
+            aggregates: Aggregates::with_options(reader, name, options),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<R: BufRead, T: FromStr<Err = Error>> Iterator for Parsed<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.aggregates
+            .next()
+            .map(|aggregate| aggregate.and_then(|sgml| sgml.parse()))
+    }
+}
+
+/// Streams every `<STMTTRN>` in a document, from any statement, one at a time.
+#[must_use]
+pub fn transactions<R: BufRead>(reader: R) -> Parsed<R, Transaction> {
+    Parsed::new(reader, "STMTTRN")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn streams_transactions() {
+        let input = "OFXHEADER:100\n\n<OFX><BANKTRANLIST><DTSTART>20250901\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250902<TRNAMT>-1.50<FITID>1<NAME>A &amp; B\
+            </STMTTRN>\
+            <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20250903<TRNAMT>2<FITID>2\
+            </BANKTRANLIST></OFX>";
+        // A tiny buffer shows that the input need not fit in memory at once
+        let reader = std::io::BufReader::with_capacity(8, input.as_bytes());
+        let transactions: Vec<_> = transactions(reader).collect::<Result<_>>().unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].name.as_deref(), Some("A & B"));
+        assert_eq!(transactions[1].id, "2");
+    }
+
+    #[test]
+    fn limits_what_is_buffered() {
+        let limit = |input: &str, options: &ParserOptions| {
+            let err = Aggregates::with_options(input.as_bytes(), "STMTTRN", options)
+                .find_map(Result::err)
+                .unwrap();
+            match err.kind() {
+                ErrorKind::LimitExceeded { limit, .. } => *limit,
+                kind => panic!("unexpected error: {kind}"),
+            }
+        };
+        let unclosed = format!("<OFX><STMTTRN><TRNTYPE>DEBIT{}", "<MEMO>x".repeat(1024));
+        let options = ParserOptions {
+            max_aggregate_size: 1024,
+            ..ParserOptions::default()
+        };
+        assert_eq!(limit(&unclosed, &options), "aggregate size");
+
+        let long_text = format!("<OFX>{}<STMTTRN></STMTTRN>", "x".repeat(1024));
+        let options = ParserOptions {
+            max_value_len: 1023,
+            ..ParserOptions::default()
+        };
+        assert_eq!(limit(&long_text, &options), "value length");
+    }
+}
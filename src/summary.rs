@@ -0,0 +1,137 @@
+//! A compact, human-readable overview of a document, for logs and quick CLI output.
+
+use std::fmt::{self, Write};
+
+use crate::{
+    Ofx,
+    body::{Balance, BankTransactionList},
+    datetime,
+};
+
+/// Formats an unparsed OFX datetime as `YYYY-MM-DD`, or as given if it does not parse.
+fn raw_date(s: &str) -> String {
+    datetime::parse(s).map_or_else(|_| s.to_owned(), |dt| datetime::iso_date(&dt))
+}
+
+fn write_transactions(f: &mut impl Write, list: Option<&BankTransactionList>) -> fmt::Result {
+    let Some(list) = list else {
+        return Ok(());
+    };
+    write!(f, "\n  Transactions: {}", list.transactions.len())?;
+    if let (Some(start), Some(end)) = (&list.dtstart, &list.dtend) {
+        write!(f, ", {} to {}", raw_date(start), raw_date(end))?;
+    }
+    Ok(())
+}
+
+fn write_balance(f: &mut impl Write, label: &str, balance: Option<&Balance>) -> fmt::Result {
+    if let Some(balance) = balance {
+        let date = datetime::iso_date(&balance.date);
+        write!(f, "\n  {label}: {} as of {date}", balance.amount)?;
+    }
+    Ok(())
+}
+
+impl Ofx {
+    /// The same overview as `Display`: the institution, then each account with its date range,
+    /// balances, and transaction count, then any server errors.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Ofx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let body = &self.body;
+        let institution = body
+            .sign_on
+            .as_ref()
+            .and_then(|sign_on| sign_on.response.financial_institution.as_ref());
+        match institution {
+            Some(fi) => {
+                write!(f, "{}", fi.organization)?;
+                if let Some(id) = &fi.id {
+                    write!(f, " (FID {id})")?;
+                }
+            }
+            None => f.write_str("Unknown institution")?,
+        }
+
+        if let Some(credit_card) = &body.credit_card
+            && let Some(statement) = &credit_card.transaction_response.statement
+        {
+            write!(
+                f,
+                "\nCredit card {} ({})",
+                statement.account.id, statement.currency
+            )?;
+            write_transactions(f, statement.bank_transactions.as_ref())?;
+            write_balance(f, "Ledger balance", Some(&statement.ledger_balance))?;
+            write_balance(f, "Available balance", statement.available_balance.as_ref())?;
+        }
+        if let Some(bank) = &body.bank
+            && let Some(statement) = &bank.transaction_response.statement
+        {
+            f.write_str("\nBank account")?;
+            if let Some(account) = &statement.account {
+                write!(f, " {} {}", account.account_type.code(), account.id)?;
+            }
+            write!(f, " ({})", statement.currency)?;
+            write_transactions(f, statement.bank_transactions.as_ref())?;
+            write_balance(f, "Ledger balance", statement.ledger_balance.as_ref())?;
+            write_balance(f, "Available balance", statement.available_balance.as_ref())?;
+        }
+        if let Some(investment) = &body.investment
+            && let Some(statement) = &investment.transaction_response.statement
+        {
+            let account = &statement.account;
+            write!(
+                f,
+                "\nInvestment account {} at {} ({}), as of {}",
+                account.id,
+                account.broker_id,
+                statement.currency,
+                datetime::iso_date(&statement.date_as_of)
+            )?;
+            if let Some(list) = &statement.transactions {
+                write!(
+                    f,
+                    "\n  Transactions: {}, {} to {}",
+                    list.transactions.len(),
+                    datetime::iso_date(&list.start),
+                    datetime::iso_date(&list.end)
+                )?;
+            }
+            if let Some(list) = &statement.positions {
+                write!(f, "\n  Positions: {}", list.positions.len())?;
+            }
+            if let Some(list) = &statement.open_orders {
+                write!(f, "\n  Open orders: {}", list.orders.len())?;
+            }
+        }
+        for error in self.server_errors() {
+            write!(f, "\nServer error: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_statements() {
+        let input = std::fs::read_to_string("tests/files/simple.ofx").unwrap();
+        let ofx: Ofx = input.parse().unwrap();
+        assert_eq!(
+            ofx.summary(),
+            "Apple Card (FID 23456)\n\
+             Credit card ****c123 (USD)\n  \
+             Transactions: 4, 2024-04-01 to 2024-04-30\n  \
+             Ledger balance: -2749.00 as of 2024-04-30\n  \
+             Available balance: 17251.00 as of 2024-04-30"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Generating fake statements for test fixtures.
+//!
+//! [`generate`] writes a complete OFX file from a [`Config`], so importers can be tested without
+//! copying real bank data. The same config always produces the same file, and [`Quirk`]s
+//! reproduce the mistakes real banks make.
+
+use crate::{
+    Result,
+    body::{AccountType, BankAccount, Currency, Transaction, TransactionType},
+    csv::Statement,
+    datetime::{self, date, days},
+    decimal::scaled,
+    error::Error,
+    header::{HeaderBuilder, Version},
+};
+
+const PAYEES: &[&str] = &[
+    "ACME GROCERY",
+    "CITY WATER",
+    "COFFEE HOUSE",
+    "CORNER PHARMACY",
+    "FUEL STOP",
+    "HARDWARE BARN",
+    "ONLINE BOOKS",
+    "PIZZA PLACE",
+    "STREAMING CO",
+    "TRANSIT AUTHORITY",
+];
+
+/// A mistake seen in real bank files, for testing how an importer copes with it and recording
+/// which [institutions](crate::institution) make it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Quirk {
+    /// Omits the blank line between the headers and the body.
+    MissingBlankLine,
+    /// Writes amounts with a decimal comma, e.g. `-12,50`.
+    DecimalComma,
+    /// Leaves out the end tags of elements, as most OFX 1.x servers do.
+    UnclosedTags,
+    /// Gives the last transaction the same FITID as the first.
+    DuplicateFitId,
+}
+
+/// What to generate: by default ten USD transactions in January 2025.
+#[derive(Clone, Debug)]
+pub struct Config {
+    seed: u64,
+    transactions: usize,
+    start: String,
+    end: String,
+    currencies: Vec<String>,
+    quirks: Vec<Quirk>,
+}
+
+impl Config {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            transactions: 10,
+            start: "20250101".into(),
+            end: "20250131".into(),
+            currencies: vec!["USD".into()],
+            quirks: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn transactions(mut self, count: usize) -> Self {
+        self.transactions = count;
+        self
+    }
+
+    /// Sets the statement period, as inclusive `YYYYMMDD` dates.
+    #[must_use]
+    pub fn dates(mut self, start: impl Into<String>, end: impl Into<String>) -> Self {
+        self.start = start.into();
+        self.end = end.into();
+        self
+    }
+
+    /// Sets the currencies. The first is the statement's default, and about a quarter of the
+    /// transactions are converted from one of the others.
+    #[must_use]
+    pub fn currencies(mut self, currencies: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.currencies = currencies.into_iter().map(Into::into).collect();
+        self
+    }
+
+    #[must_use]
+    pub fn quirk(mut self, quirk: Quirk) -> Self {
+        self.quirks.push(quirk);
+        self
+    }
+}
+
+/// A `SplitMix64` generator: small, fast, and stable across platforms and releases.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, or 0 if `n` is not positive.
+    fn below(&mut self, n: i64) -> i64 {
+        let n = u64::try_from(n).unwrap_or_default();
+        let value = self.next_u64().checked_rem(n).unwrap_or_default();
+        i64::try_from(value).unwrap_or_default()
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        let len = i64::try_from(items.len()).unwrap_or_default();
+        items.get(usize::try_from(self.below(len)).unwrap_or_default())
+    }
+}
+
+/// Rewrites the text of each `<TRNAMT>` with a decimal comma.
+fn decimal_commas(ofx: &str) -> String {
+    let mut out = String::with_capacity(ofx.len());
+    let mut rest = ofx;
+    while let Some(start) = rest.find("<TRNAMT>") {
+        let (before, after) = rest.split_at(start + "<TRNAMT>".len());
+        let end = after.find('<').unwrap_or(after.len());
+        out.push_str(before);
+        out.push_str(&after[..end].replace('.', ","));
+        rest = &after[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Drops the end tags of elements that hold text, leaving those of aggregates.
+fn unclose_tags(ofx: &str) -> String {
+    let mut out = String::with_capacity(ofx.len());
+    let mut rest = ofx;
+    // The name of the last start tag, while only text has followed it
+    let mut leaf: Option<&str> = None;
+    while let Some(start) = rest.find('<') {
+        let (text, after) = rest.split_at(start);
+        out.push_str(text);
+        let end = after.find('>').map_or(after.len(), |end| end + 1);
+        let tag = &after[..end];
+        rest = &after[end..];
+        let name = tag.trim_matches(['<', '>']);
+        if let Some(name) = name.strip_prefix('/') {
+            if leaf.take() == Some(name) && !text.trim().is_empty() {
+                out.push_str("\r\n");
+                continue;
+            }
+        } else {
+            leaf = Some(name);
+        }
+        out.push_str(tag);
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Generates a version 102 bank statement, with a signon, as described by `config`.
+///
+/// Transactions are sorted by date, about four in five are debits, and each FITID is the seed
+/// and index in hexadecimal. The `NEWFILEUID` is derived from the seed too, so the output is
+/// byte-for-byte reproducible.
+///
+/// # Errors
+///
+/// Returns an error if the dates are not valid `YYYYMMDD` dates or the end precedes the start.
+pub fn generate(config: &Config) -> Result<String> {
+    let (start, end) = (days(&config.start)?, days(&config.end)?);
+    if end < start {
+        return Err(Error::parse(format!(
+            "{} is before {}",
+            config.end, config.start
+        )));
+    }
+
+    let mut rng = Rng(config.seed);
+    let account = BankAccount::new(
+        format!("{:09}", rng.below(1_000_000_000)),
+        format!("{:010}", rng.below(10_000_000_000)),
+        AccountType::Checking,
+    );
+    let mut offsets: Vec<i64> = (0..config.transactions)
+        .map(|_| rng.below(end - start + 1))
+        .collect();
+    offsets.sort_unstable();
+
+    let mut balance = 0;
+    let mut transactions = Vec::with_capacity(config.transactions);
+    for (index, offset) in offsets.into_iter().enumerate() {
+        let debit = rng.below(5) != 0;
+        let cents = if debit {
+            -1 - rng.below(20_000)
+        } else {
+            1 + rng.below(300_000)
+        };
+        balance += cents;
+        let transaction_type = if debit {
+            TransactionType::Debit
+        } else {
+            TransactionType::Credit
+        };
+        let posted = date(start + offset);
+        let mut transaction = Transaction::new(
+            transaction_type,
+            datetime::parse(&posted)?,
+            scaled(cents, 2),
+            format!("{:X}-{index:X}", config.seed),
+        )
+        .with_name(rng.pick(PAYEES).copied().unwrap_or_default());
+
+        let foreign = config.currencies.get(1..).unwrap_or_default();
+        if !foreign.is_empty()
+            && rng.below(4) == 0
+            && let Some(symbol) = rng.pick(foreign)
+        {
+            let rate = scaled(5_000 + rng.below(20_000), 4);
+            transaction = transaction.with_currency(Currency::new(rate, symbol));
+        }
+        transactions.push(transaction);
+    }
+
+    if config.quirks.contains(&Quirk::DuplicateFitId)
+        && let [first, .., last] = &mut transactions[..]
+    {
+        last.id.clone_from(&first.id);
+    }
+
+    let currency = config.currencies.first().map_or("USD", String::as_str);
+    let header = HeaderBuilder::new(Version::V102)
+        .new_file_uid(format!("{:016X}", config.seed))
+        .build();
+    let mut ofx = Statement::new(account, currency)
+        .balance(scaled(balance, 2).to_string())
+        .render(&header, &config.start, &config.end, &transactions);
+
+    for quirk in &config.quirks {
+        ofx = match quirk {
+            Quirk::MissingBlankLine => ofx.replacen("\r\n<OFX>", "<OFX>", 1),
+            Quirk::DecimalComma => decimal_commas(&ofx),
+            Quirk::UnclosedTags => unclose_tags(&ofx),
+            Quirk::DuplicateFitId => ofx,
+        };
+    }
+    Ok(ofx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Ofx, ParserOptions, Warning};
+
+    #[test]
+    fn generates_reproducible_statements() {
+        let config = Config::new(42)
+            .transactions(25)
+            .dates("20250301", "20250331")
+            .currencies(["USD", "EUR", "GBP"]);
+        let ofx = generate(&config).unwrap();
+        assert_eq!(ofx, generate(&config).unwrap());
+        assert_ne!(ofx, generate(&Config::new(43)).unwrap());
+
+        let parsed: Ofx = ofx.parse().unwrap();
+        let list = parsed
+            .body
+            .bank
+            .unwrap()
+            .transaction_response
+            .statement
+            .unwrap()
+            .bank_transactions
+            .unwrap();
+        assert_eq!(list.transactions.len(), 25);
+        assert_eq!(list.dtstart.as_deref(), Some("20250301"));
+        assert!(list.transactions.iter().any(|t| t.currency.is_some()));
+
+        assert!(generate(&Config::new(1).dates("20250301", "20250201")).is_err());
+    }
+
+    #[test]
+    fn applies_quirks() {
+        let config = Config::new(7)
+            .quirk(Quirk::MissingBlankLine)
+            .quirk(Quirk::DecimalComma)
+            .quirk(Quirk::UnclosedTags)
+            .quirk(Quirk::DuplicateFitId);
+        let ofx = generate(&config).unwrap();
+        assert!(!ofx.contains("</TRNAMT>"));
+        assert!(ofx.contains("</STMTTRN>"));
+
+        let lenient = ParserOptions {
+            lenient: true,
+            ..ParserOptions::default()
+        };
+        let (parsed, warnings) = lenient.parse(&ofx).unwrap();
+        assert!(warnings.iter().any(|w| matches!(w, Warning::Fixup(_))));
+        let transactions = parsed
+            .body
+            .bank
+            .unwrap()
+            .transaction_response
+            .statement
+            .unwrap()
+            .bank_transactions
+            .unwrap()
+            .transactions;
+        assert_eq!(transactions[0].id, transactions[9].id);
+    }
+}
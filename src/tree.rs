@@ -0,0 +1,133 @@
+//! A minimal element tree built from normalized SGML events.
+
+use sgmlish::{SgmlEvent, SgmlFragment};
+
+use crate::{Result, error::Error};
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Element {
+    pub name: String,
+    pub text: Option<String>,
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// A leaf element such as `<USERID>jdoe`.
+    pub fn leaf(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            text: Some(text.into()),
+            children: Vec::new(),
+        }
+    }
+
+    /// An aggregate element containing `children`.
+    pub fn aggregate(name: impl Into<String>, children: Vec<Element>) -> Self {
+        Self {
+            name: name.into(),
+            text: None,
+            children,
+        }
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    pub fn children_named<'a, 'n>(
+        &'a self,
+        name: &'n str,
+    ) -> impl Iterator<Item = &'a Element> + use<'a, 'n> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Follows a path of child names, taking the first match at each step.
+    pub fn descendant(&self, path: &[&str]) -> Option<&Element> {
+        path.iter()
+            .try_fold(self, |element, name| element.child(name))
+    }
+
+    /// Finds the first element named `name`, depth-first, including `self`.
+    pub fn find(&self, name: &str) -> Option<&Element> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+
+    /// The text of a leaf element, or the SGML markup of an aggregate's children.
+    pub fn value(&self) -> String {
+        if self.children.is_empty() {
+            self.text.clone().unwrap_or_default()
+        } else {
+            let mut out = String::new();
+            for child in &self.children {
+                child.render(&mut out);
+            }
+            out
+        }
+    }
+
+    /// Renders this element back to SGML with explicit end tags.
+    pub fn to_sgml(&self) -> String {
+        let mut out = String::new();
+        self.render(&mut out);
+        out
+    }
+
+    fn render(&self, out: &mut String) {
+        out.push('<');
+        out.push_str(&self.name);
+        out.push('>');
+        if let Some(text) = &self.text {
+            for c in text.chars() {
+                match c {
+                    '&' => out.push_str("&amp;"),
+                    '<' => out.push_str("&lt;"),
+                    '>' => out.push_str("&gt;"),
+                    _ => out.push(c),
+                }
+            }
+        }
+        for child in &self.children {
+            child.render(out);
+        }
+        out.push_str("</");
+        out.push_str(&self.name);
+        out.push('>');
+    }
+
+    /// Builds the tree for a fragment whose end tags have already been normalized.
+    pub fn from_fragment(fragment: &SgmlFragment<'_>) -> Result<Self> {
+        let mut stack: Vec<Element> = Vec::new();
+        let mut root = None;
+        for event in fragment {
+            match event {
+                SgmlEvent::OpenStartTag { name } => stack.push(Element::new(name.to_string())),
+                SgmlEvent::Character(text) if !text.trim().is_empty() => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.get_or_insert_with(String::new).push_str(text);
+                    }
+                }
+                SgmlEvent::XmlCloseEmptyElement | SgmlEvent::EndTag { .. } => {
+                    let element = stack
+                        .pop()
+                        .ok_or_else(|| Error::parse("unbalanced end tag"))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => root = Some(element),
+                    }
+                }
+                _ => {}
+            }
+        }
+        root.ok_or_else(|| Error::parse("no root element found"))
+    }
+}
@@ -0,0 +1,470 @@
+//! Checks of parsed or built documents against constraints in the OFX 1.6 spec.
+//!
+//! Parsing is deliberately lenient, so a document that parses may still be rejected by stricter
+//! clients. [`Ofx::validate`] is meant for producers who want to check their output first, and
+//! [`Ofx::conformance_report`] groups the same checks by severity.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    Ofx,
+    body::{BankTransactionList, Transaction, TransactionType},
+    datetime,
+    header::Version,
+};
+
+/// The kind of constraint a [`Violation`] breaks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Rule {
+    /// A value is empty or longer than its `A-n` maximum
+    FieldLength,
+    /// A currency is not a three-letter ISO-4217 code (5.2)
+    CurrencyCode,
+    /// A `FITID` repeats within a transaction list (3.2.3)
+    DuplicateFitId,
+    /// A `TRNAMT` has the wrong sign for its `TRNTYPE`, e.g. a negative `CREDIT`
+    AmountSign,
+    /// A `DTPOSTED` falls outside the list's `DTSTART`..`DTEND` window
+    DateRange,
+    /// The document declares a different `VERSION` than the one checked against
+    VersionMismatch,
+}
+
+impl Rule {
+    const ALL: [Rule; 6] = [
+        Rule::DuplicateFitId,
+        Rule::AmountSign,
+        Rule::DateRange,
+        Rule::FieldLength,
+        Rule::CurrencyCode,
+        Rule::VersionMismatch,
+    ];
+
+    /// The rule's stable code, e.g. `OFXY001`. Codes are never reused or renumbered.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Rule::DuplicateFitId => "OFXY001",
+            Rule::AmountSign => "OFXY002",
+            Rule::DateRange => "OFXY003",
+            Rule::FieldLength => "OFXY004",
+            Rule::CurrencyCode => "OFXY005",
+            Rule::VersionMismatch => "OFXY006",
+        }
+    }
+
+    /// Looks a rule up by its [`Rule::code`], ignoring case.
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|rule| rule.code().eq_ignore_ascii_case(code))
+    }
+
+    /// How serious a violation of this rule is.
+    ///
+    /// Breaking an explicit spec constraint is an error; rules that catch likely bank bugs the
+    /// spec does not strictly forbid are warnings.
+    #[must_use]
+    pub fn severity(self) -> Severity {
+        match self {
+            Rule::FieldLength | Rule::CurrencyCode | Rule::DuplicateFitId => Severity::Error,
+            Rule::AmountSign | Rule::DateRange => Severity::Warning,
+            Rule::VersionMismatch => Severity::Info,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// A single spec violation found by [`Ofx::validate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation {
+    pub rule: Rule,
+    /// The section of the 1.6 spec defining the constraint, e.g. `3.2.1`
+    pub section: &'static str,
+    /// Where the offending element is, e.g. `BANKMSGSRSV1 > STMTTRNRS > TRNUID`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.path, self.message, self.section)
+    }
+}
+
+/// The findings of [`Ofx::conformance_report`], ordered by severity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConformanceReport {
+    /// The version the document was checked against
+    pub profile: Version,
+    pub findings: Vec<Violation>,
+}
+
+impl ConformanceReport {
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Violation> {
+        self.findings
+            .iter()
+            .filter(move |finding| finding.rule.severity() == severity)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Violation> {
+        self.with_severity(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Violation> {
+        self.with_severity(Severity::Warning)
+    }
+
+    /// Whether the document has no error-level findings.
+    #[must_use]
+    pub fn is_conformant(&self) -> bool {
+        self.errors().next().is_none()
+    }
+}
+
+/// Whether `transaction_type` implies money in (`Some(true)`) or out (`Some(false)`).
+///
+/// Types like `ATM` and `POS` may go either way (1.6 spec, 11.4.3.1) and return `None`. This is
+/// narrower than [`TransactionType::direction`]: `CHECK`, `PAYMENT`, and the like are often
+/// used for refunds and deposits in the wild, so they are not flagged.
+fn is_inflow(transaction_type: &TransactionType) -> Option<bool> {
+    match transaction_type {
+        TransactionType::Credit
+        | TransactionType::Interest
+        | TransactionType::Dividend
+        | TransactionType::Deposit
+        | TransactionType::DirectDeposit => Some(true),
+        TransactionType::Debit
+        | TransactionType::Fee
+        | TransactionType::ServiceCharge
+        | TransactionType::DirectDebit => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    fn push(&mut self, rule: Rule, section: &'static str, path: String, message: String) {
+        self.violations.push(Violation {
+            rule,
+            section,
+            path,
+            message,
+        });
+    }
+
+    /// Checks an `A-n` value: present and at most `max` characters.
+    fn length(&mut self, section: &'static str, path: &str, tag: &str, value: &str, max: usize) {
+        let len = value.chars().count();
+        if len == 0 || len > max {
+            self.push(
+                Rule::FieldLength,
+                section,
+                format!("{path} > {tag}"),
+                format!("must be 1 to {max} characters, found {len}"),
+            );
+        }
+    }
+
+    fn currency(&mut self, path: &str, tag: &str, value: &str) {
+        if value.len() != 3 || !value.bytes().all(|b| b.is_ascii_uppercase()) {
+            self.push(
+                Rule::CurrencyCode,
+                "5.2",
+                format!("{path} > {tag}"),
+                format!("`{value}` is not a three-letter ISO-4217 currency code"),
+            );
+        }
+    }
+
+    fn amount_sign(&mut self, path: &str, transaction: &Transaction) {
+        let Some(inflow) = is_inflow(&transaction.transaction_type) else {
+            return;
+        };
+        // Compare textually so this works with either `Decimal` backend
+        let amount = transaction.amount.to_string();
+        let is_zero = amount
+            .bytes()
+            .all(|b| matches!(b, b'-' | b'+' | b'0' | b'.'));
+        if !is_zero && amount.starts_with('-') == inflow {
+            self.push(
+                Rule::AmountSign,
+                "11.4.3.1",
+                format!("{path} > TRNAMT"),
+                format!(
+                    "{:?} transaction has {} amount {amount}",
+                    transaction.transaction_type,
+                    if inflow { "negative" } else { "positive" },
+                ),
+            );
+        }
+    }
+
+    fn date_range(&mut self, section: &'static str, path: &str, list: &BankTransactionList) {
+        // Unparseable bounds are not this rule's concern; treat them as open
+        let bound = |s: &Option<String>| s.as_deref().and_then(|s| datetime::parse(s).ok());
+        let (start, end) = (bound(&list.dtstart), bound(&list.dtend));
+        for (index, transaction) in list.transactions.iter().enumerate() {
+            let posted = &transaction.date_posted;
+            let before = start.as_ref().is_some_and(|start| posted < start);
+            let after = end.as_ref().is_some_and(|end| posted > end);
+            if before || after {
+                self.push(
+                    Rule::DateRange,
+                    section,
+                    format!("{path} > STMTTRN[{index}] > DTPOSTED"),
+                    format!(
+                        "posted {} the statement's {}",
+                        if before { "before" } else { "after" },
+                        if before { "DTSTART" } else { "DTEND" },
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Checks a `BANKTRANLIST` in the statement response defined by `section`.
+    fn transactions(&mut self, section: &'static str, path: &str, list: &BankTransactionList) {
+        let path = format!("{path} > BANKTRANLIST");
+        let mut first_seen = HashMap::new();
+        for (index, transaction) in list.transactions.iter().enumerate() {
+            let path = format!("{path} > STMTTRN[{index}]");
+            // FITID is A-255 and must be unique
+            self.length("3.2.3", &path, "FITID", &transaction.id, 255);
+            let first = *first_seen.entry(transaction.id.as_str()).or_insert(index);
+            if first != index {
+                self.push(
+                    Rule::DuplicateFitId,
+                    "3.2.3",
+                    format!("{path} > FITID"),
+                    format!("`{}` duplicates STMTTRN[{first}]", transaction.id),
+                );
+            }
+            // NAME is A-32, MEMO is A-255
+            if let Some(name) = &transaction.name {
+                self.length("11.4.3", &path, "NAME", name, 32);
+            }
+            if let Some(memo) = &transaction.memo {
+                self.length("11.4.3", &path, "MEMO", memo, 255);
+            }
+            if let Some(currency) = &transaction.currency {
+                self.currency(&path, "CURRENCY > CURSYM", &currency.symbol);
+            }
+            self.amount_sign(&path, transaction);
+        }
+        self.date_range(section, &path, list);
+    }
+}
+
+impl Ofx {
+    /// Checks the document against field length and format constraints from the spec.
+    ///
+    /// Returns every violation found; an empty list means the document passed.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut v = Validator::default();
+
+        if let Some(credit_card) = &self.body.credit_card {
+            let path = "CREDITCARDMSGSRSV1 > CCSTMTTRNRS";
+            let response = &credit_card.transaction_response;
+            // TRNUID is A-36
+            v.length("3.2.1", path, "TRNUID", &response.transaction_id, 36);
+
+            if let Some(statement) = &response.statement {
+                let path = format!("{path} > CCSTMTRS");
+                v.currency(&path, "CURDEF", &statement.currency);
+                // ACCTID is A-22
+                let account_id = statement.account.id.expose();
+                v.length("11.3.2", &path, "CCACCTFROM > ACCTID", account_id, 22);
+                if let Some(list) = &statement.bank_transactions {
+                    v.transactions("11.4.2.2", &path, list);
+                }
+            }
+        }
+
+        if let Some(bank) = &self.body.bank {
+            let path = "BANKMSGSRSV1 > STMTTRNRS";
+            let response = &bank.transaction_response;
+            v.length("3.2.1", path, "TRNUID", &response.transaction_id, 36);
+
+            if let Some(statement) = &response.statement {
+                let path = format!("{path} > STMTRS");
+                v.currency(&path, "CURDEF", &statement.currency);
+                if let Some(account) = &statement.account {
+                    // BANKID is A-9, ACCTID is A-22
+                    v.length(
+                        "11.3.1",
+                        &path,
+                        "BANKACCTFROM > BANKID",
+                        &account.bank_id,
+                        9,
+                    );
+                    v.length(
+                        "11.3.1",
+                        &path,
+                        "BANKACCTFROM > ACCTID",
+                        account.id.expose(),
+                        22,
+                    );
+                }
+                if let Some(list) = &statement.bank_transactions {
+                    v.transactions("11.4.1.2", &path, list);
+                }
+            }
+        }
+
+        v.violations
+    }
+
+    /// Runs every check in [`Ofx::validate`] against the given version, grouping the findings
+    /// by [`Severity`].
+    #[must_use]
+    pub fn conformance_report(&self, profile: Version) -> ConformanceReport {
+        let mut findings = self.validate();
+        if self.header.version != profile {
+            findings.push(Violation {
+                rule: Rule::VersionMismatch,
+                section: "2.2.3",
+                path: "VERSION".into(),
+                message: format!(
+                    "document declares {:?} but was checked against {profile:?}",
+                    self.header.version
+                ),
+            });
+        }
+        findings.sort_by_key(|finding| finding.rule.severity());
+        ConformanceReport { profile, findings }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_violations() {
+        let ofx = crate::document(
+            "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>usd<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1\
+            <NAME>A name that is much too long for the spec</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>",
+        );
+        let violations = ofx.validate();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].rule, Rule::CurrencyCode);
+        assert_eq!(
+            violations[0].path,
+            "BANKMSGSRSV1 > STMTTRNRS > STMTRS > CURDEF"
+        );
+        assert_eq!(violations[1].rule, Rule::FieldLength);
+        assert_eq!(
+            violations[1].path,
+            "BANKMSGSRSV1 > STMTTRNRS > STMTRS > BANKTRANLIST > STMTTRN[0] > NAME"
+        );
+    }
+
+    #[test]
+    fn reports_duplicate_fitids() {
+        let mut ofx = crate::document(
+            "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>USD<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1</STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250902<TRNAMT>-6<FITID>2</STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>",
+        );
+        let violations = ofx.validate();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::DuplicateFitId);
+        assert_eq!(violations[0].message, "`1` duplicates STMTTRN[0]");
+
+        let statement = ofx
+            .body
+            .bank
+            .as_mut()
+            .unwrap()
+            .transaction_response
+            .statement
+            .as_mut()
+            .unwrap();
+        let list = statement.bank_transactions.as_mut().unwrap();
+        assert_eq!(list.dedup_fitids(), vec![2]);
+        assert_eq!(list.transactions.len(), 2);
+        assert!(ofx.validate().is_empty());
+    }
+
+    #[test]
+    fn reports_amount_sign_mismatches() {
+        let ofx = crate::document(
+            "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>USD<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1</STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250902<TRNAMT>6<FITID>2</STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250902<TRNAMT>-6<FITID>3</STMTTRN>\
+            <STMTTRN><TRNTYPE>POS<DTPOSTED>20250903<TRNAMT>7<FITID>4</STMTTRN>\
+            <STMTTRN><TRNTYPE>FEE<DTPOSTED>20250903<TRNAMT>0.00<FITID>5</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>",
+        );
+        let violations = ofx.validate();
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.rule == Rule::AmountSign));
+        assert_eq!(
+            violations[1].path,
+            "BANKMSGSRSV1 > STMTTRNRS > STMTRS > BANKTRANLIST > STMTTRN[1] > TRNAMT"
+        );
+    }
+
+    #[test]
+    fn reports_transactions_outside_statement_window() {
+        let ofx = crate::document(
+            "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>USD<BANKTRANLIST><DTSTART>20250901<DTEND>20250930\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250828<TRNAMT>-5<FITID>1</STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250915<TRNAMT>-6<FITID>2</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>",
+        );
+        let violations = ofx.validate();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::DateRange);
+        assert_eq!(
+            violations[0].path,
+            "BANKMSGSRSV1 > STMTTRNRS > STMTRS > BANKTRANLIST > STMTTRN[0] > DTPOSTED"
+        );
+    }
+
+    #[test]
+    fn groups_findings_by_severity() {
+        let ofx = crate::document(
+            "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>1\
+            <STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+            <STMTRS><CURDEF>usd<BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20250901<TRNAMT>-5<FITID>1</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>",
+        );
+        let report = ofx.conformance_report(Version::V160);
+        assert!(!report.is_conformant());
+        let severities: Vec<_> = report.findings.iter().map(|f| f.rule.severity()).collect();
+        assert_eq!(
+            severities,
+            [Severity::Error, Severity::Warning, Severity::Info]
+        );
+        assert_eq!(report.errors().next().unwrap().section, "5.2");
+    }
+}
@@ -0,0 +1,152 @@
+//! Valuing investment positions and accounts.
+//!
+//! Brokers report each position's market value (`MKTVAL`) alongside its units and unit price.
+//! [`PositionList::valuations`] recomputes each value so stale or rounded figures can be spotted,
+//! and [`InvestmentStatementResponse::total_value`] adds up the account the way the broker does.
+
+use crate::{
+    Decimal,
+    body::investment::{
+        InvestmentStatementResponse, Position, PositionList, Security, SecurityId, SecurityList,
+    },
+};
+
+/// The shares per option contract assumed when the security list does not give `SHPERCTRCT`.
+pub const SHARES_PER_CONTRACT: u32 = 100;
+
+/// The value of one position, in the statement's currency.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Valuation<'a> {
+    pub security: &'a SecurityId,
+    /// Units times unit price, unrounded.
+    pub value: Decimal,
+    /// The market value the broker reported.
+    pub market_value: Decimal,
+}
+
+impl Position {
+    /// Units times unit price, in the position's currency.
+    ///
+    /// Bonds are priced as a percentage of par and options per share of the underlying, while
+    /// their units are face value and contracts, so a bond's value is divided by 100 and an
+    /// option's multiplied by `shares_per_contract`.
+    #[must_use]
+    pub fn value(&self, shares_per_contract: u32) -> Decimal {
+        let info = self.info();
+        let value = info.units * info.unit_price;
+        match self {
+            Self::Debt(_) => value / Decimal::ONE_HUNDRED,
+            Self::Option(_) => value * Decimal::from(shares_per_contract),
+            _ => value,
+        }
+    }
+
+    /// The rate converting the position's currency to the statement's.
+    fn rate(&self) -> Decimal {
+        self.info()
+            .currency
+            .as_ref()
+            .map_or(Decimal::ONE, |currency| currency.rate)
+    }
+}
+
+impl PositionList {
+    /// The sum of the positions' reported market values, in the statement's currency.
+    #[must_use]
+    pub fn market_value(&self) -> Decimal {
+        self.positions
+            .iter()
+            .map(|position| position.info().market_value * position.rate())
+            .sum()
+    }
+
+    /// Values each position, looking up the shares per contract of options in `securities`.
+    #[must_use]
+    pub fn valuations(&self, securities: Option<&SecurityList>) -> Vec<Valuation<'_>> {
+        self.positions
+            .iter()
+            .map(|position| {
+                let info = position.info();
+                let shares_per_contract = securities
+                    .into_iter()
+                    .flat_map(|list| &list.securities)
+                    .find_map(|security| match security {
+                        Security::Option(option) if option.info.id == info.security => {
+                            Some(option.shares_per_contract)
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or(SHARES_PER_CONTRACT);
+                let rate = position.rate();
+                Valuation {
+                    security: &info.security,
+                    value: position.value(shares_per_contract) * rate,
+                    market_value: info.market_value * rate,
+                }
+            })
+            .collect()
+    }
+}
+
+impl InvestmentStatementResponse {
+    /// The market value of the positions plus the available cash, which is how brokers total an
+    /// account.
+    ///
+    /// Some brokers hold cash in a money market fund and report it both as a position and as
+    /// `AVAILCASH`; compare with the `BALLIST` totals in [`InvestmentStatementResponse::balance`]
+    /// when in doubt.
+    #[must_use]
+    pub fn total_value(&self) -> Decimal {
+        let positions = self
+            .positions
+            .as_ref()
+            .map(PositionList::market_value)
+            .unwrap_or_default();
+        let cash = self
+            .balance
+            .as_ref()
+            .map(|balance| balance.available_cash)
+            .unwrap_or_default();
+        positions + cash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Ofx;
+
+    #[test]
+    fn values_positions() {
+        let positions: PositionList = "<INVPOSLIST><POSSTOCK><INVPOS>\
+            <SECID><UNIQUEID>458140100<UNIQUEIDTYPE>CUSIP</SECID><HELDINACCT>CASH\
+            <POSTYPE>LONG<UNITS>100<UNITPRICE>24.5<MKTVAL>2450<DTPRICEASOF>20250930\
+            </INVPOS></POSSTOCK><POSOPT><INVPOS>\
+            <SECID><UNIQUEID>INTC251017C00025000<UNIQUEIDTYPE>TICKER</SECID>\
+            <HELDINACCT>MARGIN<POSTYPE>LONG<UNITS>1<UNITPRICE>5<MKTVAL>500\
+            <DTPRICEASOF>20250930</INVPOS></POSOPT></INVPOSLIST>"
+            .parse()
+            .unwrap();
+        let valuations = positions.valuations(None);
+        assert_eq!(valuations.len(), 2);
+        for valuation in &valuations {
+            assert_eq!(valuation.value, valuation.market_value);
+        }
+        assert_eq!(valuations[1].value, Decimal::from(500));
+        assert_eq!(positions.market_value(), Decimal::from(2950));
+
+        let ofx = Ofx::from_file("tests/files/ofxparse/fidelity.ofx").unwrap();
+        let statement = ofx
+            .body
+            .investment
+            .unwrap()
+            .transaction_response
+            .statement
+            .unwrap();
+        let positions = statement.positions.as_ref().unwrap();
+        assert_eq!(positions.market_value(), "14919.80".parse().unwrap());
+        // Fidelity's own `Networth` balance is a cent higher
+        assert_eq!(statement.total_value(), "32993.78".parse().unwrap());
+    }
+}
@@ -0,0 +1,33 @@
+//! Non-fatal issues encountered while parsing.
+
+use std::fmt;
+
+/// Something in the input that deviated from the spec but was accepted anyway.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// A header was missing, so its default value was used
+    DefaultedHeader(String),
+    /// Leading or trailing whitespace was trimmed from the described value
+    WhitespaceTrimmed(String),
+    /// A lenient fixup was applied to the input
+    Fixup(String),
+    /// The content looks like a different encoding than the one the document declares
+    EncodingMismatch { declared: String, detected: String },
+    /// A tag was skipped because it is not modeled; `path` names the aggregate holding it
+    UnknownTag { name: String, path: String },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::DefaultedHeader(name) => write!(f, "missing header {name}; using default"),
+            Warning::WhitespaceTrimmed(what) => write!(f, "trimmed whitespace from {what}"),
+            Warning::Fixup(what) => write!(f, "applied fixup: {what}"),
+            Warning::EncodingMismatch { declared, detected } => {
+                write!(f, "declared as {declared} but looks like {detected}")
+            }
+            Warning::UnknownTag { name, path } => write!(f, "skipped unknown tag {name} in {path}"),
+        }
+    }
+}
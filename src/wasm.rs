@@ -0,0 +1,18 @@
+//! `wasm-bindgen` bindings for parsing OFX from JavaScript.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Ofx;
+
+/// Parses OFX text and returns the serialized model as a JavaScript object.
+///
+/// # Errors
+///
+/// Throws a JavaScript `Error` if the input cannot be parsed or serialized.
+#[wasm_bindgen]
+pub fn parse(input: &str) -> std::result::Result<JsValue, JsError> {
+    let ofx: Ofx = input
+        .parse()
+        .map_err(|err: crate::error::Error| JsError::new(&err.to_string()))?;
+    serde_wasm_bindgen::to_value(&ofx).map_err(|err| JsError::new(&err.to_string()))
+}
@@ -2,17 +2,21 @@ use std::fs::read_dir;
 use std::{ffi::OsStr, path::Path, result};
 use std::{path::PathBuf, str::FromStr};
 
+#[cfg(feature = "chrono")]
 use chrono::{TimeZone, Utc};
-use rust_decimal::Decimal;
+#[cfg(feature = "chrono")]
+use ofxy::body::{Currency, TransactionType};
 
 use ofxy::{
-    Ofx,
-    body::{Currency, TransactionType},
-    header::{Encoding, Version},
+    Decimal, Ofx, Warning,
+    body::investment::{IncomeType, InvestmentTransaction, Position},
+    error::ErrorKind,
+    header::{Compression, Encoding, Security, Version},
 };
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+#[cfg(feature = "chrono")]
 #[test]
 fn test_ofx_sgmlish() -> result::Result<(), Box<dyn std::error::Error>> {
     let input = std::fs::read_to_string("tests/files/sgmlish/example.ofx")
@@ -25,6 +29,7 @@ fn test_ofx_sgmlish() -> result::Result<(), Box<dyn std::error::Error>> {
         .expect("missing credit card statement")
         .transaction_response
         .statement
+        .expect("missing statement")
         .bank_transactions
         .expect("missing transaction list");
 
@@ -56,10 +61,7 @@ fn test_ofx_sgmlish() -> result::Result<(), Box<dyn std::error::Error>> {
     assert_eq!(trn.memo.as_deref(), Some("Example International Payment "));
     assert_eq!(
         trn.currency,
-        Some(Currency {
-            rate: Decimal::from_str("1.1153").unwrap(),
-            symbol: "EUR".to_owned(),
-        }),
+        Some(Currency::new(Decimal::from_str("1.1153").unwrap(), "EUR")),
     );
 
     let trn = &transaction_list.transactions[2];
@@ -85,7 +87,7 @@ fn test_ofx_simple() -> Result<()> {
     assert_eq!(ofx.header.ofxheader, 100);
     assert_eq!(ofx.header.version, Version::V102);
     assert_eq!(ofx.header.encoding, Encoding::UsAscii);
-    assert_eq!(ofx.header.compression, "NONE");
+    assert_eq!(ofx.header.compression, Compression::None);
 
     assert_eq!(
         ofx.body.sign_on.expect("no sign on").response.status.code,
@@ -97,10 +99,10 @@ fn test_ofx_simple() -> Result<()> {
         .credit_card
         .expect("missing credit card statement")
         .transaction_response;
-    assert_eq!(&transaction_response.statement.account.id, "abc123");
+    let statement = transaction_response.statement.expect("missing statement");
+    assert_eq!(statement.account.id.expose(), "abc123");
 
-    let transactions = transaction_response
-        .statement
+    let transactions = statement
         .bank_transactions
         .expect("missing bank transactions")
         .transactions;
@@ -155,7 +157,7 @@ fn parses_files() -> result::Result<(), Box<dyn std::error::Error>> {
                 // Bad datetimes
                 //   has `00000000000000` as a date
                 "tests/files/ofx-reader/Bradesco.ofx",
-                //   bad timezone: `[-:EST]`
+                //   bad timezone: `[-:EST]`, only accepted in lenient mode
                 "tests/files/ofxparse/investment_medium.ofx",
                 // Non-standard structure with duplicate STMTRS elements in single STMTTRNRS
                 "tests/files/ofxparser/ofxdata-bb-two-stmtrs.ofx",
@@ -191,13 +193,13 @@ fn test_citi() {
         .expect("missing bank statement")
         .transaction_response;
 
+    let statement = transaction_response.statement.expect("missing statement");
     assert_eq!(
-        transaction_response.statement.account.as_ref().unwrap().id,
+        statement.account.as_ref().unwrap().id.expose(),
         "XXXXXXXXXXXX1234"
     );
 
-    let transactions = transaction_response
-        .statement
+    let transactions = statement
         .bank_transactions
         .expect("missing bank transactions")
         .transactions;
@@ -207,3 +209,202 @@ fn test_citi() {
         Decimal::from_str("-127.15").unwrap()
     );
 }
+
+#[test]
+fn test_fidelity_investments() -> Result<()> {
+    let input = std::fs::read_to_string("tests/files/ofxparse/fidelity.ofx")?;
+    let ofx: Ofx = input.parse()?;
+    let statement = ofx
+        .body
+        .investment
+        .expect("missing investment statement")
+        .transaction_response
+        .statement
+        .expect("missing statement");
+    assert_eq!(statement.account.broker_id, "fidelity.com");
+    let positions = statement.positions.expect("missing positions").positions;
+    assert_eq!(positions.len(), 6);
+    assert!(
+        positions
+            .iter()
+            .all(|position| matches!(position, Position::Stock(_)))
+    );
+
+    let incomes: Vec<_> = statement
+        .transactions
+        .expect("missing investment transactions")
+        .transactions
+        .into_iter()
+        .filter_map(|transaction| match transaction {
+            InvestmentTransaction::Income(income) => Some(income),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(incomes.len(), 4);
+    assert_eq!(incomes[0].income_type, IncomeType::Dividend);
+    assert_eq!(incomes[0].total, Decimal::from_str("5.53")?);
+    Ok(())
+}
+
+#[test]
+fn test_parse_with_warnings() -> Result<()> {
+    let input = std::fs::read_to_string("tests/files/simple.ofx")?;
+    let (_, warnings) = Ofx::parse_with_warnings(&input)?;
+    assert!(warnings.is_empty());
+
+    let input = input
+        .replace("DATA:OFXSGML\n", "")
+        .replace("NEWFILEUID:NONE\n\n", "NEWFILEUID:NONE\n")
+        .replace("CREDITCARDMSGSRSV1>", "CREDITCARDMSGSRSV2>");
+    let (ofx, warnings) = Ofx::parse_with_warnings(&input)?;
+    assert!(ofx.body.credit_card.is_some());
+    assert_eq!(
+        warnings,
+        [
+            Warning::Fixup("no blank line between headers and body".into()),
+            Warning::DefaultedHeader("DATA".into()),
+            Warning::Fixup("treated CREDITCARDMSGSRSV2 as V1".into()),
+        ]
+    );
+    Ok(())
+}
+
+/// Tiny xorshift generator, so the corruption below is reproducible without extra dependencies.
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn never_panics_on_corrupted_input() -> Result<()> {
+    let bytes = std::fs::read("tests/files/simple.ofx")?;
+    let text = String::from_utf8_lossy(&bytes);
+    for (end, _) in text.char_indices() {
+        let _ = text[..end].parse::<Ofx>();
+    }
+
+    let replacements = b"<>/&;[]:.-+0 \n\xff";
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..2000 {
+        let mut corrupted = bytes.clone();
+        for _ in 0..=xorshift(&mut state) % 4 {
+            let len = u64::try_from(corrupted.len())?;
+            let at = usize::try_from(xorshift(&mut state) % len)?;
+            let replacement = xorshift(&mut state) % u64::try_from(replacements.len())?;
+            corrupted[at] = replacements[usize::try_from(replacement)?];
+        }
+        let _ = String::from_utf8_lossy(&corrupted).parse::<Ofx>();
+    }
+
+    // Including the files `parses_files` skips for being invalid
+    for dir in ["sgmlish", "ofx-reader", "ofxparse", "ofxparser"] {
+        for entry in ofx_files_from_dir("tests/files/".to_owned() + dir)? {
+            let _ = String::from_utf8_lossy(&std::fs::read(entry)?).parse::<Ofx>();
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn parses_garbled_offsets_leniently() -> Result<()> {
+    let input = std::fs::read_to_string("tests/files/ofxparse/investment_medium.ofx")?;
+    assert!(input.parse::<Ofx>().is_err());
+
+    let mut options = ofxy::ParserOptions::default();
+    options.lenient = true;
+    let (_, warnings) = options.parse(&input)?;
+    assert!(
+        warnings
+            .iter()
+            .any(|warning| matches!(warning, Warning::Fixup(fixup) if fixup.contains("[-:EST]")))
+    );
+    Ok(())
+}
+
+#[test]
+fn reports_encrypted_type1_bodies() -> Result<()> {
+    let input = std::fs::read_to_string("tests/files/simple.ofx")?
+        .replace("SECURITY:NONE", "SECURITY:TYPE1");
+    let ofx: Ofx = input.parse()?;
+    assert_eq!(ofx.header.security, Security::Type1);
+
+    let start = input.find("<OFX>").expect("missing body");
+    let encrypted = format!("{}\u{1}\u{7f}x9Qz+/=", &input[..start]);
+    let err = encrypted.parse::<Ofx>().unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::EncryptedBody);
+
+    // Old Mac line endings and the spacing and case the lenient header parser accepts
+    let cr_only = encrypted
+        .lines()
+        .collect::<Vec<_>>()
+        .join("\r")
+        .replace("SECURITY:TYPE1", "security: type1");
+    let err = cr_only.parse::<Ofx>().unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::EncryptedBody);
+    Ok(())
+}
+
+#[test]
+fn round_trips_through_json() -> Result<()> {
+    for path in [
+        "tests/files/simple.ofx",
+        "tests/files/ofxparse/fidelity.ofx",
+    ] {
+        let ofx: Ofx = std::fs::read_to_string(path)?.parse()?;
+        let json = serde_json::to_value(&ofx)?;
+        assert_eq!(serde_json::from_value::<Ofx>(json)?, ofx, "{path}");
+    }
+    Ok(())
+}
+
+#[test]
+fn reads_files() -> Result<()> {
+    let ofx = Ofx::from_file("tests/files/simple.ofx")?;
+    assert!(ofx.body.credit_card.is_some());
+
+    let err = Ofx::from_file("tests/files/does-not-exist.ofx").unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Io);
+    Ok(())
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn reads_gzipped_files() -> Result<()> {
+    use std::io::Write;
+
+    let bytes = std::fs::read("tests/files/simple.ofx")?;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&bytes)?;
+    let (ofx, _) = Ofx::parse_bytes(&encoder.finish()?)?;
+    assert_eq!(ofx, Ofx::parse_bytes(&bytes)?.0);
+    Ok(())
+}
+
+#[test]
+fn queries_tag_paths() -> Result<()> {
+    let ofx = Ofx::from_file("tests/files/simple.ofx")?;
+    let statement = "CREDITCARDMSGSRSV1/CCSTMTTRNRS/CCSTMTRS";
+    assert_eq!(
+        ofx.get(&format!("OFX/{statement}/LEDGERBAL/BALAMT")),
+        Some("-2749.00")
+    );
+    assert_eq!(ofx.get("SIGNONMSGSRSV1/SONRS/FI/ORG"), Some("Apple Card"));
+    assert_eq!(
+        ofx.get(&format!("{statement}/BANKTRANLIST/STMTTRN[1]/TRNAMT")),
+        Some("-3.00")
+    );
+    assert_eq!(
+        ofx.get_all(&format!("{statement}/BANKTRANLIST/STMTTRN/FITID"))
+            .len(),
+        4
+    );
+    assert_eq!(ofx.get(&format!("{statement}/LEDGERBAL")), None);
+    assert_eq!(ofx.get("BANKMSGSRSV1/STMTTRNRS"), None);
+
+    let built = Ofx::new(ofx.header.clone(), ofx.body.clone());
+    assert_eq!(built, ofx);
+    assert_eq!(built.get("SIGNONMSGSRSV1/SONRS/FI/ORG"), None);
+    Ok(())
+}